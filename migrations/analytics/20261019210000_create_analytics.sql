@@ -0,0 +1,20 @@
+-- Arama kayıtları, kullanıcıyı tanımlayabilecek IP adresi veya tarayıcı bilgisi tutulmuyor
+CREATE TABLE IF NOT EXISTS search_events (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    query           TEXT, -- Sadece filtreyle yapılan aramalarda boş
+    mode            TEXT NOT NULL,
+    result_count    INTEGER NOT NULL,
+    latency_ms      REAL NOT NULL,
+    created_at      INTEGER NOT NULL -- Unix zamanı, saniye
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_events_created ON search_events (created_at);
+
+-- /food/{slug} görüntülenmeleri, en çok ilgi gören yemekleri bulmak için
+CREATE TABLE IF NOT EXISTS food_views (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    slug            TEXT NOT NULL,
+    created_at      INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_food_views_created ON food_views (created_at);
\ No newline at end of file
@@ -0,0 +1,8 @@
+-- Arama sonuçlarından tıklanan yemekler, istemcinin gönderdiği beacon ile kaydediliyor
+CREATE TABLE IF NOT EXISTS search_clicks (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    slug            TEXT NOT NULL,
+    created_at      INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_clicks_created ON search_clicks (created_at);
\ No newline at end of file
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS recipes (
+    food_id         INTEGER PRIMARY KEY NOT NULL,
+    yield_factor    REAL NOT NULL
+)
\ No newline at end of file
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS recipe_ingredients (
+    recipe_id       INTEGER NOT NULL,
+    ingredient_id   INTEGER NOT NULL,
+    position        INTEGER NOT NULL,
+    weight          REAL NOT NULL,
+    PRIMARY KEY (recipe_id, ingredient_id)
+)
\ No newline at end of file
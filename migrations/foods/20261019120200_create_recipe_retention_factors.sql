@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS recipe_retention_factors (
+    recipe_id       INTEGER NOT NULL,
+    nutrient        TEXT NOT NULL,
+    factor          REAL NOT NULL,
+    PRIMARY KEY (recipe_id, nutrient)
+)
\ No newline at end of file
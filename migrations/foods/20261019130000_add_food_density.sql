@@ -0,0 +1 @@
+ALTER TABLE foods ADD COLUMN density REAL
\ No newline at end of file
@@ -0,0 +1,44 @@
+-- SQLite sütunlardaki NOT NULL kısıtını kaldırmaya izin vermediği için tabloyu yeniden oluşturuyoruz
+-- Besin değerlerinde NULL "bilinmiyor", 0 ise "ölçülmüş ve yok" anlamına geliyor
+CREATE TABLE foods_new (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    slug            TEXT NOT NULL UNIQUE,
+    description     TEXT NOT NULL UNIQUE,
+    verified        INTEGER DEFAULT 0,
+    created_at      INTEGER DEFAULT (strftime('%s', 'now')),
+    updated_at      INTEGER DEFAULT 0,
+    image_id        INTEGER NOT NULL,
+    source_id       INTEGER NOT NULL,
+    glycemic_index  REAL NOT NULL,
+    energy          REAL,
+    carbohydrate    REAL,
+    protein         REAL,
+    fat             REAL,
+    saturated_fat   REAL,
+    trans_fat       REAL,
+    sugar           REAL,
+    fiber           REAL,
+    cholesterol     REAL,
+    sodium          REAL,
+    potassium       REAL,
+    water           REAL,
+    iron            REAL,
+    magnesium       REAL,
+    calcium         REAL,
+    zinc            REAL,
+    vitamin_a       REAL,
+    vitamin_b6      REAL,
+    vitamin_b12     REAL,
+    vitamin_c       REAL,
+    vitamin_d       REAL,
+    vitamin_e       REAL,
+    vitamin_k       REAL,
+    density         REAL
+);
+
+INSERT INTO foods_new (id, slug, description, verified, created_at, updated_at, image_id, source_id, glycemic_index, energy, carbohydrate, protein, fat, saturated_fat, trans_fat, sugar, fiber, cholesterol, sodium, potassium, water, iron, magnesium, calcium, zinc, vitamin_a, vitamin_b6, vitamin_b12, vitamin_c, vitamin_d, vitamin_e, vitamin_k, density)
+SELECT id, slug, description, verified, created_at, updated_at, image_id, source_id, glycemic_index, energy, carbohydrate, protein, fat, saturated_fat, trans_fat, sugar, fiber, cholesterol, sodium, potassium, water, iron, magnesium, calcium, zinc, vitamin_a, vitamin_b6, vitamin_b12, vitamin_c, vitamin_d, vitamin_e, vitamin_k, density FROM foods;
+
+DROP TABLE foods;
+
+ALTER TABLE foods_new RENAME TO foods
\ No newline at end of file
@@ -0,0 +1,44 @@
+-- Besin tanımları, yeni bir besin eklemek için sadece bu tabloya bir satır eklemek yeterli
+CREATE TABLE IF NOT EXISTS nutrients (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name            TEXT NOT NULL UNIQUE,
+    description     TEXT NOT NULL,
+    unit            TEXT NOT NULL,
+    position        INTEGER NOT NULL
+);
+
+INSERT INTO nutrients (name, description, unit, position) VALUES
+    ('energy', 'Enerji', 'kcal', 1),
+    ('carbohydrate', 'Karbonhidrat', 'g', 2),
+    ('protein', 'Protein', 'g', 3),
+    ('fat', 'Yağ', 'g', 4),
+    ('saturated_fat', 'Doymuş Yağ', 'g', 5),
+    ('trans_fat', 'Trans Yağ', 'g', 6),
+    ('sugar', 'Şeker', 'g', 7),
+    ('fiber', 'Lif', 'g', 8),
+    ('cholesterol', 'Kolesterol', 'mg', 9),
+    ('sodium', 'Sodyum', 'mg', 10),
+    ('potassium', 'Potasyum', 'mg', 11),
+    ('water', 'Su', 'g', 12),
+    ('iron', 'Demir', 'mg', 13),
+    ('magnesium', 'Magnezyum', 'mg', 14),
+    ('calcium', 'Kalsiyum', 'mg', 15),
+    ('zinc', 'Çinko', 'mg', 16),
+    ('vitamin_a', 'A Vitamini', 'µg', 17),
+    ('vitamin_b6', 'B6 Vitamini', 'mg', 18),
+    ('vitamin_b12', 'B12 Vitamini', 'µg', 19),
+    ('vitamin_c', 'C Vitamini', 'mg', 20),
+    ('vitamin_d', 'D Vitamini', 'µg', 21),
+    ('vitamin_e', 'E Vitamini', 'mg', 22),
+    ('vitamin_k', 'K Vitamini', 'µg', 23),
+    ('folate', 'Folat', 'µg', 24),
+    ('thiamin', 'Tiamin (B1)', 'mg', 25),
+    ('riboflavin', 'Riboflavin (B2)', 'mg', 26),
+    ('niacin', 'Niasin (B3)', 'mg', 27),
+    ('phosphorus', 'Fosfor', 'mg', 28),
+    ('selenium', 'Selenyum', 'µg', 29),
+    ('omega_3', 'Omega-3', 'g', 30),
+    ('monounsaturated_fat', 'Tekli Doymamış Yağ', 'g', 31),
+    ('polyunsaturated_fat', 'Çoklu Doymamış Yağ', 'g', 32),
+    ('added_sugar', 'İlave Şeker', 'g', 33),
+    ('caffeine', 'Kafein', 'mg', 34)
\ No newline at end of file
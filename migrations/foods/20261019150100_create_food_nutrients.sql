@@ -0,0 +1,57 @@
+-- Her yemeğin sadece bilinen besin değerleri tutuluyor, satırın olmaması değerin bilinmediği anlamına geliyor
+CREATE TABLE IF NOT EXISTS food_nutrients (
+    food_id         INTEGER NOT NULL,
+    nutrient_id     INTEGER NOT NULL,
+    value           REAL NOT NULL,
+    PRIMARY KEY (food_id, nutrient_id)
+);
+
+INSERT INTO food_nutrients (food_id, nutrient_id, value)
+SELECT V.food_id, N.id, V.value
+FROM (
+SELECT id AS food_id, 'energy' AS name, energy AS value FROM foods WHERE energy IS NOT NULL
+UNION ALL SELECT id, 'carbohydrate', carbohydrate FROM foods WHERE carbohydrate IS NOT NULL
+UNION ALL SELECT id, 'protein', protein FROM foods WHERE protein IS NOT NULL
+UNION ALL SELECT id, 'fat', fat FROM foods WHERE fat IS NOT NULL
+UNION ALL SELECT id, 'saturated_fat', saturated_fat FROM foods WHERE saturated_fat IS NOT NULL
+UNION ALL SELECT id, 'trans_fat', trans_fat FROM foods WHERE trans_fat IS NOT NULL
+UNION ALL SELECT id, 'sugar', sugar FROM foods WHERE sugar IS NOT NULL
+UNION ALL SELECT id, 'fiber', fiber FROM foods WHERE fiber IS NOT NULL
+UNION ALL SELECT id, 'cholesterol', cholesterol FROM foods WHERE cholesterol IS NOT NULL
+UNION ALL SELECT id, 'sodium', sodium FROM foods WHERE sodium IS NOT NULL
+UNION ALL SELECT id, 'potassium', potassium FROM foods WHERE potassium IS NOT NULL
+UNION ALL SELECT id, 'water', water FROM foods WHERE water IS NOT NULL
+UNION ALL SELECT id, 'iron', iron FROM foods WHERE iron IS NOT NULL
+UNION ALL SELECT id, 'magnesium', magnesium FROM foods WHERE magnesium IS NOT NULL
+UNION ALL SELECT id, 'calcium', calcium FROM foods WHERE calcium IS NOT NULL
+UNION ALL SELECT id, 'zinc', zinc FROM foods WHERE zinc IS NOT NULL
+UNION ALL SELECT id, 'vitamin_a', vitamin_a FROM foods WHERE vitamin_a IS NOT NULL
+UNION ALL SELECT id, 'vitamin_b6', vitamin_b6 FROM foods WHERE vitamin_b6 IS NOT NULL
+UNION ALL SELECT id, 'vitamin_b12', vitamin_b12 FROM foods WHERE vitamin_b12 IS NOT NULL
+UNION ALL SELECT id, 'vitamin_c', vitamin_c FROM foods WHERE vitamin_c IS NOT NULL
+UNION ALL SELECT id, 'vitamin_d', vitamin_d FROM foods WHERE vitamin_d IS NOT NULL
+UNION ALL SELECT id, 'vitamin_e', vitamin_e FROM foods WHERE vitamin_e IS NOT NULL
+UNION ALL SELECT id, 'vitamin_k', vitamin_k FROM foods WHERE vitamin_k IS NOT NULL
+) V
+INNER JOIN nutrients N ON N.name = V.name;
+
+-- Besin sütunları artık food_nutrients tablosunda olduğu için foods tablosunu onlarsız yeniden oluşturuyoruz
+CREATE TABLE foods_new (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    slug            TEXT NOT NULL UNIQUE,
+    description     TEXT NOT NULL UNIQUE,
+    verified        INTEGER DEFAULT 0,
+    created_at      INTEGER DEFAULT (strftime('%s', 'now')),
+    updated_at      INTEGER DEFAULT 0,
+    image_id        INTEGER NOT NULL,
+    source_id       INTEGER NOT NULL,
+    glycemic_index  REAL NOT NULL,
+    density         REAL
+);
+
+INSERT INTO foods_new (id, slug, description, verified, created_at, updated_at, image_id, source_id, glycemic_index, density)
+SELECT id, slug, description, verified, created_at, updated_at, image_id, source_id, glycemic_index, density FROM foods;
+
+DROP TABLE foods;
+
+ALTER TABLE foods_new RENAME TO foods
\ No newline at end of file
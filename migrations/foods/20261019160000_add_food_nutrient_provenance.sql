@@ -0,0 +1,6 @@
+ALTER TABLE food_nutrients ADD COLUMN method TEXT;
+ALTER TABLE food_nutrients ADD COLUMN source_id INTEGER;
+ALTER TABLE food_nutrients ADD COLUMN confidence TEXT;
+ALTER TABLE food_nutrients ADD COLUMN min_value REAL;
+ALTER TABLE food_nutrients ADD COLUMN max_value REAL;
+ALTER TABLE food_nutrients ADD COLUMN std_dev REAL
\ No newline at end of file
@@ -0,0 +1,6 @@
+ALTER TABLE food_sources ADD COLUMN title TEXT;
+ALTER TABLE food_sources ADD COLUMN organization TEXT;
+ALTER TABLE food_sources ADD COLUMN url TEXT;
+ALTER TABLE food_sources ADD COLUMN year INTEGER;
+ALTER TABLE food_sources ADD COLUMN license TEXT;
+ALTER TABLE food_sources ADD COLUMN retrieved_at TEXT
\ No newline at end of file
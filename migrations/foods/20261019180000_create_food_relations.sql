@@ -0,0 +1,11 @@
+-- Yemekler arasındaki ilişkiler: 'parent' bir alt türü (Elma -> Fuji Elma), 'variant' aynı malzemenin farklı
+-- hazırlanış şeklini (Tavuk Göğsü (Çiğ) -> Tavuk Göğsü (Haşlanmış)) bağlıyor. Her yemeğin her ilişkiden en fazla bir tane var
+CREATE TABLE IF NOT EXISTS food_relations (
+    food_id         INTEGER NOT NULL,
+    related_id      INTEGER NOT NULL,
+    relation        TEXT NOT NULL CHECK (relation IN ('parent', 'variant')),
+    PRIMARY KEY (food_id, relation),
+    CHECK (food_id != related_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_food_relations_related ON food_relations (related_id, relation);
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- Etiketler ağaç yapısında tutuluyor (meyve > narenciye), üst etiketi olmayanlar kök etiket
+ALTER TABLE tags ADD COLUMN parent_id INTEGER;
+ALTER TABLE tags ADD COLUMN name TEXT;
+ALTER TABLE tags ADD COLUMN name_en TEXT;
+ALTER TABLE tags ADD COLUMN summary TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_tags_parent ON tags (parent_id);
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Eklerinden ayrılmış ve eş anlamlıları sadeleştirilmiş yemek ismi, "elmalar" gibi çekimli sorguların da bulunabilmesi için
+-- Her açılışta eş anlamlılar sözlüğüne göre yeniden hesaplanıyor
+ALTER TABLE foods ADD COLUMN search_text TEXT NOT NULL DEFAULT '';
\ No newline at end of file
@@ -0,0 +1,29 @@
+-- Yemek isimleri, etiketler, alerjenler ve porsiyon isimleri için çeviriler, db/translations dizinindeki JSON'lardan yükleniyor
+-- Türkçe metinler asıl tablolarda kalıyor, çevirisi olmayan alanlar yanıtlarda Türkçe gösteriliyor
+CREATE TABLE IF NOT EXISTS food_translations (
+    food_id         INTEGER NOT NULL,
+    language        TEXT NOT NULL,
+    description     TEXT NOT NULL,
+    PRIMARY KEY (food_id, language)
+);
+
+CREATE TABLE IF NOT EXISTS tag_translations (
+    tag_id          INTEGER NOT NULL,
+    language        TEXT NOT NULL,
+    description     TEXT NOT NULL,
+    PRIMARY KEY (tag_id, language)
+);
+
+CREATE TABLE IF NOT EXISTS allergen_translations (
+    allergen_id     INTEGER NOT NULL,
+    language        TEXT NOT NULL,
+    description     TEXT NOT NULL,
+    PRIMARY KEY (allergen_id, language)
+);
+
+CREATE TABLE IF NOT EXISTS serving_description_translations (
+    serving_description_id  INTEGER NOT NULL,
+    language                TEXT NOT NULL,
+    description             TEXT NOT NULL,
+    PRIMARY KEY (serving_description_id, language)
+);
\ No newline at end of file
@@ -0,0 +1,49 @@
+use axum::http::{HeaderMap, header::AUTHORIZATION};
+
+use crate::{
+    SharedState,
+    api::error::{APIError, ErrorCode},
+};
+
+// Yönetici endpoint'leri config'deki admin_token ile korunuyor, anahtar girilmemişse bu endpoint'ler hiç açılmıyor
+pub(crate) async fn require_admin(
+    shared_state: &SharedState,
+    headers: &HeaderMap,
+) -> Result<(), APIError> {
+    let admin_token = shared_state.config.lock().await.api.admin_token.to_owned();
+    if admin_token.is_empty() {
+        return Err(APIError::new(ErrorCode::AdminDisabled));
+    }
+
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !constant_time_eq(token.as_bytes(), admin_token.as_bytes()) {
+        return Err(APIError::new(ErrorCode::InvalidAdminToken));
+    }
+
+    Ok(())
+}
+
+// Anahtarı karakter karakter karşılaştırıp ilk farkta dönersek yanıt süresinden anahtar tahmin edilebilir
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"anahtar", b"anahtar"));
+        assert!(!constant_time_eq(b"anahtar", b"anahtaR"));
+        assert!(!constant_time_eq(b"anahtar", b"anahta"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
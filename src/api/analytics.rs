@@ -0,0 +1,551 @@
+use std::{
+    collections::HashMap,
+    fs,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Error};
+use axum::{
+    Extension,
+    body::{Body, Bytes},
+    extract::{Query, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use real::RealIp;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use tracing::{error, info, warn};
+
+use crate::{
+    SharedState,
+    api::{
+        admin::require_admin,
+        error::{APIError, ErrorCode},
+        format::{ResponseFormat, format_response},
+    },
+    core::{
+        popularity::{CLICK_WEIGHT, Popularity, VIEW_WEIGHT},
+        str::to_lower_tr,
+    },
+};
+
+// Bir istemcinin aynı yemeğe tıklaması bu süre içinde en fazla bir kez sayılıyor
+pub(crate) const CLICK_DEDUP_WINDOW: Duration = Duration::from_secs(60 * 60);
+// Raporlarda en fazla bu kadar gün geriye ve bu kadar satıra bakılabiliyor
+const MAX_REPORT_DAYS: u64 = 365;
+const MAX_REPORT_LIMIT: u64 = 100;
+// Sunucu uzun süre açık kaldığında eski kayıtlar günde bir kez siliniyor
+const RETENTION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub(crate) async fn connect_analytics_database(retention_days: u64) -> Result<Pool<Sqlite>, Error> {
+    // Arama kayıtları yemek veritabanından ayrı tutuluyor, yemek veritabanı silinip JSON'lardan yeniden oluşturulabiliyor
+    fs::create_dir_all("db").context("db/ dizini oluşturulamadı!")?;
+    let pool = SqlitePool::connect("sqlite:db/analytics.sqlite?mode=rwc")
+        .await
+        .context("Analitik veritabanına bağlanılamadı!")?;
+
+    sqlx::migrate!("./migrations/analytics")
+        .run(&pool)
+        .await
+        .context("Analitik migration'ları uygulanamadı!")?;
+
+    let deleted = delete_events_before(&pool, now() - days_to_seconds(retention_days)).await?;
+    info!(
+        "Analitik veritabanına bağlanıldı, {} eski kayıt silindi.",
+        deleted
+    );
+
+    Ok(pool)
+}
+
+// Saklama süresi config'ten her seferinde okunuyor, ilk silme connect_analytics_database içinde yapıldığı için bir gün sonra başlıyor
+pub(crate) fn spawn_retention_task(state: SharedState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + RETENTION_INTERVAL,
+            RETENTION_INTERVAL,
+        );
+        loop {
+            interval.tick().await;
+            let retention_days = state.config.lock().await.analytics.retention_days;
+            let before = now() - days_to_seconds(retention_days);
+            match delete_events_before(&*state.analytics_db.lock().await, before).await {
+                Ok(deleted) => info!("{} eski analitik kaydı silindi.", deleted),
+                Err(e) => warn!("Eski analitik kayıtları silinemedi: {:?}", e),
+            }
+        }
+    });
+}
+
+fn now() -> i64 {
+    Utc::now().timestamp()
+}
+
+fn days_to_seconds(days: u64) -> i64 {
+    days as i64 * 24 * 60 * 60
+}
+
+// Aramaları ve yemek görüntülenmelerini cache'ten önce yakalıyoruz ki cache'ten dönen yanıtlar da sayılsın
+pub(crate) async fn analytics_middleware(
+    State(state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let (analytics, popularity) = {
+        let config = state.config.lock().await;
+        (config.analytics.enabled, config.popularity.enabled)
+    };
+    if !analytics && !popularity {
+        return Ok(next.run(request).await);
+    }
+
+    let uri = request.uri().clone();
+    let path = uri.path().to_owned();
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    // Hatalı istekler kaydedilmiyor
+    if response.status() != StatusCode::OK {
+        return Ok(response);
+    }
+
+    if path.ends_with("/foods/search") {
+        if !analytics {
+            return Ok(response);
+        }
+
+        let params: HashMap<String, String> = Query::try_from_uri(&uri)
+            .map(|Query(params)| params)
+            .unwrap_or_default();
+        let event = SearchEvent {
+            query: params.get("q").map(|q| {
+                to_lower_tr(
+                    q.split_whitespace()
+                        .collect::<Vec<&str>>()
+                        .join(" ")
+                        .as_str(),
+                )
+            }),
+            // Sorgusuz aramalar sadece filtre ile yapılmış oluyor
+            mode: match (params.get("q"), params.get("mode")) {
+                (None, _) => "filter".to_owned(),
+                (Some(_), Some(mode)) => mode.to_lowercase(),
+                (Some(_), None) => "description".to_owned(),
+            },
+            result_count: response
+                .extensions()
+                .get::<ResultCount>()
+                .map_or(0, |count| count.0),
+            latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+        };
+
+        // Kaydı yanıtı bekletmeden arka planda yapıyoruz
+        let db = state.analytics_db.clone();
+        tokio::spawn(async move {
+            if let Err(e) = insert_search_event(&*db.lock().await, &event, now()).await {
+                warn!("Arama kaydı eklenemedi: {:?}", e);
+            }
+        });
+
+        return Ok(response);
+    }
+
+    // /food/{slug} görüntülenmeleri, /food/{slug}/substitutes gibi alt yollar sayılmıyor
+    if let Some((_, slug)) = path
+        .rsplit_once('/')
+        .filter(|(prefix, _)| prefix.ends_with("/food"))
+    {
+        if popularity {
+            state
+                .popularity
+                .lock()
+                .await
+                .record(slug, VIEW_WEIGHT, now());
+        }
+        if analytics {
+            let slug = slug.to_owned();
+            let db = state.analytics_db.clone();
+            tokio::spawn(async move {
+                if let Err(e) = insert_food_view(&*db.lock().await, &slug, now()).await {
+                    warn!("Yemek görüntülenme kaydı eklenemedi: {:?}", e);
+                }
+            });
+        }
+    }
+
+    Ok(response)
+}
+
+// Arama handler'ının yanıta eklediği sonuç sayısı, cache'ten dönen yanıtlarda da yanıtla birlikte saklanıyor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ResultCount(pub(crate) i64);
+
+pub(crate) struct SearchEvent {
+    pub(crate) query: Option<String>,
+    pub(crate) mode: String,
+    pub(crate) result_count: i64,
+    pub(crate) latency_ms: f64,
+}
+
+async fn insert_search_event(
+    pool: &SqlitePool,
+    event: &SearchEvent,
+    created_at: i64,
+) -> Result<(), Error> {
+    sqlx::query(
+        "INSERT INTO search_events (query, mode, result_count, latency_ms, created_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&event.query)
+    .bind(&event.mode)
+    .bind(event.result_count)
+    .bind(event.latency_ms)
+    .bind(created_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn insert_food_view(pool: &SqlitePool, slug: &str, created_at: i64) -> Result<(), Error> {
+    sqlx::query("INSERT INTO food_views (slug, created_at) VALUES (?, ?)")
+        .bind(slug)
+        .bind(created_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+async fn insert_search_click(pool: &SqlitePool, slug: &str, created_at: i64) -> Result<(), Error> {
+    sqlx::query("INSERT INTO search_clicks (slug, created_at) VALUES (?, ?)")
+        .bind(slug)
+        .bind(created_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// Sunucu yeniden başladığında popülerlik sıfırlanmasın diye kayıtlı görüntülenme ve tıklanmalardan yeniden hesaplanıyor
+pub(crate) async fn load_popularity(
+    pool: &SqlitePool,
+    half_life_days: f64,
+) -> Result<Popularity, Error> {
+    let mut popularity = Popularity::new(half_life_days);
+    for (table, weight) in [("food_views", VIEW_WEIGHT), ("search_clicks", CLICK_WEIGHT)] {
+        for row in sqlx::query(&format!("SELECT slug, created_at FROM {}", table))
+            .fetch_all(pool)
+            .await?
+        {
+            popularity.record(row.try_get("slug")?, weight, row.try_get("created_at")?);
+        }
+    }
+    Ok(popularity)
+}
+
+async fn delete_events_before(pool: &SqlitePool, before: i64) -> Result<u64, Error> {
+    let mut deleted = 0;
+    for table in ["search_events", "food_views", "search_clicks"] {
+        deleted += sqlx::query(&format!("DELETE FROM {} WHERE created_at < ?", table))
+            .bind(before)
+            .execute(pool)
+            .await?
+            .rows_affected();
+    }
+    Ok(deleted)
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct QueryCount {
+    query: String,
+    mode: String,
+    count: i64,
+    average_results: f64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct TrendingFood {
+    slug: String,
+    views: i64,
+    // Bir önceki aynı uzunluktaki dönemdeki görüntülenme sayısı
+    previous_views: i64,
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct AnalyticsReport {
+    days: u64,
+    total_searches: i64,
+    zero_result_searches: i64,
+    average_latency_ms: Option<f64>,
+    top_queries: Vec<QueryCount>,
+    // Veri ekibi için en önemli liste, aranıp bulunamayan yemekler db/foods'a eklenmeye aday
+    zero_result_queries: Vec<QueryCount>,
+    trending_foods: Vec<TrendingFood>,
+}
+
+async fn select_query_counts(
+    pool: &SqlitePool,
+    since: i64,
+    zero_results: bool,
+    limit: u64,
+) -> Result<Vec<QueryCount>, Error> {
+    let sql = format!(
+        "SELECT query, mode, COUNT(*) AS count, AVG(result_count) AS average_results
+        FROM search_events
+        WHERE created_at >= ? AND query IS NOT NULL {}
+        GROUP BY query, mode
+        ORDER BY count DESC, query
+        LIMIT ?",
+        if zero_results {
+            "AND result_count = 0"
+        } else {
+            ""
+        }
+    );
+
+    let mut counts = Vec::new();
+    for row in sqlx::query(&sql)
+        .bind(since)
+        .bind(limit as i64)
+        .fetch_all(pool)
+        .await?
+    {
+        counts.push(QueryCount {
+            query: row.try_get("query")?,
+            mode: row.try_get("mode")?,
+            count: row.try_get("count")?,
+            average_results: row.try_get("average_results")?,
+        });
+    }
+    Ok(counts)
+}
+
+// Görüntülenmeleri seçilen dönem ve ondan önceki aynı uzunluktaki dönem için sayıp en çok artanları döndürüyoruz
+async fn select_trending_foods(
+    pool: &SqlitePool,
+    since: i64,
+    previous_since: i64,
+    limit: u64,
+) -> Result<Vec<TrendingFood>, Error> {
+    let mut foods = Vec::new();
+    for row in sqlx::query(
+        "SELECT slug,
+            SUM(CASE WHEN created_at >= ? THEN 1 ELSE 0 END) AS views,
+            SUM(CASE WHEN created_at < ? THEN 1 ELSE 0 END) AS previous_views
+        FROM food_views
+        WHERE created_at >= ?
+        GROUP BY slug
+        HAVING views > 0
+        ORDER BY views - previous_views DESC, views DESC, slug
+        LIMIT ?",
+    )
+    .bind(since)
+    .bind(since)
+    .bind(previous_since)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?
+    {
+        foods.push(TrendingFood {
+            slug: row.try_get("slug")?,
+            views: row.try_get("views")?,
+            previous_views: row.try_get("previous_views")?,
+        });
+    }
+    Ok(foods)
+}
+
+async fn select_report(
+    pool: &SqlitePool,
+    until: i64,
+    days: u64,
+    limit: u64,
+) -> Result<AnalyticsReport, Error> {
+    let since = until - days_to_seconds(days);
+
+    let totals = sqlx::query(
+        "SELECT COUNT(*) AS total,
+            SUM(CASE WHEN result_count = 0 THEN 1 ELSE 0 END) AS zero_results,
+            AVG(latency_ms) AS average_latency
+        FROM search_events WHERE created_at >= ?",
+    )
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(AnalyticsReport {
+        days,
+        total_searches: totals.try_get("total")?,
+        // Hiç kayıt yoksa SUM null dönüyor
+        zero_result_searches: totals
+            .try_get::<Option<i64>, _>("zero_results")?
+            .unwrap_or(0),
+        average_latency_ms: totals.try_get("average_latency")?,
+        top_queries: select_query_counts(pool, since, false, limit).await?,
+        zero_result_queries: select_query_counts(pool, since, true, limit).await?,
+        trending_foods: select_trending_foods(pool, since, since - days_to_seconds(days), limit)
+            .await?,
+    })
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SearchClick {
+    slug: String,
+}
+
+// Arama sonuçlarından bir yemeğe tıklandığında istemcinin gönderdiği beacon. navigator.sendBeacon ile string gönderildiğinde
+// Content-Type text/plain oluyor, bu yüzden gövdeyi Content-Type'a bakmadan JSON olarak okuyoruz
+pub(crate) async fn search_click(
+    State(shared_state): State<SharedState>,
+    Extension(RealIp(ip)): Extension<RealIp>,
+    body: Bytes,
+) -> Result<StatusCode, APIError> {
+    let click: SearchClick = serde_json::from_slice(&body)
+        .map_err(|_| APIError::from_status(StatusCode::BAD_REQUEST))?;
+
+    // Var olmayan slug'lar için sayaç açılmasın, bellekteki otomatik tamamlama indeksi tüm onaylı yemekleri içeriyor
+    let exists = shared_state
+        .autocomplete
+        .read()
+        .map_err(|_| APIError::new(ErrorCode::InternalError))?
+        .contains_slug(&click.slug);
+    if !exists {
+        return Err(APIError::new(ErrorCode::FoodNotFound));
+    }
+
+    // Aynı istemcinin aynı yemeğe tekrar tekrar tıklaması popülerliği şişirmesin, pencere içindeki tekrarlar sessizce yok sayılıyor
+    if !shared_state
+        .clicks
+        .entry((ip, click.slug.clone()))
+        .or_insert(())
+        .await
+        .is_fresh()
+    {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let (analytics, popularity) = {
+        let config = shared_state.config.lock().await;
+        (config.analytics.enabled, config.popularity.enabled)
+    };
+    if popularity {
+        shared_state
+            .popularity
+            .lock()
+            .await
+            .record(&click.slug, CLICK_WEIGHT, now());
+    }
+    if analytics {
+        insert_search_click(&*shared_state.analytics_db.lock().await, &click.slug, now())
+            .await
+            .map_err(|e| {
+                error!("Tıklama kaydı eklenemedi: {:?}", e);
+                APIError::new(ErrorCode::DatabaseError)
+            })?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ReportParams {
+    days: Option<u64>,
+    limit: Option<u64>,
+}
+
+pub(crate) async fn analytics_report(
+    params: Query<ReportParams>,
+    format: ResponseFormat,
+    headers: HeaderMap,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    require_admin(&shared_state, &headers).await?;
+
+    let days = params.days.unwrap_or(7);
+    let limit = params.limit.unwrap_or(20);
+    if days == 0 || days > MAX_REPORT_DAYS || limit > MAX_REPORT_LIMIT {
+        return Err(APIError::new(ErrorCode::InvalidReportRange));
+    }
+
+    let report = select_report(&*shared_state.analytics_db.lock().await, now(), days, limit)
+        .await
+        .map_err(|e| {
+            error!("Analitik raporu oluşturulurken hata oluştu: {:?}", e);
+            APIError::new(ErrorCode::DatabaseError)
+        })?;
+
+    format_response(format, &report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_analytics_report() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/analytics").run(&pool).await?;
+
+        let day = days_to_seconds(1);
+        let until = 100 * day;
+        let search = |query: &str, result_count: i64| SearchEvent {
+            query: Some(query.to_owned()),
+            mode: "description".to_owned(),
+            result_count,
+            latency_ms: 2.0,
+        };
+        for _ in 0..3 {
+            insert_search_event(&pool, &search("elma", 2), until - day).await?;
+        }
+        insert_search_event(&pool, &search("ayran", 0), until - day).await?;
+        insert_search_event(&pool, &search("ayran", 0), until - 2 * day).await?;
+        // Rapor dönemi dışında kalıyor
+        insert_search_event(&pool, &search("boza", 0), until - 30 * day).await?;
+
+        insert_food_view(&pool, "elma", until - day).await?;
+        insert_food_view(&pool, "elma", until - 10 * day).await?;
+        insert_food_view(&pool, "elma", until - 11 * day).await?;
+        insert_food_view(&pool, "muz", until - day).await?;
+        insert_food_view(&pool, "muz", until - 2 * day).await?;
+
+        let report = select_report(&pool, until, 7, 10).await?;
+        assert_eq!(report.total_searches, 5);
+        assert_eq!(report.zero_result_searches, 2);
+        assert_eq!(report.average_latency_ms, Some(2.0));
+        assert_eq!(report.top_queries[0].query, "elma");
+        assert_eq!(report.top_queries[0].count, 3);
+        assert_eq!(
+            report.zero_result_queries,
+            vec![QueryCount {
+                query: "ayran".to_owned(),
+                mode: "description".to_owned(),
+                count: 2,
+                average_results: 0.0,
+            }]
+        );
+        // Muz bu dönemde daha çok ilgi görüyor, elma önceki dönemde daha çok görüntülenmiş
+        assert_eq!(
+            report.trending_foods,
+            vec![
+                TrendingFood {
+                    slug: "muz".to_owned(),
+                    views: 2,
+                    previous_views: 0,
+                },
+                TrendingFood {
+                    slug: "elma".to_owned(),
+                    views: 1,
+                    previous_views: 2,
+                },
+            ]
+        );
+
+        insert_search_click(&pool, "muz", until - day).await?;
+        let popularity = load_popularity(&pool, 7.0).await?;
+        assert_eq!(popularity.len(), 2);
+        assert!(popularity.score("muz", until) > popularity.score("elma", until));
+
+        assert_eq!(delete_events_before(&pool, until - 7 * day).await?, 3);
+        Ok(())
+    }
+}
@@ -1,19 +1,65 @@
 use axum::{
-    body::Body,
+    body::{Body, Bytes},
     extract::{Request, State},
+    http::HeaderValue,
     middleware::Next,
     response::Response,
 };
-use reqwest::{header::{CACHE_CONTROL, CONTENT_TYPE}, StatusCode};
+use reqwest::{header::{CACHE_CONTROL, CONTENT_TYPE, VARY}, Method, StatusCode};
 
-use crate::SharedState;
+use crate::{
+    SharedState,
+    api::{
+        analytics::ResultCount,
+        format::request_format,
+        language::request_language,
+    },
+};
+
+// Yanıtlar JSON, CSV, MessagePack veya CBOR olabildiği için gövdeyle birlikte biçimini de saklıyoruz
+#[derive(Clone)]
+pub(crate) struct CachedResponse {
+    content_type: HeaderValue,
+    body: Bytes,
+    // Arama yanıtlarının sonuç sayısı, analitik katmanı cache'ten dönen yanıtları da sayabilsin diye
+    result_count: Option<ResultCount>,
+}
 
 pub async fn cache_middleware(
     State(state): State<SharedState>,
     request: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    let url = request.uri().to_string();
+    // Sadece GET isteklerini cache'liyoruz, POST gibi isteklerde yanıt URL'ye değil gövdeye bağlı
+    if request.method() != Method::GET {
+        return Ok(next.run(request).await);
+    }
+
+    // Otomatik tamamlama her tuş vuruşunda farklı bir URL ile çağrılıyor ve zaten bellekten cevap veriyor, cache'i doldurmasın
+    if request.uri().path().ends_with("/foods/autocomplete") {
+        return Ok(next.run(request).await);
+    }
+
+    // Dışa aktarım veritabanından akıtılıyor, tüm veri setini belleğe almamak için cache'in dışında tutuyoruz
+    if request.uri().path().ends_with("/foods/export.ndjson") {
+        return Ok(next.run(request).await);
+    }
+
+    // Cache URL'ye göre tutulduğu için yönetici yanıtları anahtarsız isteklere de dönebilir, bunları hiç cache'lemiyoruz
+    if request.uri().path().contains("/admin/") {
+        return Ok(next.run(request).await);
+    }
+
+    // Aynı URL Accept-Language başlığına göre farklı dilde dönebildiği için dili de anahtara ekliyoruz
+    // Geçersiz bir lang parametresinde hatayı handler döndürüyor
+    let Ok(language) = request_language(request.uri(), request.headers()) else {
+        return Ok(next.run(request).await);
+    };
+    // Accept başlığı URL'de görünmediği için yanıtın biçimini de anahtara ekliyoruz, geçersiz biçimde hatayı handler döndürüyor
+    let Ok(format) = request_format(request.uri(), request.headers()) else {
+        return Ok(next.run(request).await);
+    };
+    let url = format!("{} {} {}", language.code(), format.cache_key(), request.uri());
     let ttl = match request.uri().path() {
         "/api" | "/api/foods" => std::time::Duration::MAX, // Bu 2 endpoint zaten statik o yüzden bir defa cache atmamız yeterli,
         "/api/health" => std::time::Duration::from_secs(600), // Timestamp attığı ve anlık önemli olduğu için 10 dakikada 1 cache
@@ -24,12 +70,16 @@ pub async fn cache_middleware(
     // Önce veri önbelleğe zaten kaydedilmiş mi bakıyoruz
     // Eğer cache edilen sayfanın ömrü bittiyse zaten moka halletmiş olacak, bizim bir ttl kontrolü yapmamıza gerek yok
     if let Some(cached) = state.cache.get(&url).await {
-        let response = Response::builder()
+        let mut response = Response::builder()
             .status(StatusCode::OK)
-            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_TYPE, cached.content_type)
             .header(CACHE_CONTROL, format!("public, max-age={}", ttl.as_secs()))
-            .body(cached.into())
+            .header(VARY, "Accept-Language, Accept")
+            .body(cached.body.into())
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if let Some(result_count) = cached.result_count {
+            response.extensions_mut().insert(result_count);
+        }
 
         return Ok(response);
     }
@@ -41,23 +91,40 @@ pub async fn cache_middleware(
         return Ok(response);
     }
 
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .cloned()
+        .unwrap_or_else(|| HeaderValue::from_static("application/json"));
+    let result_count = response.extensions().get::<ResultCount>().copied();
     // Body boyutunu garip sorgulara karşı 10MB olarak limitleyelim, DoS saldırılarına karşı yardımcı olabilir
     let body = axum::body::to_bytes(response.into_body(), 10 * 1024 * 1024)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let body = std::str::from_utf8(&body)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        .to_owned();
     // Daha sonra cache'e ekleyeceğiz, yanıt başarılı veya başarısız olabilir
-    state.cache.insert(url, body.to_owned()).await;
+    state
+        .cache
+        .insert(
+            url,
+            CachedResponse {
+                content_type: content_type.clone(),
+                body: body.clone(),
+                result_count,
+            },
+        )
+        .await;
 
     // Cache-Control başlığını da unutmuyoruz header olarak, client tarafında da cache için
-    let response = Response::builder()
+    let mut response = Response::builder()
         .status(StatusCode::OK)
-        .header(CONTENT_TYPE, "application/json")
+        .header(CONTENT_TYPE, content_type)
         .header(CACHE_CONTROL, format!("public, max-age={}", ttl.as_secs()))
+        .header(VARY, "Accept-Language, Accept")
         .body(body.into())
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(result_count) = result_count {
+        response.extensions_mut().insert(result_count);
+    }
 
     Ok(response)
 }
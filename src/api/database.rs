@@ -1,12 +1,32 @@
-use std::fs;
-
-use crate::core::{food::Food, str::to_lower_en_kebab_case};
+use std::{collections::BTreeMap, fs};
+
+use crate::core::{
+    autocomplete::Suggestion,
+    facet::{Bucket, DIET_FLAGS, ENERGY_BUCKETS, Facets, PROTEIN_BUCKETS, bucket_sql, histogram},
+    filter::{Expr, Field, parse_filter},
+    food::{Food, FoodSummary},
+    language::Language,
+    nutrient::{Nutrient, Provenance},
+    recipe::{Recipe, compute_recipe},
+    source::Source,
+    stem::Synonyms,
+    str::to_lower_en_kebab_case,
+    tag::Tag,
+    translation::{Translation, TranslationKind, Translations},
+};
 use anyhow::{Context, Error, anyhow};
-use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use futures_util::stream::BoxStream;
+use serde::de::DeserializeOwned;
+use sqlx::{
+    FromRow, Pool, Row, Sqlite, SqlitePool, Transaction,
+    query::Query,
+    sqlite::{SqliteArguments, SqliteRow},
+};
 use tracing::{info, warn};
 
-fn load_foods_from_jsons(dir: &str) -> Result<Vec<Food>, Error> {
-    let mut all_foods: Vec<Food> = Vec::new();
+// Yemekler ve tarifler aynı şekilde, bir dizindeki JSON dizilerinden yüklendiği için fonksiyonu generic yaptık
+fn load_from_jsons<T: DeserializeOwned>(dir: &str) -> Result<Vec<T>, Error> {
+    let mut all_items: Vec<T> = Vec::new();
 
     let paths = fs::read_dir(dir)?;
     for path in paths {
@@ -22,20 +42,35 @@ fn load_foods_from_jsons(dir: &str) -> Result<Vec<Food>, Error> {
             continue;
         };
 
-        if let Ok(mut foods) = serde_json::from_reader::<_, Vec<Food>>(file) {
-            all_foods.append(&mut foods);
-        } else {
+        let Ok(items) = serde_json::from_reader::<_, Vec<serde_json::Value>>(file) else {
             warn!(
-                "{}/{} dosyası JSON yemek formatında okunamadı!",
+                "{}/{} dosyası beklenen JSON formatında okunamadı!",
                 dir, file_name
             );
+            continue;
         };
+
+        // Kayıtları tek tek okuyoruz ki hatalı bir kayıt dosyadaki diğer kayıtları da düşürmesin
+        for (idx, item) in items.into_iter().enumerate() {
+            let name = item
+                .get("description")
+                .and_then(|description| description.as_str())
+                .map(|description| description.to_owned())
+                .unwrap_or_else(|| format!("{}. kayıt", idx + 1));
+            match T::deserialize(item) {
+                Ok(item) => all_items.push(item),
+                Err(e) => warn!(
+                    "{}/{} dosyasındaki {} okunamadı: {}",
+                    dir, file_name, name, e
+                ),
+            }
+        }
     }
 
-    Ok(all_foods)
+    Ok(all_items)
 }
 
-pub(crate) async fn connect_database() -> Result<Pool<Sqlite>, Error> {
+pub(crate) async fn connect_database(synonyms: &Synonyms) -> Result<Pool<Sqlite>, Error> {
     // Veritabanı olarak SQLite kullanıyoruz, db/foods.sqlite dizininde olacak şekilde
     fs::create_dir_all("db").expect("db/ dizini oluşturulamadı");
     let database_url = "sqlite:db/foods.sqlite?mode=rwc"; // rwc mod sayesinde eğer veritabanı dosyası yoksa oluşturuyoruz
@@ -51,12 +86,36 @@ pub(crate) async fn connect_database() -> Result<Pool<Sqlite>, Error> {
         .context("Migration'lar uygulanamadı!")?;
     info!("Migration'lar uygulandı!");
 
+    // Kaynak bilgileri yemeklerden önce ekleniyor, yemekler description üzerinden bu kaynaklara bağlanıyor
+    if let Ok(sources) = load_from_jsons::<Source>("./db/sources") {
+        for source in sources {
+            if let Err(e) = upsert_source(&pool, &source).await {
+                warn!(
+                    "{} kaynağını JSON dosyasından veritabanına aktarırken bir sorun oluştu: {}",
+                    source.description, e
+                );
+            }
+        }
+    }
+
+    // Etiket ağacı da aynı şekilde her açılışta güncelleniyor, yemeklerin etiketleri bu ağaca description üzerinden bağlanıyor
+    if let Ok(tags) = load_from_jsons::<Tag>("./db/tags") {
+        for tag in tags {
+            if let Err(e) = upsert_tag(&pool, &tag).await {
+                warn!(
+                    "{} etiketini JSON dosyasından veritabanına aktarırken bir sorun oluştu: {}",
+                    tag.description, e
+                );
+            }
+        }
+    }
+
     // JSON dosyalarını bulup hepsini veritabanına eğer mevcut değillerse ekliyoruz. Bu sayede toplu şekilde veritabanına kolayca ekleme yapabiliriz
     // Ayrıca veritabanı dosyası .gitignore'da olacağı ve üzerine JSON harici eklemeler yapılacağı için; varsayılan JSON dosyalarının depoda olması yığın eklemeleri kolaylaştıracaktır
     // *DİKKAT* JSON okuma methodumuz async değil, bu kod sadece bağlantıda yani ilk açılışta çalıştırıldığı için main thread'i bloklamak sorun olmayacaktır
-    if let Ok(foods) = load_foods_from_jsons("./db/foods") {
+    if let Ok(foods) = load_from_jsons::<Food>("./db/foods") {
         // Eğer yoklar ise bu yemekleri veritabanına eklemeliyiz
-        for food in foods {
+        for food in foods.iter().cloned() {
             let food_name = food.description.to_owned();
 
             match insert_food(&pool, food).await {
@@ -82,6 +141,60 @@ pub(crate) async fn connect_database() -> Result<Pool<Sqlite>, Error> {
                 }
             }
         }
+
+        // İlişkiler iki yemeğin de eklenmiş olmasını gerektiriyor, dosyalardaki sıradan bağımsız olsun diye en sona bırakıyoruz
+        for food in &foods {
+            if let Err(e) = insert_food_relations(&pool, food).await {
+                warn!(
+                    "{} yemeğinin ilişkileri veritabanına eklenirken bir sorun oluştu: {}",
+                    food.description, e
+                );
+            }
+        }
+    }
+
+    // Tarifler malzemelerine ihtiyaç duyduğu için yemeklerden sonra ekleniyor, besin değerleri eklenirken hesaplanıyor
+    if let Ok(recipes) = load_from_jsons::<Recipe>("./db/recipes") {
+        for recipe in recipes {
+            let recipe_name = recipe.description.to_owned();
+
+            match insert_recipe(&pool, recipe).await {
+                Ok(food) => {
+                    info!(
+                        "{} tarifi başarıyla {} ID'si ile JSON dosyasından, veritabanına eklendi.",
+                        recipe_name,
+                        food.id.unwrap_or_default()
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        "{} tarifini JSON dosyasından veritabanına aktarırken bir sorun oluştu: {}",
+                        recipe_name, e
+                    );
+                }
+            }
+        }
+    }
+
+    // Çeviriler yemeklere slug'ları, diğer alanlara Türkçe açıklamaları üzerinden bağlanıyor. Alerjen ve porsiyon isimleri
+    // yemeklerle birlikte eklendiği için en sona bırakıyoruz, çeviriler de etiketler gibi her açılışta güncelleniyor
+    if let Ok(translations) = load_from_jsons::<Translation>("./db/translations") {
+        for translation in translations {
+            if let Err(e) = upsert_translation(&pool, &translation).await {
+                warn!(
+                    "{} çevirisini JSON dosyasından veritabanına aktarırken bir sorun oluştu: {}",
+                    translation.key, e
+                );
+            }
+        }
+    }
+
+    // Tarifler de yemek olarak eklendiği için aranabilir metinleri en son hesaplıyoruz
+    if let Err(e) = update_search_texts(&pool, synonyms).await {
+        warn!(
+            "Yemeklerin aranabilir metinleri güncellenirken bir sorun oluştu: {}",
+            e
+        );
     }
 
     Ok(pool)
@@ -107,26 +220,104 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     }
 
     let mut tx = pool.begin().await?;
+    let food_id = insert_food_in_tx(&mut tx, &food).await?;
 
-    // Resim ve kaynak için veri açılmadıysa açmamız ve id'yi almamız gerek
+    // Transaction'ı tamamlayalım
+    tx.commit().await?;
+
+    // Yeni yemek yapısını döndürüyoruz, tabii ki veritabanı ID'si ile
+    Ok(Food {
+        id: Some(food_id),
+        ..food
+    })
+}
+
+// Yemeklerden farklı olarak kaynak bilgileri her açılışta JSON'daki haliyle güncelleniyor, yemek eklenirken sadece
+// description ile açılmış bir kaynağın detayları sonradan da girilebilsin diye
+async fn upsert_source(pool: &SqlitePool, source: &Source) -> Result<(), Error> {
+    sqlx::query(
+        "INSERT INTO food_sources (description, title, organization, url, year, license, retrieved_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT (description) DO UPDATE SET
+            title = excluded.title, organization = excluded.organization, url = excluded.url,
+            year = excluded.year, license = excluded.license, retrieved_at = excluded.retrieved_at",
+    )
+    .bind(&source.description)
+    .bind(&source.title)
+    .bind(&source.organization)
+    .bind(&source.url)
+    .bind(source.year)
+    .bind(&source.license)
+    .bind(&source.retrieved_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn upsert_tag(pool: &SqlitePool, tag: &Tag) -> Result<(), Error> {
+    if tag
+        .parent
+        .as_ref()
+        .is_some_and(|parent| parent.to_lowercase() == tag.description.to_lowercase())
+    {
+        return Err(anyhow!(
+            "{} etiketi kendisinin üst etiketi olamaz",
+            tag.description
+        ));
+    }
+
+    // Üst etiket dosyada daha sonra tanımlanmış olabilir, sadece ismiyle açıp detaylarını o sırası geldiğinde dolduruyoruz
+    if let Some(parent) = &tag.parent {
+        sqlx::query("INSERT OR IGNORE INTO tags (description) VALUES (LOWER(?))")
+            .bind(parent)
+            .execute(pool)
+            .await?;
+    }
+
+    sqlx::query(
+        "INSERT INTO tags (description, parent_id, name, name_en, summary)
+        VALUES (LOWER(?), (SELECT id FROM tags WHERE description = LOWER(?)), ?, ?, ?)
+        ON CONFLICT (description) DO UPDATE SET
+            parent_id = excluded.parent_id, name = excluded.name, name_en = excluded.name_en,
+            summary = excluded.summary",
+    )
+    .bind(&tag.description)
+    .bind(&tag.parent)
+    .bind(&tag.name)
+    .bind(&tag.name_en)
+    .bind(&tag.summary)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// Kaynaklar hem yemeklerde hem de tek tek besin değerlerinde kullanılıyor, yoksa ekleyip id'sini döndürüyoruz
+async fn insert_source_in_tx(tx: &mut Transaction<'_, Sqlite>, source: &str) -> Result<i64, Error> {
     sqlx::query("INSERT OR IGNORE INTO food_sources (description) VALUES (?)")
-        .bind(&food.source)
-        .execute(&mut *tx)
+        .bind(source)
+        .execute(&mut **tx)
         .await?;
-    let source_id =
+    Ok(
         sqlx::query_scalar::<_, i64>("SELECT id FROM food_sources WHERE description = ? LIMIT 1")
-            .bind(&food.source)
-            .fetch_one(&mut *tx)
-            .await?;
+            .bind(source)
+            .fetch_one(&mut **tx)
+            .await?,
+    )
+}
+
+// Tarifler de yemek olarak kaydedildiği için ekleme işlemini transaction üzerinden yapan ayrı bir fonksiyona aldık
+async fn insert_food_in_tx(tx: &mut Transaction<'_, Sqlite>, food: &Food) -> Result<i64, Error> {
+    // Resim ve kaynak için veri açılmadıysa açmamız ve id'yi almamız gerek
+    let source_id = insert_source_in_tx(tx, &food.source).await?;
 
     sqlx::query("INSERT OR IGNORE INTO food_images (image_url) VALUES (?)")
         .bind(&food.image_url)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
     let image_id =
         sqlx::query_scalar::<_, i64>("SELECT id FROM food_images WHERE image_url = ? LIMIT 1")
             .bind(&food.image_url)
-            .fetch_one(&mut *tx)
+            .fetch_one(&mut **tx)
             .await?;
 
     // Resim ve kaynak id'leri yeni bir yemek eklemek için yeterli olacak
@@ -139,13 +330,8 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     // created_at ve updated_at değerlerini SQLite kendisi varsayılan vereceği için buradan müdahale etmiyoruz
     let food_id = sqlx
         ::query_scalar::<_, i64>(
-            "INSERT OR IGNORE INTO foods (
-            slug, description, verified, image_id, source_id, glycemic_index, energy, carbohydrate, protein, fat, saturated_fat, 
-            trans_fat, sugar, fiber, water, cholesterol, sodium, potassium, iron, magnesium, calcium, zinc, vitamin_a, vitamin_b6, 
-            vitamin_b12, vitamin_c, vitamin_d, vitamin_e, vitamin_k)
-
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            
+            "INSERT OR IGNORE INTO foods (slug, description, verified, image_id, source_id, glycemic_index, density)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             RETURNING ID"
         )
         .bind(to_lower_en_kebab_case(&food.description))
@@ -154,30 +340,56 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
         .bind(&image_id)
         .bind(&source_id)
         .bind(&food.glycemic_index)
-        .bind(&food.energy)
-        .bind(&food.carbohydrate)
-        .bind(&food.protein)
-        .bind(&food.fat)
-        .bind(&food.saturated_fat)
-        .bind(&food.trans_fat)
-        .bind(&food.sugar)
-        .bind(&food.fiber)
-        .bind(&food.water)
-        .bind(&food.cholesterol)
-        .bind(&food.sodium)
-        .bind(&food.potassium)
-        .bind(&food.iron)
-        .bind(&food.magnesium)
-        .bind(&food.calcium)
-        .bind(&food.zinc)
-        .bind(&food.vitamin_a)
-        .bind(&food.vitamin_b6)
-        .bind(&food.vitamin_b12)
-        .bind(&food.vitamin_c)
-        .bind(&food.vitamin_d)
-        .bind(&food.vitamin_e)
-        .bind(&food.vitamin_k)
-        .fetch_one(&mut *tx).await?;
+        .bind(food.density)
+        .fetch_one(&mut **tx).await?;
+
+    // Besin değerlerini tanımlarına göre ayrı tabloya yazıyoruz, bilinmeyen değerler için satır eklemiyoruz
+    // Tanımı olmayan bir besin, JSON'daki bir yazım hatası olabileceği için yemeğin eklenmesini engelliyor
+    if let Some(nutrient) = food
+        .provenance
+        .keys()
+        .find(|nutrient| food.nutrient(nutrient).is_none())
+    {
+        return Err(anyhow!(
+            "{} değeri bilinmediği halde kaynak bilgisi girilmiş",
+            nutrient
+        ));
+    }
+
+    for (nutrient, value) in &food.nutrients {
+        let Some(value) = value else {
+            continue;
+        };
+
+        let provenance = food.provenance.get(nutrient);
+        let mut source_id = None;
+        if let Some(provenance) = provenance {
+            provenance.validate(nutrient, *value)?;
+            if let Some(source) = &provenance.source {
+                source_id = Some(insert_source_in_tx(tx, source).await?);
+            }
+        }
+
+        let inserted = sqlx::query(
+            "INSERT INTO food_nutrients (food_id, nutrient_id, value, method, source_id, confidence, min_value, max_value, std_dev)
+            SELECT ?, id, ?, ?, ?, ?, ?, ?, ? FROM nutrients WHERE name = ?",
+        )
+        .bind(food_id)
+        .bind(value)
+        .bind(provenance.map(|provenance| provenance.method.as_str()))
+        .bind(source_id)
+        .bind(provenance.and_then(|provenance| provenance.confidence.map(|confidence| confidence.as_str())))
+        .bind(provenance.and_then(|provenance| provenance.min))
+        .bind(provenance.and_then(|provenance| provenance.max))
+        .bind(provenance.and_then(|provenance| provenance.std_dev))
+        .bind(nutrient)
+        .execute(&mut **tx)
+        .await?;
+
+        if inserted.rows_affected() == 0 {
+            return Err(anyhow!("{} tanımlı bir besin değil", nutrient));
+        }
+    }
 
     // Her tag var mı kontrol edeceğiz, varsa da id'lerini yemekle eşleştirmek için food_tags'e ekleyeceğiz
     // Aynı normalizasyonu alerjenler için de yapacağız.
@@ -185,20 +397,20 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     for tag in &food.tags {
         sqlx::query("INSERT OR IGNORE INTO tags (description) VALUES (LOWER(?))")
             .bind(&tag)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         let tag_id = sqlx::query_scalar::<_, i64>(
             "SELECT id FROM tags WHERE description = LOWER(?) LIMIT 1",
         )
         .bind(&tag)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         // Şimdi de food_id <-> tag_id olarak birbirine eşleyeceğiz
         sqlx::query("INSERT OR IGNORE INTO food_tags (food_id, tag_id) VALUES (?, ?)")
             .bind(&food_id)
             .bind(&tag_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
     }
 
@@ -206,19 +418,19 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     for allergen in &food.allergens {
         sqlx::query("INSERT OR IGNORE INTO allergens (description) VALUES (LOWER(?))")
             .bind(&allergen)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         let allergen_id = sqlx::query_scalar::<_, i64>(
             "SELECT id FROM allergens WHERE description = LOWER(?) LIMIT 1",
         )
         .bind(&allergen)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         sqlx::query("INSERT OR IGNORE INTO food_allergens (food_id, allergen_id) VALUES (?, ?)")
             .bind(&food_id)
             .bind(&allergen_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
     }
 
@@ -227,27 +439,135 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     for serving in &food.servings {
         sqlx::query("INSERT OR IGNORE INTO serving_descriptions (description) VALUES (?)")
             .bind(&serving.0)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
         let serving_description_id = sqlx::query_scalar::<_, i64>(
             "SELECT id FROM serving_descriptions WHERE description = ? LIMIT 1",
         )
         .bind(&serving.0)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         sqlx::query("INSERT OR IGNORE INTO food_servings (food_id, serving_description_id, weight) VALUES (?, ?, ?)")
         .bind(&food_id)
         .bind(&serving_description_id)
         .bind(serving.1)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(food_id)
+}
+
+// Üst yemek ve varyant ilişkileri slug üzerinden giriliyor. Varyant grupları tek seviyeli, yani bir varyantın da
+// varyantı olamıyor; böylece bir grubun tüm yemekleri aynı temel yemeğe bağlı ve gruplar tek sorguyla bulunabiliyor
+async fn insert_food_relations(pool: &SqlitePool, food: &Food) -> Result<(), Error> {
+    for (relation, related) in [("parent", &food.parent), ("variant", &food.variant_of)] {
+        let Some(related) = related else {
+            continue;
+        };
+
+        let related_id = sqlx::query_scalar::<_, i64>("SELECT id FROM foods WHERE slug = ?")
+            .bind(related)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| anyhow!("{} slug'ına sahip bir yemek bulunamadı", related))?;
+
+        // Bağlanılan yemek başka bir yemeğin varyantıysa ya da bu yemeğin kendi varyantları varsa grup iki seviyeli olurdu
+        if relation == "variant"
+            && sqlx::query_scalar::<_, i64>(
+                "SELECT 1 FROM food_relations WHERE relation = 'variant'
+                AND (food_id = ? OR related_id = (SELECT id FROM foods WHERE description = ?))",
+            )
+            .bind(related_id)
+            .bind(&food.description)
+            .fetch_optional(pool)
+            .await?
+            .is_some()
+        {
+            return Err(anyhow!(
+                "{} ile {} arasındaki varyant ilişkisi tek seviyeli değil, varyantlar temel yemeğe bağlanmalı",
+                food.description,
+                related
+            ));
+        }
+
+        // Uygulama her açıldığında JSON'lar tekrar okunduğu için var olan ilişkiyi değiştirmiyoruz
+        sqlx::query(
+            "INSERT OR IGNORE INTO food_relations (food_id, related_id, relation)
+            SELECT id, ?, ? FROM foods WHERE description = ? AND id != ?",
+        )
+        .bind(related_id)
+        .bind(relation)
+        .bind(&food.description)
+        .bind(related_id)
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn insert_recipe(pool: &SqlitePool, recipe: Recipe) -> Result<Food, Error> {
+    recipe.validate(&select_all_nutrients(pool).await?)?;
+
+    if food_exists_by_description(pool, &recipe.description).await? {
+        return Err(anyhow!(
+            "{} isimli tarif zaten veritabanında mevcut, ekleme işlemi atlanıyor.",
+            recipe.description
+        ));
+    }
+
+    // Besin değerlerini hesaplayabilmek için önce malzemelerin veritabanında olması gerekiyor
+    let mut ingredients: Vec<(Food, f64)> = Vec::new();
+    for ingredient in &recipe.ingredients {
+        let food = select_food_by_slug(pool, ingredient.slug.to_owned())
+            .await
+            .with_context(|| {
+                format!(
+                    "{} tarifinin {} malzemesi veritabanında bulunamadı",
+                    recipe.description, ingredient.slug
+                )
+            })?;
+        ingredients.push((food, ingredient.weight));
+    }
+
+    let food = compute_recipe(&recipe, &ingredients);
+
+    let mut tx = pool.begin().await?;
+    let food_id = insert_food_in_tx(&mut tx, &food).await?;
+
+    sqlx::query("INSERT INTO recipes (food_id, yield_factor) VALUES (?, ?)")
+        .bind(food_id)
+        .bind(recipe.yield_factor)
+        .execute(&mut *tx)
+        .await?;
+
+    for (position, (ingredient, weight)) in ingredients.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO recipe_ingredients (recipe_id, ingredient_id, position, weight) VALUES (?, ?, ?, ?)",
+        )
+        .bind(food_id)
+        .bind(ingredient.id)
+        .bind(position as i64)
+        .bind(weight)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (nutrient, factor) in &recipe.retention_factors {
+        sqlx::query(
+            "INSERT INTO recipe_retention_factors (recipe_id, nutrient, factor) VALUES (?, ?, ?)",
+        )
+        .bind(food_id)
+        .bind(nutrient)
+        .bind(factor)
         .execute(&mut *tx)
         .await?;
     }
 
-    // Transaction'ı tamamlayalım
     tx.commit().await?;
 
-    // Yeni yemek yapısını döndürüyoruz, tabii ki veritabanı ID'si ile
     Ok(Food {
         id: Some(food_id),
         ..food
@@ -265,22 +585,363 @@ pub(crate) async fn select_all_foods_slugs(pool: &SqlitePool) -> Result<Vec<Stri
     Ok(slugs)
 }
 
-pub(crate) async fn select_all_tags(pool: &SqlitePool) -> Result<Vec<String>, Error> {
-    let mut tags: Vec<String> = Vec::new();
-    for row in sqlx::query("SELECT description FROM tags")
+// Eş anlamlılar sözlüğü değişmiş olabileceği için tüm yemeklerin aranabilir metinlerini her açılışta yeniden hesaplıyoruz
+pub(crate) async fn update_search_texts(
+    pool: &SqlitePool,
+    synonyms: &Synonyms,
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+    for row in sqlx::query("SELECT id, description FROM foods")
+        .fetch_all(&mut *tx)
+        .await?
+    {
+        let id: i64 = row.try_get("id")?;
+        let description: String = row.try_get("description")?;
+        sqlx::query("UPDATE foods SET search_text = ? WHERE id = ?")
+            .bind(synonyms.normalize(&description))
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+// Eş anlamlı grupları db/synonyms dizinindeki JSON dosyalarından yükleniyor, dosya yoksa sadece ekler atılıyor
+pub(crate) fn load_synonyms() -> Synonyms {
+    match load_from_jsons::<Vec<String>>("./db/synonyms") {
+        Ok(groups) => Synonyms::new(groups),
+        Err(e) => {
+            warn!("Eş anlamlılar sözlüğü yüklenemedi: {}", e);
+            Synonyms::default()
+        }
+    }
+}
+
+// Çeviri tablosu, bağlı olduğu tablo ve çevirinin JSON'da girildiği anahtar sütunu
+fn translation_table(
+    kind: TranslationKind,
+) -> (&'static str, &'static str, &'static str, &'static str) {
+    match kind {
+        TranslationKind::Food => ("food_translations", "food_id", "foods", "slug"),
+        TranslationKind::Tag => ("tag_translations", "tag_id", "tags", "description"),
+        TranslationKind::Allergen => (
+            "allergen_translations",
+            "allergen_id",
+            "allergens",
+            "description",
+        ),
+        TranslationKind::Serving => (
+            "serving_description_translations",
+            "serving_description_id",
+            "serving_descriptions",
+            "description",
+        ),
+    }
+}
+
+async fn upsert_translation(pool: &SqlitePool, translation: &Translation) -> Result<(), Error> {
+    let (table, id_column, source_table, key_column) = translation_table(translation.kind);
+
+    for (code, text) in &translation.translations {
+        // Türkçe metinler zaten asıl tablolarda duruyor
+        let language = Language::from_code(code)
+            .filter(|language| *language != Language::Tr)
+            .ok_or_else(|| anyhow!("{} desteklenen bir çeviri dili değil", code))?;
+
+        let affected = sqlx::query(&format!(
+            "INSERT INTO {table} ({id_column}, language, description)
+            SELECT id, ?, ? FROM {source_table} WHERE {key_column} = ?
+            ON CONFLICT ({id_column}, language) DO UPDATE SET description = excluded.description"
+        ))
+        .bind(language.code())
+        .bind(text)
+        .bind(&translation.key)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        if affected == 0 {
+            return Err(anyhow!(
+                "{} tablosunda {} kaydı bulunamadı",
+                source_table,
+                translation.key
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Yanıtları çevirirken her seferinde veritabanına gitmemek için tüm çeviriler başlangıçta belleğe alınıyor
+pub(crate) async fn select_translations(pool: &SqlitePool) -> Result<Translations, Error> {
+    let mut rows = Vec::new();
+    for kind in [
+        TranslationKind::Food,
+        TranslationKind::Tag,
+        TranslationKind::Allergen,
+        TranslationKind::Serving,
+    ] {
+        let (table, id_column, source_table, key_column) = translation_table(kind);
+        for row in sqlx::query(&format!(
+            "SELECT S.{key_column} AS key, T.language, T.description FROM {table} T
+            INNER JOIN {source_table} S ON S.id = T.{id_column}"
+        ))
+        .fetch_all(pool)
+        .await?
+        {
+            let code: String = row.try_get("language")?;
+            let Some(language) = Language::from_code(&code) else {
+                continue;
+            };
+            rows.push((
+                kind,
+                language,
+                row.try_get("key")?,
+                row.try_get("description")?,
+            ));
+        }
+    }
+    Ok(Translations::new(rows))
+}
+
+// Otomatik tamamlama indeksi için sadece onaylanmış yemeklerin slug ve isimleri yeterli
+pub(crate) async fn select_autocomplete_entries(
+    pool: &SqlitePool,
+) -> Result<Vec<Suggestion>, Error> {
+    let mut entries: Vec<Suggestion> = Vec::new();
+    for row in sqlx::query("SELECT slug, description FROM foods WHERE verified = 1")
         .fetch_all(pool)
         .await?
     {
-        tags.push(row.try_get("description")?);
+        entries.push(Suggestion {
+            slug: row.try_get("slug")?,
+            description: row.try_get("description")?,
+        });
+    }
+    Ok(entries)
+}
+
+// Her etiketi kendisi ve tüm alt etiketleriyle eşleştiren ağaç, UNION tekrarları sildiği için hatalı girilmiş
+// döngüsel bir ilişkide bile sorgu sonlanıyor
+const TAG_TREE_SQL_QUERY: &str = "
+        WITH RECURSIVE tag_tree (root_id, tag_id) AS (
+            SELECT id, id FROM tags
+            UNION
+            SELECT TT.root_id, T.id FROM tag_tree TT INNER JOIN tags T ON T.parent_id = TT.tag_id
+        )";
+
+const SELECT_TAG_SQL_QUERY: &str = "
+        SELECT T.description, P.description AS parent, T.name, T.name_en, T.summary,
+            (SELECT COUNT(DISTINCT FT.food_id)
+             FROM tag_tree TT
+             INNER JOIN food_tags FT ON FT.tag_id = TT.tag_id
+             INNER JOIN foods F ON F.id = FT.food_id
+             WHERE TT.root_id = T.id AND F.verified = 1) AS food_count
+        FROM tags T
+        LEFT JOIN tags P ON P.id = T.parent_id";
+
+fn tag_from_row(row: &SqliteRow) -> Result<Tag, Error> {
+    Ok(Tag {
+        description: row.try_get("description")?,
+        parent: row.try_get("parent")?,
+        name: row.try_get("name")?,
+        name_en: row.try_get("name_en")?,
+        summary: row.try_get("summary")?,
+        food_count: row.try_get("food_count")?,
+        children: None,
+        foods: None,
+    })
+}
+
+pub(crate) async fn select_all_tags(pool: &SqlitePool) -> Result<Vec<Tag>, Error> {
+    let mut tags: Vec<Tag> = Vec::new();
+    for row in sqlx::query(&format!(
+        "{} {} ORDER BY T.description",
+        TAG_TREE_SQL_QUERY, SELECT_TAG_SQL_QUERY
+    ))
+    .fetch_all(pool)
+    .await?
+    {
+        tags.push(tag_from_row(&row)?);
     }
     Ok(tags)
 }
 
+// Etiketi, doğrudan alt etiketleri ve kendisine veya alt etiketlerinden birine sahip onaylı yemeklerle birlikte döndürüyoruz
+pub(crate) async fn select_tag(pool: &SqlitePool, description: &str) -> Result<Tag, Error> {
+    let row = sqlx::query(&format!(
+        "{} {} WHERE T.description = LOWER(?)",
+        TAG_TREE_SQL_QUERY, SELECT_TAG_SQL_QUERY
+    ))
+    .bind(description)
+    .fetch_one(pool)
+    .await?;
+    let mut tag = tag_from_row(&row)?;
+
+    tag.children = Some(
+        sqlx::query_scalar(
+            "SELECT T.description FROM tags T
+            INNER JOIN tags P ON P.id = T.parent_id
+            WHERE P.description = ?
+            ORDER BY T.description",
+        )
+        .bind(&tag.description)
+        .fetch_all(pool)
+        .await?,
+    );
+
+    tag.foods = Some(
+        sqlx::query_scalar(&format!(
+            "{} SELECT DISTINCT F.slug FROM tag_tree TT
+            INNER JOIN tags T ON T.id = TT.root_id
+            INNER JOIN food_tags FT ON FT.tag_id = TT.tag_id
+            INNER JOIN foods F ON F.id = FT.food_id
+            WHERE T.description = ? AND F.verified = 1
+            ORDER BY F.slug",
+            TAG_TREE_SQL_QUERY
+        ))
+        .bind(&tag.description)
+        .fetch_all(pool)
+        .await?,
+    );
+
+    Ok(tag)
+}
+
+pub(crate) async fn select_all_nutrients(pool: &SqlitePool) -> Result<Vec<Nutrient>, Error> {
+    let mut nutrients: Vec<Nutrient> = Vec::new();
+    for row in sqlx::query("SELECT name, description, unit FROM nutrients ORDER BY position")
+        .fetch_all(pool)
+        .await?
+    {
+        nutrients.push(Nutrient {
+            name: row.try_get("name")?,
+            description: row.try_get("description")?,
+            unit: row.try_get("unit")?,
+        });
+    }
+    Ok(nutrients)
+}
+
+const SELECT_SOURCE_SQL_QUERY: &str = "SELECT id, description, title, organization, url, year, license, retrieved_at FROM food_sources";
+
+fn source_from_row(row: &SqliteRow) -> Result<Source, Error> {
+    Ok(Source {
+        id: Some(row.try_get("id")?),
+        description: row.try_get("description")?,
+        title: row.try_get("title")?,
+        organization: row.try_get("organization")?,
+        url: row.try_get("url")?,
+        year: row.try_get("year")?,
+        license: row.try_get("license")?,
+        retrieved_at: row.try_get("retrieved_at")?,
+        foods: None,
+    })
+}
+
+pub(crate) async fn select_all_sources(pool: &SqlitePool) -> Result<Vec<Source>, Error> {
+    let mut sources: Vec<Source> = Vec::new();
+    for row in sqlx::query(&format!("{} ORDER BY id", SELECT_SOURCE_SQL_QUERY))
+        .fetch_all(pool)
+        .await?
+    {
+        sources.push(source_from_row(&row)?);
+    }
+    Ok(sources)
+}
+
+// Kaynağı, yemeğin kendisi veya herhangi bir besin değeri bu kaynağa dayanan onaylı yemeklerle birlikte döndürüyoruz
+pub(crate) async fn select_source_by_id(pool: &SqlitePool, id: i64) -> Result<Source, Error> {
+    let row = sqlx::query(&format!("{} WHERE id = ?", SELECT_SOURCE_SQL_QUERY))
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    let mut source = source_from_row(&row)?;
+
+    source.foods = Some(
+        sqlx::query_scalar(
+            "SELECT slug FROM foods F
+            WHERE F.verified = 1 AND (F.source_id = ?
+                OR EXISTS (SELECT 1 FROM food_nutrients FN WHERE FN.food_id = F.id AND FN.source_id = ?))
+            ORDER BY slug",
+        )
+        .bind(id)
+        .bind(id)
+        .fetch_all(pool)
+        .await?,
+    );
+
+    Ok(source)
+}
+
+pub(crate) async fn select_food_children(
+    pool: &SqlitePool,
+    food_id: i64,
+) -> Result<Vec<String>, Error> {
+    Ok(sqlx::query_scalar(
+        "SELECT F.slug FROM food_relations R
+        INNER JOIN foods F ON F.id = R.food_id
+        WHERE R.related_id = ? AND R.relation = 'parent' AND F.verified = 1
+        ORDER BY F.slug",
+    )
+    .bind(food_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+// Yemeğin kendisi hariç, aynı temel yemeğe bağlı tüm varyantlar ve temel yemeğin kendisi
+pub(crate) async fn select_food_variants(
+    pool: &SqlitePool,
+    food_id: i64,
+) -> Result<Vec<String>, Error> {
+    Ok(sqlx::query_scalar(
+        "WITH base AS (
+            SELECT COALESCE(
+                (SELECT related_id FROM food_relations WHERE food_id = ? AND relation = 'variant'), ?
+            ) AS id
+        )
+        SELECT F.slug FROM foods F, base
+        WHERE F.verified = 1 AND F.id != ? AND (F.id = base.id OR EXISTS (
+            SELECT 1 FROM food_relations R
+            WHERE R.food_id = F.id AND R.related_id = base.id AND R.relation = 'variant'
+        ))
+        ORDER BY F.slug",
+    )
+    .bind(food_id)
+    .bind(food_id)
+    .bind(food_id)
+    .fetch_all(pool)
+    .await?)
+}
+
+// Kaynak bilgisi girilmiş besinlerin { "isim": { "method": ..., "source": ... } } şeklinde listesi
+pub(crate) async fn select_food_provenance(
+    pool: &SqlitePool,
+    food_id: i64,
+) -> Result<BTreeMap<String, Provenance>, Error> {
+    let provenance: String = sqlx::query_scalar(
+        "SELECT json_group_object(N.name, json_object(
+            'method', FN.method, 'source', S.description, 'confidence', FN.confidence,
+            'min', FN.min_value, 'max', FN.max_value, 'std_dev', FN.std_dev))
+        FROM food_nutrients FN
+        INNER JOIN nutrients N ON N.id = FN.nutrient_id
+        LEFT JOIN food_sources S ON S.id = FN.source_id
+        WHERE FN.food_id = ? AND FN.method IS NOT NULL",
+    )
+    .bind(food_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(serde_json::from_str(&provenance)?)
+}
+
 const SELECT_FOOD_SQL_QUERY: &str = r#"
         SELECT 
             F.*,
             FI.image_url, 
             FS.description as source_description,
+            PF.slug as parent,
+            VF.slug as variant_of,
 
             -- Etiketleri de JSON yapıyoruz, birden fazla SQL sorgusu atmak istemiyoruz network roundtrip olmaması için
             (SELECT json_group_array(T.description)
@@ -298,64 +959,441 @@ const SELECT_FOOD_SQL_QUERY: &str = r#"
             (SELECT json_group_object(SD.description, FS.weight)
              FROM serving_descriptions SD
              INNER JOIN food_servings FS ON SD.id = FS.serving_description_id
-             WHERE FS.food_id = F.id) as "servings"
+             WHERE FS.food_id = F.id) as "servings",
+
+            -- Tarifler için malzemeleri de tarifteki sırasıyla JSON dizisi yapıyoruz, normal yemeklerde boş dizi dönecek
+            (SELECT json_group_array(json_object('slug', I.slug, 'description', I.description, 'weight', I.weight))
+             FROM (SELECT IF2.slug, IF2.description, RI.weight
+                   FROM recipe_ingredients RI
+                   INNER JOIN foods IF2 ON IF2.id = RI.ingredient_id
+                   WHERE RI.recipe_id = F.id
+                   ORDER BY RI.position) I) as "ingredients",
+
+            -- Tüm besin tanımları için { "isim": değer } nesnesi yapıyoruz, değeri olmayan besinler null olarak geliyor
+            (SELECT json_group_object(N.name, FN.value)
+             FROM nutrients N
+             LEFT JOIN food_nutrients FN ON FN.nutrient_id = N.id AND FN.food_id = F.id) as "nutrients"
 
         FROM foods F
         
         LEFT JOIN food_images FI ON FI.id = F.image_id
         LEFT JOIN food_sources FS ON FS.id = F.source_id
+        LEFT JOIN food_relations PR ON PR.food_id = F.id AND PR.relation = 'parent'
+        LEFT JOIN foods PF ON PF.id = PR.related_id
+        LEFT JOIN food_relations VR ON VR.food_id = F.id AND VR.relation = 'variant'
+        LEFT JOIN foods VF ON VF.id = VR.related_id
         "#;
 
-pub(crate) async fn select_food_by_slug(pool: &SqlitePool, slug: String) -> Result<Food, Error> {
-    Ok(
-        sqlx::query_as(&format!("{} WHERE F.slug = ?", SELECT_FOOD_SQL_QUERY))
-            .bind(slug)
-            .fetch_one(pool)
-            .await?,
-    )
+pub(crate) async fn select_food_by_slug(pool: &SqlitePool, slug: String) -> Result<Food, Error> {
+    Ok(
+        sqlx::query_as(&format!("{} WHERE F.slug = ?", SELECT_FOOD_SQL_QUERY))
+            .bind(slug)
+            .fetch_one(pool)
+            .await?,
+    )
+}
+
+pub(crate) async fn select_all_verified_foods(pool: &SqlitePool) -> Result<Vec<Food>, Error> {
+    Ok(
+        sqlx::query_as(&format!("{} WHERE F.verified = 1", SELECT_FOOD_SQL_QUERY))
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+pub(crate) fn export_foods_query(include_unverified: bool) -> String {
+    match include_unverified {
+        true => format!("{} ORDER BY F.id", SELECT_FOOD_SQL_QUERY),
+        false => format!(
+            "{} WHERE F.verified = 1 ORDER BY F.id",
+            SELECT_FOOD_SQL_QUERY
+        ),
+    }
+}
+
+// Dışa aktarımda yemekler tüm tablo belleğe alınmadan cursor üzerinden tek tek okunuyor
+pub(crate) fn stream_foods<'a>(
+    pool: &'a SqlitePool,
+    query: &'a str,
+) -> BoxStream<'a, Result<Food, sqlx::Error>> {
+    sqlx::query_as(query).fetch(pool)
+}
+
+// Aramalarla birlikte kullanılabilen filtreler, etiketler birebir ve alt etiketleriyle birlikte eşleşiyor
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct SearchFilter {
+    // Yemekte bu etiketlerin hepsi, en az biri veya hiçbiri bulunmalı
+    pub(crate) all: Vec<String>,
+    pub(crate) any: Vec<String>,
+    pub(crate) none: Vec<String>,
+    // filter= parametresiyle gelen, ayrıştırılmış filtre ifadesi
+    pub(crate) expression: Option<Expr>,
+}
+
+// Filtrelerde SQL'e bağlanan değerler, kullanıcıdan gelen hiçbir değer sorgu metnine yazılmıyor
+#[derive(Clone)]
+enum SqlValue {
+    Text(String),
+    Real(f64),
+}
+
+impl SearchFilter {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.all.is_empty()
+            && self.any.is_empty()
+            && self.none.is_empty()
+            && self.expression.is_none()
+    }
+
+    // WHERE koşuluna eklenecek " AND ..." ifadeleri ve sırasıyla bağlanacak değerler
+    fn sql(&self) -> (String, Vec<SqlValue>) {
+        let exact = tag_exists_sql("= LOWER(?)");
+        let mut sql = String::new();
+        let mut binds: Vec<SqlValue> = Vec::new();
+
+        for tag in &self.all {
+            sql += &format!(" AND {}", exact);
+            binds.push(SqlValue::Text(tag.to_owned()));
+        }
+        if !self.any.is_empty() {
+            sql += &format!(
+                " AND ({})",
+                vec![exact.as_str(); self.any.len()].join(" OR ")
+            );
+            binds.extend(self.any.iter().map(|tag| SqlValue::Text(tag.to_owned())));
+        }
+        for tag in &self.none {
+            sql += &format!(" AND NOT {}", exact);
+            binds.push(SqlValue::Text(tag.to_owned()));
+        }
+
+        if let Some(expression) = &self.expression {
+            sql += &format!(" AND {}", expression_sql(expression, &mut binds));
+        }
+
+        (sql, binds)
+    }
+}
+
+// Filtre ifadesini SQL'e çeviriyoruz. Değeri bilinmeyen besinler ve yoğunluk hiçbir karşılaştırmayı sağlamıyor,
+// böylece "not protein > 10" proteini bilinmeyen yemekleri de döndürüyor ve NULL'ın üç değerli mantığına takılmıyoruz
+fn expression_sql(expression: &Expr, binds: &mut Vec<SqlValue>) -> String {
+    match expression {
+        Expr::And(left, right) => format!(
+            "({} AND {})",
+            expression_sql(left, binds),
+            expression_sql(right, binds)
+        ),
+        Expr::Or(left, right) => format!(
+            "({} OR {})",
+            expression_sql(left, binds),
+            expression_sql(right, binds)
+        ),
+        Expr::Not(expression) => format!("NOT {}", expression_sql(expression, binds)),
+        Expr::Compare(field, op, value) => {
+            let sql = match field {
+                Field::GlycemicIndex => format!("(F.glycemic_index {} ?)", op.as_sql()),
+                Field::Density => {
+                    format!("(F.density IS NOT NULL AND F.density {} ?)", op.as_sql())
+                }
+                Field::Nutrient(name) => {
+                    binds.push(SqlValue::Text(name.to_owned()));
+                    format!(
+                        "EXISTS (SELECT 1 FROM food_nutrients FN
+                            INNER JOIN nutrients N ON N.id = FN.nutrient_id
+                            WHERE FN.food_id = F.id AND N.name = ? AND FN.value {} ?)",
+                        op.as_sql()
+                    )
+                }
+            };
+            binds.push(SqlValue::Real(*value));
+            sql
+        }
+        Expr::Tag(tag) => {
+            binds.push(SqlValue::Text(tag.to_owned()));
+            tag_exists_sql("= LOWER(?)")
+        }
+        Expr::Allergen(allergen) => {
+            binds.push(SqlValue::Text(allergen.to_owned()));
+            "EXISTS (SELECT 1 FROM food_allergens FA
+                INNER JOIN allergens A ON A.id = FA.allergen_id
+                WHERE FA.food_id = F.id AND A.description = LOWER(?))"
+                .to_owned()
+        }
+    }
+}
+
+// Yemeğin, koşula uyan etiketlerden veya bunların alt etiketlerinden birine sahip olup olmadığını kontrol eden ifade
+// Eşleşen etiketlerin alt etiketleri de aranıyor, meyve araması narenciye etiketli yemekleri de bulsun
+fn tag_exists_sql(condition: &str) -> String {
+    format!(
+        "EXISTS (
+            WITH RECURSIVE matched (id) AS (
+                SELECT id FROM tags WHERE description {}
+                UNION
+                SELECT T.id FROM tags T INNER JOIN matched M ON T.parent_id = M.id
+            )
+            SELECT 1 FROM food_tags FT
+                WHERE FT.food_id = F.id AND FT.tag_id IN (SELECT id FROM matched)
+        )",
+        condition
+    )
+}
+
+// Özetler için JSON alt sorgularına gerek yok, enerji ve makroları tek bir birleştirmeyle satıra çeviriyoruz
+const SELECT_FOOD_SUMMARY_SQL_QUERY: &str = "
+        SELECT
+            F.slug,
+            F.description,
+            FI.image_url,
+            VF.slug AS variant_of,
+            MAX(CASE WHEN N.name = 'energy' THEN FN.value END) AS energy,
+            MAX(CASE WHEN N.name = 'carbohydrate' THEN FN.value END) AS carbohydrate,
+            MAX(CASE WHEN N.name = 'protein' THEN FN.value END) AS protein,
+            MAX(CASE WHEN N.name = 'fat' THEN FN.value END) AS fat
+        FROM foods F
+        LEFT JOIN food_images FI ON FI.id = F.image_id
+        LEFT JOIN food_relations VR ON VR.food_id = F.id AND VR.relation = 'variant'
+        LEFT JOIN foods VF ON VF.id = VR.related_id
+        LEFT JOIN food_nutrients FN ON FN.food_id = F.id
+        LEFT JOIN nutrients N ON N.id = FN.nutrient_id";
+
+// Aramanın ana koşulu, filtreler bunun üzerine ekleniyor
+pub(crate) enum SearchQuery<'a> {
+    // %Elma% şeklinde aratıyoruz ki Fuji Elma, Elma Turtası gibi sonuçlar da çıksın. İkinci değer sorgunun Synonyms ile
+    // sadeleştirilmiş hali, search_text sütununda aranıyor ki "elmalar" veya "zerdali" gibi sorgular da sonuç bulsun
+    Description(&'a str, String),
+    Tag(&'a str),
+    // Türkçe dışındaki dillerde isim ve etiket araması, çevirilerle birlikte Türkçe isimler de aranıyor
+    TranslatedDescription(&'a str, Language),
+    TranslatedTag(&'a str, Language),
+    // Arama metni olmadan sadece filtrelerle arama
+    Everything,
+}
+
+// Arama ve facet sorgularının aynı yemek kümesi üzerinde çalışması için WHERE koşulunu tek bir yerde oluşturuyoruz
+fn search_where_sql(query: &SearchQuery, filter: &SearchFilter) -> (String, Vec<SqlValue>) {
+    let (condition, values) = match query {
+        // Sadeleştirilmiş sorgu boşsa "%%" her yemekle eşleşeceği için sadece isimde arıyoruz
+        SearchQuery::Description(description, normalized) if normalized.is_empty() => (
+            "F.description LIKE ?".to_owned(),
+            vec![format!("%{}%", description)],
+        ),
+        SearchQuery::Description(description, normalized) => (
+            "(F.description LIKE ? OR F.search_text LIKE ?)".to_owned(),
+            vec![format!("%{}%", description), format!("%{}%", normalized)],
+        ),
+        SearchQuery::Tag(tag) => (tag_exists_sql("LIKE ?"), vec![format!("%{}%", tag)]),
+        SearchQuery::TranslatedDescription(description, language) => (
+            "(F.description LIKE ? OR EXISTS (SELECT 1 FROM food_translations FT
+                WHERE FT.food_id = F.id AND FT.language = ? AND FT.description LIKE ?))"
+                .to_owned(),
+            vec![
+                format!("%{}%", description),
+                language.code().to_owned(),
+                format!("%{}%", description),
+            ],
+        ),
+        SearchQuery::TranslatedTag(tag, language) => (
+            tag_exists_sql(
+                "LIKE ? OR id IN (SELECT tag_id FROM tag_translations WHERE language = ? AND description LIKE ?)",
+            ),
+            vec![
+                format!("%{}%", tag),
+                language.code().to_owned(),
+                format!("%{}%", tag),
+            ],
+        ),
+        SearchQuery::Everything => ("1 = 1".to_owned(), Vec::new()),
+    };
+    let (filter_sql, filter_binds) = filter.sql();
+
+    let binds = values
+        .into_iter()
+        .map(SqlValue::Text)
+        .chain(filter_binds)
+        .collect();
+    (format!("{}{}", condition, filter_sql), binds)
+}
+
+fn bind_values<'q>(
+    mut query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    values: &'q [SqlValue],
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    for value in values {
+        query = match value {
+            SqlValue::Text(text) => query.bind(text.as_str()),
+            SqlValue::Real(real) => query.bind(*real),
+        };
+    }
+    query
+}
+
+pub(crate) async fn search_foods(
+    pool: &SqlitePool,
+    query: &SearchQuery<'_>,
+    filter: &SearchFilter,
+) -> Result<Vec<Food>, Error> {
+    let (where_sql, binds) = search_where_sql(query, filter);
+    let sql = format!("{} WHERE {}", SELECT_FOOD_SQL_QUERY, where_sql);
+
+    let mut rows = Vec::new();
+    for row in bind_values(sqlx::query(&sql), &binds)
+        .fetch_all(pool)
+        .await?
+    {
+        rows.push(Food::from_row(&row)?);
+    }
+    Ok(rows)
+}
+
+// Aramayla eşleşen onaylı yemeklerin özetleri, search_foods ile aynı koşullar kullanılıyor
+pub(crate) async fn search_food_summaries(
+    pool: &SqlitePool,
+    query: &SearchQuery<'_>,
+    filter: &SearchFilter,
+) -> Result<Vec<FoodSummary>, Error> {
+    let (where_sql, binds) = search_where_sql(query, filter);
+    let sql = format!(
+        "{} WHERE F.verified = 1 AND {} GROUP BY F.id",
+        SELECT_FOOD_SUMMARY_SQL_QUERY, where_sql
+    );
+
+    let mut summaries = Vec::new();
+    for row in bind_values(sqlx::query(&sql), &binds)
+        .fetch_all(pool)
+        .await?
+    {
+        summaries.push(FoodSummary::from_row(&row)?);
+    }
+    Ok(summaries)
 }
 
-pub(crate) async fn search_foods_by_description_wild(
+// Aramayla eşleşen onaylı yemeklerin etiket, alerjen ve diyet sayıları ile enerji ve protein dağılımları
+pub(crate) async fn search_facets(
     pool: &SqlitePool,
-    description: &str,
-) -> Result<Vec<Food>, Error> {
-    Ok(sqlx::query_as(&format!(
-        "{} WHERE F.description LIKE ?",
-        SELECT_FOOD_SQL_QUERY
-    ))
-    // %Elma% şeklinde aratıyoruz ki Fuji Elma, Elma Turtası gibi sonuçlar da çıksın
-    .bind(&format!("%{}%", description))
-    .fetch_all(pool)
-    .await?)
+    query: &SearchQuery<'_>,
+    filter: &SearchFilter,
+) -> Result<Facets, Error> {
+    let (where_sql, binds) = search_where_sql(query, filter);
+    let candidates = format!(
+        "WITH candidates AS (SELECT F.id FROM foods F WHERE F.verified = 1 AND {})",
+        where_sql
+    );
+
+    let total: i64 = bind_values(
+        sqlx::query(&format!(
+            "{} SELECT COUNT(*) AS total FROM candidates",
+            candidates
+        )),
+        &binds,
+    )
+    .fetch_one(pool)
+    .await?
+    .try_get("total")?;
+
+    let mut counts: Vec<BTreeMap<String, i64>> = Vec::new();
+    for (table, join_table, join_column) in [
+        ("tags", "food_tags", "tag_id"),
+        ("allergens", "food_allergens", "allergen_id"),
+    ] {
+        let sql = format!(
+            "{} SELECT T.description, COUNT(*) AS count FROM candidates C
+            INNER JOIN {} J ON J.food_id = C.id
+            INNER JOIN {} T ON T.id = J.{}
+            GROUP BY T.description",
+            candidates, join_table, table, join_column
+        );
+
+        let mut count = BTreeMap::new();
+        for row in bind_values(sqlx::query(&sql), &binds)
+            .fetch_all(pool)
+            .await?
+        {
+            count.insert(row.try_get("description")?, row.try_get("count")?);
+        }
+        counts.push(count);
+    }
+    let allergens = counts.pop().unwrap_or_default();
+    let tags = counts.pop().unwrap_or_default();
+
+    // Diyetler filtre dili ile tanımlı olduğu için arama filtreleriyle aynı şekilde SQL'e çeviriyoruz
+    let mut diet_binds = binds.clone();
+    let mut diet_columns = Vec::new();
+    for (idx, (_, expression)) in DIET_FLAGS.iter().enumerate() {
+        let expression = parse_filter(expression)?;
+        diet_columns.push(format!(
+            "SUM(CASE WHEN {} THEN 1 ELSE 0 END) AS diet_{}",
+            expression_sql(&expression, &mut diet_binds),
+            idx
+        ));
+    }
+    let row = bind_values(
+        sqlx::query(&format!(
+            "{} SELECT {} FROM foods F WHERE F.id IN (SELECT id FROM candidates)",
+            candidates,
+            diet_columns.join(", ")
+        )),
+        &diet_binds,
+    )
+    .fetch_one(pool)
+    .await?;
+    let mut diets = BTreeMap::new();
+    for (idx, (diet, _)) in DIET_FLAGS.iter().enumerate() {
+        // Hiç sonuç yoksa SUM null dönüyor
+        let count: Option<i64> = row.try_get(format!("diet_{}", idx).as_str())?;
+        diets.insert(*diet, count.unwrap_or(0));
+    }
+
+    Ok(Facets {
+        total,
+        tags,
+        allergens,
+        diets,
+        energy: nutrient_histogram(pool, &candidates, &binds, "energy", &ENERGY_BUCKETS).await?,
+        protein: nutrient_histogram(pool, &candidates, &binds, "protein", &PROTEIN_BUCKETS).await?,
+    })
 }
 
-pub(crate) async fn search_foods_by_tag_wild(
+async fn nutrient_histogram(
     pool: &SqlitePool,
-    tag: &str,
-) -> Result<Vec<Food>, Error> {
-    Ok(sqlx::query_as(&format!(
-        "{} 
-        WHERE EXISTS (
-            SELECT 1 FROM tags T 
-                INNER JOIN food_tags FT ON T.id = FT.tag_id 
-                WHERE FT.food_id = F.id AND T.description LIKE ?
-        )",
-        SELECT_FOOD_SQL_QUERY
-    ))
-    .bind(&format!("%{}%", tag))
-    .fetch_all(pool)
-    .await?)
+    candidates: &str,
+    binds: &[SqlValue],
+    nutrient: &str,
+    edges: &[f64],
+) -> Result<Vec<Bucket>, Error> {
+    let sql = format!(
+        "{} SELECT {} AS bucket, COUNT(*) AS count FROM candidates C
+        INNER JOIN food_nutrients FN ON FN.food_id = C.id
+        INNER JOIN nutrients N ON N.id = FN.nutrient_id
+        WHERE N.name = ?
+        GROUP BY bucket",
+        candidates,
+        bucket_sql("FN.value", edges)
+    );
+
+    let mut counts = BTreeMap::new();
+    for row in bind_values(sqlx::query(&sql), binds)
+        .bind(nutrient)
+        .fetch_all(pool)
+        .await?
+    {
+        let bucket: i64 = row.try_get("bucket")?;
+        counts.insert(bucket as usize, row.try_get("count")?);
+    }
+    Ok(histogram(edges, &counts))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*; // Üst scope'daki fonksiyonları kullan
+    use crate::core::nutrient::{Confidence, Method};
 
     #[tokio::test]
     async fn test_connect_and_migrate() -> Result<(), Error> {
         // In-memory veritabanı ile test
         let _pool = SqlitePool::connect("sqlite::memory:").await?;
-        let _db_pool = connect_database().await?; // Gerçek dosya tablosu ile test için yorum satırını kaldır
+        let _db_pool = connect_database(&Synonyms::default()).await?; // Gerçek dosya tablosu ile test için yorum satırını kaldır
         info!("Veritabanı bağlantısı ve migration testi geçti.");
         Ok(())
     }
@@ -373,30 +1411,41 @@ mod tests {
             tags: vec!["test".to_string()],
             allergens: vec![],
             servings: std::collections::BTreeMap::new(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 50.0,
-            energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(100.0)),
+                ("carbohydrate".to_owned(), Some(20.0)),
+                ("protein".to_owned(), Some(5.0)),
+                ("fat".to_owned(), Some(2.0)),
+                ("saturated_fat".to_owned(), Some(1.0)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(10.0)),
+                ("fiber".to_owned(), Some(3.0)),
+                ("water".to_owned(), Some(55.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(50.0)),
+                ("potassium".to_owned(), Some(200.0)),
+                ("iron".to_owned(), Some(1.0)),
+                ("magnesium".to_owned(), Some(30.0)),
+                ("calcium".to_owned(), Some(10.0)),
+                ("zinc".to_owned(), Some(0.5)),
+                ("vitamin_a".to_owned(), Some(0.1)),
+                ("vitamin_b6".to_owned(), Some(0.2)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(5.0)),
+                ("vitamin_d".to_owned(), Some(0.0)),
+                ("vitamin_e".to_owned(), Some(0.1)),
+                ("vitamin_k".to_owned(), Some(0.05)),
+            ]),
             verified: None,
             id: None,
         };
@@ -462,7 +1511,7 @@ mod tests {
         fs::write(format!("{}/test.json", temp_dir), test_json).unwrap();
 
         // Sadece test dizininden yükle
-        let foods = load_foods_from_jsons(temp_dir).unwrap();
+        let foods = load_from_jsons::<Food>(temp_dir).unwrap();
         assert_eq!(foods.len(), 1, "Sadece bir yemek yüklenmeli"); // Diğer dosyaları eklemez
         let food = foods[0].clone();
 
@@ -472,7 +1521,7 @@ mod tests {
         let food_id = result.unwrap().id.unwrap();
         assert!(food_id > 0, "Geçerli bir ID olmalı");
 
-        info!("insert_food ve load_foods_from_jsons testi geçti.");
+        info!("insert_food ve load_from_jsons testi geçti.");
 
         // Testten sonra dosyayı ve dizini sil
         fs::remove_file(format!("{}/test.json", temp_dir)).unwrap();
@@ -481,6 +1530,44 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_load_from_jsons_skips_invalid_items() {
+        let temp_dir = "./db/test_temp_invalid";
+        fs::create_dir_all(temp_dir).unwrap();
+        let food = |description: &str, extra: &str| {
+            format!(
+                r#"{{"description": "{}", "image_url": "", "source": "", "tags": [], "allergens": [],
+                "servings": {{}}, "glycemic_index": 0.0, "energy": 52.0, "vitamin_d": null{}}}"#,
+                description, extra
+            )
+        };
+        let test_json = format!(
+            "[{}, {}]",
+            food("Elma", ""),
+            food("Armut", r#", "notes": "kontrol edilecek""#)
+        );
+        fs::write(format!("{}/test.json", temp_dir), test_json).unwrap();
+
+        // Besin değeri olmayan alan sadece o yemeği düşürmeli, dosyadaki diğer yemekler yüklenmeli
+        let foods = load_from_jsons::<Food>(temp_dir).unwrap();
+        assert_eq!(foods.len(), 1);
+        assert_eq!(foods[0].description, "Elma");
+        assert_eq!(foods[0].nutrient("energy"), Some(52.0));
+        assert_eq!(foods[0].nutrients["vitamin_d"], None);
+
+        let error =
+            serde_json::from_str::<Food>(&food("Armut", r#", "notes": "kontrol edilecek""#))
+                .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("notes alanı sayı veya null olmalı")
+        );
+
+        fs::remove_file(format!("{}/test.json", temp_dir)).unwrap();
+        fs::remove_dir(temp_dir).unwrap();
+    }
+
     #[tokio::test]
     async fn test_select_all_foods_slugs() -> Result<(), Error> {
         // In-memory veritabanı
@@ -497,30 +1584,41 @@ mod tests {
             tags: vec!["meyve".to_string()],
             allergens: vec![],
             servings: std::collections::BTreeMap::new(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 40.0,
-            energy: 50.0,
-            carbohydrate: 10.0,
-            protein: 0.5,
-            fat: 0.2,
-            saturated_fat: 0.0,
-            trans_fat: 0.0,
-            sugar: 8.0,
-            fiber: 2.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
-            potassium: 150.0,
-            iron: 0.1,
-            magnesium: 5.0,
-            calcium: 6.0,
-            zinc: 0.1,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.0,
-            vitamin_b12: 0.0,
-            vitamin_c: 4.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.0,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(50.0)),
+                ("carbohydrate".to_owned(), Some(10.0)),
+                ("protein".to_owned(), Some(0.5)),
+                ("fat".to_owned(), Some(0.2)),
+                ("saturated_fat".to_owned(), Some(0.0)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(8.0)),
+                ("fiber".to_owned(), Some(2.0)),
+                ("water".to_owned(), Some(55.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(1.0)),
+                ("potassium".to_owned(), Some(150.0)),
+                ("iron".to_owned(), Some(0.1)),
+                ("magnesium".to_owned(), Some(5.0)),
+                ("calcium".to_owned(), Some(6.0)),
+                ("zinc".to_owned(), Some(0.1)),
+                ("vitamin_a".to_owned(), Some(0.0)),
+                ("vitamin_b6".to_owned(), Some(0.0)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(4.0)),
+                ("vitamin_d".to_owned(), Some(0.0)),
+                ("vitamin_e".to_owned(), Some(0.1)),
+                ("vitamin_k".to_owned(), Some(0.0)),
+            ]),
             verified: None,
             id: None,
         };
@@ -533,30 +1631,41 @@ mod tests {
             tags: vec!["meyve".to_string()],
             allergens: vec![],
             servings: std::collections::BTreeMap::new(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 60.0,
-            energy: 90.0,
-            carbohydrate: 20.0,
-            protein: 1.0,
-            fat: 0.3,
-            saturated_fat: 0.1,
-            trans_fat: 0.0,
-            sugar: 15.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
-            potassium: 300.0,
-            iron: 0.2,
-            magnesium: 10.0,
-            calcium: 5.0,
-            zinc: 0.15,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.3,
-            vitamin_b12: 0.0,
-            vitamin_c: 10.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.0,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(90.0)),
+                ("carbohydrate".to_owned(), Some(20.0)),
+                ("protein".to_owned(), Some(1.0)),
+                ("fat".to_owned(), Some(0.3)),
+                ("saturated_fat".to_owned(), Some(0.1)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(15.0)),
+                ("fiber".to_owned(), Some(3.0)),
+                ("water".to_owned(), Some(55.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(1.0)),
+                ("potassium".to_owned(), Some(300.0)),
+                ("iron".to_owned(), Some(0.2)),
+                ("magnesium".to_owned(), Some(10.0)),
+                ("calcium".to_owned(), Some(5.0)),
+                ("zinc".to_owned(), Some(0.15)),
+                ("vitamin_a".to_owned(), Some(0.0)),
+                ("vitamin_b6".to_owned(), Some(0.3)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(10.0)),
+                ("vitamin_d".to_owned(), Some(0.0)),
+                ("vitamin_e".to_owned(), Some(0.1)),
+                ("vitamin_k".to_owned(), Some(0.0)),
+            ]),
             verified: None,
             id: None,
         };
@@ -626,30 +1735,41 @@ mod tests {
             tags: vec!["test".to_string()],
             allergens: vec![],
             servings: std::collections::BTreeMap::new(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 50.0,
-            energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(100.0)),
+                ("carbohydrate".to_owned(), Some(20.0)),
+                ("protein".to_owned(), Some(5.0)),
+                ("fat".to_owned(), Some(2.0)),
+                ("saturated_fat".to_owned(), Some(1.0)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(10.0)),
+                ("fiber".to_owned(), Some(3.0)),
+                ("water".to_owned(), Some(55.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(50.0)),
+                ("potassium".to_owned(), Some(200.0)),
+                ("iron".to_owned(), Some(1.0)),
+                ("magnesium".to_owned(), Some(30.0)),
+                ("calcium".to_owned(), Some(10.0)),
+                ("zinc".to_owned(), Some(0.5)),
+                ("vitamin_a".to_owned(), Some(0.1)),
+                ("vitamin_b6".to_owned(), Some(0.2)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(5.0)),
+                ("vitamin_d".to_owned(), None),
+                ("vitamin_e".to_owned(), Some(0.1)),
+                ("vitamin_k".to_owned(), Some(0.05)),
+            ]),
             verified: None,
             id: None,
         };
@@ -663,13 +1783,687 @@ mod tests {
         // Temel field'ları kontrol et
         assert_eq!(result.slug, Some("test-food".to_owned()));
         assert_eq!(result.description, "Test Food");
-        assert_eq!(result.energy, 100.0);
+        assert_eq!(result.nutrient("energy"), Some(100.0));
         assert_eq!(result.glycemic_index, 50.0);
 
+        // Bilinmeyen değer 0'a dönüşmemeli, ölçülmüş 0 da bilinmiyor olarak okunmamalı
+        assert_eq!(result.nutrient("vitamin_d"), None);
+        assert_eq!(result.nutrient("vitamin_b12"), Some(0.0));
+
         info!("select_food_by_slug basic testi geçti.");
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_insert_food_with_nutrient_definitions() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        // Sonradan tanımlanan besinler de JSON'dan düz alanlar olarak okunabilmeli
+        let food: Food = serde_json::from_value(serde_json::json!({
+            "description": "Kahve",
+            "image_url": "/kahve.jpg",
+            "source": "test_source",
+            "tags": [],
+            "allergens": [],
+            "servings": {},
+            "glycemic_index": 0.0,
+            "energy": 2.0,
+            "caffeine": 40.0,
+            "vitamin_c": null
+        }))?;
+        insert_food(&pool, food).await?;
+
+        let result = select_food_by_slug(&pool, "kahve".to_owned()).await?;
+        assert_eq!(result.nutrient("caffeine"), Some(40.0));
+        assert_eq!(result.nutrient("vitamin_c"), None);
+
+        // Yanıtta tüm tanımlı besinler, bilinmeyenler null olacak şekilde eski düz yapıda yer almalı
+        let json = serde_json::to_value(&result)?;
+        assert_eq!(json["caffeine"], 40.0);
+        assert!(json["folate"].is_null());
+        assert_eq!(
+            result.nutrients.len(),
+            select_all_nutrients(&pool).await?.len()
+        );
+
+        // Tanımı olmayan bir besin yazım hatası olabileceği için kabul edilmemeli
+        let food: Food = serde_json::from_value(serde_json::json!({
+            "description": "Hatalı",
+            "image_url": "/hatali.jpg",
+            "source": "test_source",
+            "tags": [],
+            "allergens": [],
+            "servings": {},
+            "glycemic_index": 0.0,
+            "enrgy": 2.0
+        }))?;
+        assert!(insert_food(&pool, food).await.is_err());
+        assert!(
+            select_food_by_slug(&pool, "hatali".to_owned())
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_select_food_provenance() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        let food: Food = serde_json::from_value(serde_json::json!({
+            "description": "Mercimek",
+            "image_url": "/mercimek.jpg",
+            "source": "test_source",
+            "tags": [],
+            "allergens": [],
+            "servings": {},
+            "glycemic_index": 26.0,
+            "energy": 358.0,
+            "protein": 23.9,
+            "provenance": {
+                "energy": { "method": "borrowed", "source": "usda", "confidence": "high" },
+                "protein": { "method": "lab_analysis", "min": 22.0, "max": 25.5, "std_dev": 0.8 }
+            }
+        }))?;
+        let food_id = insert_food(&pool, food).await?.id.unwrap();
+
+        let provenance = select_food_provenance(&pool, food_id).await?;
+        assert_eq!(provenance.len(), 2);
+        assert_eq!(provenance["energy"].source, Some("usda".to_owned()));
+        assert_eq!(provenance["energy"].confidence, Some(Confidence::High));
+        assert_eq!(provenance["protein"].method, Method::LabAnalysis);
+        assert_eq!(provenance["protein"].max, Some(25.5));
+
+        // Aralığın dışında kalan bir değer kabul edilmemeli
+        let food: Food = serde_json::from_value(serde_json::json!({
+            "description": "Hatalı Mercimek",
+            "image_url": "/mercimek.jpg",
+            "source": "test_source",
+            "tags": [],
+            "allergens": [],
+            "servings": {},
+            "glycemic_index": 26.0,
+            "protein": 30.0,
+            "provenance": { "protein": { "method": "lab_analysis", "max": 25.5 } }
+        }))?;
+        assert!(insert_food(&pool, food).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upsert_and_select_sources() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        // Kaynak yemekle birlikte sadece ismiyle açılıyor, detayları sonradan JSON'dan geliyor
+        let food: Food = serde_json::from_value(serde_json::json!({
+            "description": "Elma",
+            "image_url": "/elma.jpg",
+            "source": "usda",
+            "tags": [],
+            "allergens": [],
+            "servings": {},
+            "glycemic_index": 36.0,
+            "energy": 52.0
+        }))?;
+        insert_food(&pool, food).await?;
+
+        let source: Source = serde_json::from_value(serde_json::json!({
+            "description": "usda",
+            "title": "FoodData Central",
+            "url": "https://fdc.nal.usda.gov/",
+            "year": 2019,
+            "license": "CC0 1.0"
+        }))?;
+        upsert_source(&pool, &source).await?;
+
+        let sources = select_all_sources(&pool).await?;
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].title, Some("FoodData Central".to_owned()));
+        assert!(sources[0].foods.is_none());
+
+        let source = select_source_by_id(&pool, sources[0].id.unwrap()).await?;
+        assert_eq!(source.year, Some(2019));
+        assert_eq!(source.foods, Some(vec!["elma".to_owned()]));
+
+        let elma = select_food_by_slug(&pool, "elma".to_owned()).await?;
+        assert_eq!(elma.source_id, source.id);
+
+        assert!(select_source_by_id(&pool, 999).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tag_hierarchy() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        let tag = |description: &str, parent: Option<&str>| Tag {
+            description: description.to_owned(),
+            parent: parent.map(str::to_owned),
+            name: Some(description.to_uppercase()),
+            ..Default::default()
+        };
+        // Alt etiket, üst etiketten önce tanımlanabilmeli
+        upsert_tag(&pool, &tag("narenciye", Some("meyve"))).await?;
+        upsert_tag(&pool, &tag("meyve", None)).await?;
+        assert!(
+            upsert_tag(&pool, &tag("sebze", Some("sebze")))
+                .await
+                .is_err()
+        );
+
+        let food = |description: &str, tags: &[&str]| Food {
+            description: description.to_owned(),
+            image_url: "/test.jpg".to_owned(),
+            source: "test".to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+        insert_food(&pool, food("Portakal", &["narenciye"])).await?;
+        insert_food(&pool, food("Elma", &["meyve"])).await?;
+        insert_food(&pool, food("Brokoli", &["sebze"])).await?;
+
+        let tags = select_all_tags(&pool).await?;
+        let meyve = tags.iter().find(|tag| tag.description == "meyve").unwrap();
+        assert_eq!(meyve.name, Some("MEYVE".to_owned()));
+        assert_eq!(meyve.food_count, 2);
+        let narenciye = tags
+            .iter()
+            .find(|tag| tag.description == "narenciye")
+            .unwrap();
+        assert_eq!(narenciye.parent, Some("meyve".to_owned()));
+        assert_eq!(narenciye.food_count, 1);
+
+        let meyve = select_tag(&pool, "meyve").await?;
+        assert_eq!(meyve.children, Some(vec!["narenciye".to_owned()]));
+        assert_eq!(
+            meyve.foods,
+            Some(vec!["elma".to_owned(), "portakal".to_owned()])
+        );
+        // Handler olmayan etiketi veritabanı hatalarından bu şekilde ayırıyor
+        assert!(matches!(
+            select_tag(&pool, "yok")
+                .await
+                .unwrap_err()
+                .downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::RowNotFound)
+        ));
+
+        // Üst etiketle yapılan arama alt etiketli yemekleri de bulmalı
+        let foods =
+            search_foods(&pool, &SearchQuery::Tag("meyve"), &SearchFilter::default()).await?;
+        assert_eq!(foods.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_foods_with_tag_filter() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        upsert_tag(
+            &pool,
+            &Tag {
+                description: "narenciye".to_owned(),
+                parent: Some("meyve".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let food = |description: &str, tags: &[&str]| Food {
+            description: description.to_owned(),
+            image_url: "/test.jpg".to_owned(),
+            source: "test".to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+        insert_food(&pool, food("Muz", &["meyve", "tropikal", "tatlı"])).await?;
+        insert_food(&pool, food("Ananas", &["meyve", "tropikal"])).await?;
+        insert_food(&pool, food("Portakal", &["narenciye", "asitli"])).await?;
+        insert_food(&pool, food("Brokoli", &["sebze", "lif"])).await?;
+        insert_food(&pool, food("Yulaf", &["tahıl", "lifli"])).await?;
+
+        let slugs = |foods: Vec<Food>| -> Vec<String> {
+            let mut slugs: Vec<String> = foods.into_iter().filter_map(|food| food.slug).collect();
+            slugs.sort();
+            slugs
+        };
+        let filter = |all: &[&str], any: &[&str], none: &[&str]| SearchFilter {
+            all: all.iter().map(|tag| tag.to_string()).collect(),
+            any: any.iter().map(|tag| tag.to_string()).collect(),
+            none: none.iter().map(|tag| tag.to_string()).collect(),
+            expression: None,
+        };
+
+        // Meyve etiketi narenciye alt etiketini de kapsıyor
+        let foods = search_foods(
+            &pool,
+            &SearchQuery::Everything,
+            &filter(&["meyve"], &[], &["tatlı"]),
+        )
+        .await?;
+        assert_eq!(slugs(foods), vec!["ananas", "portakal"]);
+
+        let foods = search_foods(
+            &pool,
+            &SearchQuery::Everything,
+            &filter(&["meyve", "tropikal"], &[], &[]),
+        )
+        .await?;
+        assert_eq!(slugs(foods), vec!["ananas", "muz"]);
+
+        // Birebir eşleşme: lif, lifli etiketini kapsamıyor
+        let foods = search_foods(
+            &pool,
+            &SearchQuery::Everything,
+            &filter(&[], &["lif", "asitli"], &[]),
+        )
+        .await?;
+        assert_eq!(slugs(foods), vec!["brokoli", "portakal"]);
+
+        // İsim aramasıyla birlikte kullanılabilmeli
+        let foods = search_foods(
+            &pool,
+            &SearchQuery::Description("a", "a".to_owned()),
+            &filter(&[], &[], &["tropikal"]),
+        )
+        .await?;
+        assert_eq!(slugs(foods), vec!["portakal", "yulaf"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_foods_with_filter_expression() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        let food =
+            |description: &str, tags: &[&str], allergens: &[&str], protein: Option<f64>| Food {
+                description: description.to_owned(),
+                image_url: "/test.jpg".to_owned(),
+                source: "test".to_owned(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                allergens: allergens
+                    .iter()
+                    .map(|allergen| allergen.to_string())
+                    .collect(),
+                glycemic_index: 40.0,
+                nutrients: BTreeMap::from([("protein".to_owned(), protein)]),
+                ..Default::default()
+            };
+        insert_food(&pool, food("Brokoli", &["sebze"], &[], Some(2.8))).await?;
+        insert_food(&pool, food("Mercimek", &["baklagil"], &[], Some(24.0))).await?;
+        insert_food(&pool, food("Makarna", &["tahıl"], &["gluten"], Some(13.0))).await?;
+        insert_food(&pool, food("Ispanak", &["sebze"], &[], None)).await?;
+
+        let search = |filter: &str| {
+            let filter = SearchFilter {
+                expression: Some(crate::core::filter::parse_filter(filter).unwrap()),
+                ..Default::default()
+            };
+            let pool = pool.clone();
+            async move {
+                let mut slugs: Vec<String> = search_foods(&pool, &SearchQuery::Everything, &filter)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .filter_map(|food| food.slug)
+                    .collect();
+                slugs.sort();
+                slugs
+            }
+        };
+
+        assert_eq!(
+            search("protein >= 10 and not allergen:gluten").await,
+            vec!["mercimek"]
+        );
+        assert_eq!(
+            search("tag:sebze or (protein > 20 and glycemic_index < 50)").await,
+            vec!["brokoli", "ispanak", "mercimek"]
+        );
+        // Proteini bilinmeyen ıspanak karşılaştırmayı sağlamıyor, değillemesini sağlıyor
+        assert_eq!(search("protein < 5").await, vec!["brokoli"]);
+        assert_eq!(search("not protein >= 5").await, vec!["brokoli", "ispanak"]);
+        // Değerler her zaman parametre olarak bağlanıyor
+        assert!(search("tag:\"x') OR 1=1 --\"").await.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_foods_with_stemming_and_synonyms() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        for description in ["Elma", "Kayısı Kurusu", "Patlıcan Kebabı", "Kabak"] {
+            insert_food(
+                &pool,
+                Food {
+                    description: description.to_owned(),
+                    image_url: "/test.jpg".to_owned(),
+                    source: "test".to_owned(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+        let synonyms = Synonyms::new(vec![
+            vec!["kayısı".to_owned(), "zerdali".to_owned()],
+            vec!["patlıcan".to_owned(), "badımcan".to_owned()],
+        ]);
+        update_search_texts(&pool, &synonyms).await?;
+
+        let search = |q: &'static str| {
+            let pool = pool.clone();
+            let normalized = synonyms.normalize(q);
+            async move {
+                let mut slugs: Vec<String> = search_foods(
+                    &pool,
+                    &SearchQuery::Description(q, normalized),
+                    &SearchFilter::default(),
+                )
+                .await
+                .unwrap()
+                .into_iter()
+                .filter_map(|food| food.slug)
+                .collect();
+                slugs.sort();
+                slugs
+            }
+        };
+
+        assert_eq!(search("elmalar").await, vec!["elma"]);
+        assert_eq!(search("kabakları").await, vec!["kabak"]);
+        assert_eq!(search("zerdali").await, vec!["kayisi-kurusu"]);
+        assert_eq!(search("badımcan kebap").await, vec!["patlican-kebabi"]);
+        // Sadeleştirilmiş sorgu boş kalırsa tüm yemekler dönmemeli
+        assert!(search("?").await.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upsert_translations_and_translated_search() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        for (description, tags) in [("Elma", vec!["meyve"]), ("Ayran", vec!["içecek"])] {
+            insert_food(
+                &pool,
+                Food {
+                    description: description.to_owned(),
+                    image_url: "/test.jpg".to_owned(),
+                    source: "test".to_owned(),
+                    tags: tags.into_iter().map(str::to_owned).collect(),
+                    servings: BTreeMap::from([("Adet (Orta)".to_owned(), 180.0)]),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+
+        let translation = |kind, key: &str, text: &str| Translation {
+            kind,
+            key: key.to_owned(),
+            translations: BTreeMap::from([("en".to_owned(), text.to_owned())]),
+        };
+        upsert_translation(&pool, &translation(TranslationKind::Food, "elma", "Apple")).await?;
+        upsert_translation(
+            &pool,
+            &translation(TranslationKind::Food, "elma", "Red Apple"),
+        )
+        .await?;
+        upsert_translation(&pool, &translation(TranslationKind::Tag, "meyve", "fruit")).await?;
+        upsert_translation(
+            &pool,
+            &translation(TranslationKind::Serving, "Adet (Orta)", "Piece (Medium)"),
+        )
+        .await?;
+        // Olmayan kayıtlar ve desteklenmeyen diller hata veriyor
+        assert!(
+            upsert_translation(&pool, &translation(TranslationKind::Food, "armut", "Pear"))
+                .await
+                .is_err()
+        );
+        assert!(
+            upsert_translation(
+                &pool,
+                &Translation {
+                    kind: TranslationKind::Tag,
+                    key: "meyve".to_owned(),
+                    translations: BTreeMap::from([("de".to_owned(), "Obst".to_owned())]),
+                },
+            )
+            .await
+            .is_err()
+        );
+
+        let translations = select_translations(&pool).await?;
+        assert_eq!(translations.len(), 3);
+        assert_eq!(
+            translations.get(TranslationKind::Food, Language::En, "elma"),
+            Some("Red Apple")
+        );
+
+        let search = |query: SearchQuery<'static>| {
+            let pool = pool.clone();
+            async move {
+                search_foods(&pool, &query, &SearchFilter::default())
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .filter_map(|food| food.slug)
+                    .collect::<Vec<String>>()
+            }
+        };
+        assert_eq!(
+            search(SearchQuery::TranslatedDescription("apple", Language::En)).await,
+            vec!["elma"]
+        );
+        // Türkçe isimler de İngilizce aramada bulunuyor
+        assert_eq!(
+            search(SearchQuery::TranslatedDescription("ayran", Language::En)).await,
+            vec!["ayran"]
+        );
+        assert_eq!(
+            search(SearchQuery::TranslatedTag("fruit", Language::En)).await,
+            vec!["elma"]
+        );
+        assert!(
+            search(SearchQuery::Description("apple", "apple".to_owned()))
+                .await
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_food_summaries() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        let food = |description: &str, nutrients: &[(&str, f64)]| Food {
+            description: description.to_owned(),
+            image_url: "/test.jpg".to_owned(),
+            source: "test".to_owned(),
+            nutrients: nutrients
+                .iter()
+                .map(|(name, value)| (name.to_string(), Some(*value)))
+                .collect(),
+            ..Default::default()
+        };
+        insert_food(
+            &pool,
+            food(
+                "Mercimek",
+                &[("energy", 352.0), ("protein", 24.0), ("fiber", 11.0)],
+            ),
+        )
+        .await?;
+        insert_food(&pool, food("Mercimek Çorbası", &[])).await?;
+
+        let summaries = search_food_summaries(
+            &pool,
+            &SearchQuery::Description("mercimek", "mercimek".to_owned()),
+            &SearchFilter::default(),
+        )
+        .await?;
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries[0],
+            FoodSummary {
+                slug: "mercimek".to_owned(),
+                description: "Mercimek".to_owned(),
+                image_url: "/test.jpg".to_owned(),
+                energy: Some(352.0),
+                protein: Some(24.0),
+                ..Default::default()
+            }
+        );
+        // Besin değeri olmayan yemekler de özetlerde dönmeli
+        assert_eq!(summaries[1].energy, None);
+
+        // Tam sonuçlarla aynı koşullar kullanılıyor
+        let foods = search_foods(
+            &pool,
+            &SearchQuery::Description("çorba", "corba".to_owned()),
+            &SearchFilter::default(),
+        )
+        .await?;
+        let summaries = search_food_summaries(
+            &pool,
+            &SearchQuery::Description("çorba", "corba".to_owned()),
+            &SearchFilter::default(),
+        )
+        .await?;
+        assert_eq!(foods.len(), summaries.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_facets() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        let food = |description: &str, tags: &[&str], allergens: &[&str], protein: f64| Food {
+            description: description.to_owned(),
+            image_url: "/test.jpg".to_owned(),
+            source: "test".to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            allergens: allergens
+                .iter()
+                .map(|allergen| allergen.to_string())
+                .collect(),
+            nutrients: BTreeMap::from([("protein".to_owned(), Some(protein))]),
+            ..Default::default()
+        };
+        insert_food(&pool, food("Brokoli", &["sebze", "vegan"], &[], 2.8)).await?;
+        insert_food(&pool, food("Mercimek", &["baklagil", "vegan"], &[], 24.0)).await?;
+        insert_food(&pool, food("Makarna", &["tahıl"], &["gluten"], 13.0)).await?;
+
+        let facets =
+            search_facets(&pool, &SearchQuery::Everything, &SearchFilter::default()).await?;
+        assert_eq!(facets.total, 3);
+        assert_eq!(facets.tags.get("vegan"), Some(&2));
+        assert_eq!(facets.allergens.get("gluten"), Some(&1));
+        assert_eq!(facets.diets.get("vegan"), Some(&2));
+        assert_eq!(facets.diets.get("glutensiz"), Some(&2));
+        assert_eq!(facets.diets.get("yuksek_protein"), Some(&1));
+        let protein: Vec<i64> = facets.protein.iter().map(|bucket| bucket.count).collect();
+        assert_eq!(protein, vec![0, 1, 0, 1, 1]);
+
+        // Facet'ler aramayla aynı koşullar üzerinden hesaplanıyor
+        let filter = SearchFilter {
+            none: vec!["tahıl".to_owned()],
+            ..Default::default()
+        };
+        let facets = search_facets(
+            &pool,
+            &SearchQuery::Description("e", "e".to_owned()),
+            &filter,
+        )
+        .await?;
+        assert_eq!(facets.total, 1);
+        assert_eq!(facets.tags.get("baklagil"), Some(&1));
+        assert!(facets.allergens.is_empty());
+
+        let facets = search_facets(&pool, &SearchQuery::Tag("yok"), &filter).await?;
+        assert_eq!(facets.total, 0);
+        assert_eq!(facets.diets.get("vegan"), Some(&0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_select_food_relations() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        let food = |description: &str, parent: Option<&str>, variant_of: Option<&str>| Food {
+            description: description.to_owned(),
+            image_url: "/test.jpg".to_owned(),
+            source: "test".to_owned(),
+            parent: parent.map(str::to_owned),
+            variant_of: variant_of.map(str::to_owned),
+            ..Default::default()
+        };
+        let foods = vec![
+            food("Fuji Elma", Some("elma"), None),
+            food("Elma", None, None),
+            food("Tavuk Göğsü (Haşlanmış)", None, Some("tavuk-gogsu-cig")),
+            food("Tavuk Göğsü (Izgara)", None, Some("tavuk-gogsu-cig")),
+            food("Tavuk Göğsü (Çiğ)", None, None),
+            // Varyantın varyantı olamaz, gruplar tek seviyeli
+            food("Tavuk Göğsü (Kızartma)", None, Some("tavuk-gogsu-izgara")),
+        ];
+        for food in &foods {
+            insert_food(&pool, food.clone()).await?;
+        }
+        // Fuji Elma, Elma'dan önce eklendiği için ilişkiler yemeklerden sonra kuruluyor
+        for food in &foods[..5] {
+            insert_food_relations(&pool, food).await?;
+        }
+        assert!(insert_food_relations(&pool, &foods[5]).await.is_err());
+        assert!(
+            insert_food_relations(&pool, &food("Elma", Some("armut"), None))
+                .await
+                .is_err()
+        );
+
+        let fuji = select_food_by_slug(&pool, "fuji-elma".to_owned()).await?;
+        assert_eq!(fuji.parent, Some("elma".to_owned()));
+
+        let elma = select_food_by_slug(&pool, "elma".to_owned()).await?;
+        assert_eq!(
+            select_food_children(&pool, elma.id.unwrap()).await?,
+            vec!["fuji-elma".to_owned()]
+        );
+
+        let haslanmis = select_food_by_slug(&pool, "tavuk-gogsu-haslanmis".to_owned()).await?;
+        assert_eq!(haslanmis.variant_of, Some("tavuk-gogsu-cig".to_owned()));
+        assert_eq!(
+            select_food_variants(&pool, haslanmis.id.unwrap()).await?,
+            vec![
+                "tavuk-gogsu-cig".to_owned(),
+                "tavuk-gogsu-izgara".to_owned()
+            ]
+        );
+
+        let cig = select_food_by_slug(&pool, "tavuk-gogsu-cig".to_owned()).await?;
+        assert_eq!(
+            select_food_variants(&pool, cig.id.unwrap()).await?,
+            vec![
+                "tavuk-gogsu-haslanmis".to_owned(),
+                "tavuk-gogsu-izgara".to_owned()
+            ]
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_select_food_by_slug_not_found() -> Result<(), Error> {
         let pool = SqlitePool::connect("sqlite::memory:").await?;
@@ -697,30 +2491,41 @@ mod tests {
             tags: vec!["fruit".to_string()],
             allergens: vec![],
             servings: std::collections::BTreeMap::new(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 40.0,
-            energy: 52.0,
-            carbohydrate: 14.0,
-            protein: 0.3,
-            fat: 0.2,
-            saturated_fat: 0.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 2.4,
-            water: 86.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
-            potassium: 107.0,
-            iron: 0.1,
-            magnesium: 5.0,
-            calcium: 6.0,
-            zinc: 0.1,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.0,
-            vitamin_b12: 0.0,
-            vitamin_c: 4.6,
-            vitamin_d: 0.0,
-            vitamin_e: 0.2,
-            vitamin_k: 0.0,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(52.0)),
+                ("carbohydrate".to_owned(), Some(14.0)),
+                ("protein".to_owned(), Some(0.3)),
+                ("fat".to_owned(), Some(0.2)),
+                ("saturated_fat".to_owned(), Some(0.0)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(10.0)),
+                ("fiber".to_owned(), Some(2.4)),
+                ("water".to_owned(), Some(86.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(1.0)),
+                ("potassium".to_owned(), Some(107.0)),
+                ("iron".to_owned(), Some(0.1)),
+                ("magnesium".to_owned(), Some(5.0)),
+                ("calcium".to_owned(), Some(6.0)),
+                ("zinc".to_owned(), Some(0.1)),
+                ("vitamin_a".to_owned(), Some(0.0)),
+                ("vitamin_b6".to_owned(), Some(0.0)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(4.6)),
+                ("vitamin_d".to_owned(), Some(0.0)),
+                ("vitamin_e".to_owned(), Some(0.2)),
+                ("vitamin_k".to_owned(), Some(0.0)),
+            ]),
             verified: None,
             id: None,
         };
@@ -733,30 +2538,41 @@ mod tests {
             tags: vec!["fruit".to_string()],
             allergens: vec![],
             servings: std::collections::BTreeMap::new(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 51.0,
-            energy: 89.0,
-            carbohydrate: 23.0,
-            protein: 1.1,
-            fat: 0.3,
-            saturated_fat: 0.1,
-            trans_fat: 0.0,
-            sugar: 12.0,
-            fiber: 2.6,
-            water: 75.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
-            potassium: 358.0,
-            iron: 0.3,
-            magnesium: 27.0,
-            calcium: 5.0,
-            zinc: 0.2,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.4,
-            vitamin_b12: 0.0,
-            vitamin_c: 8.7,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.0,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(89.0)),
+                ("carbohydrate".to_owned(), Some(23.0)),
+                ("protein".to_owned(), Some(1.1)),
+                ("fat".to_owned(), Some(0.3)),
+                ("saturated_fat".to_owned(), Some(0.1)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(12.0)),
+                ("fiber".to_owned(), Some(2.6)),
+                ("water".to_owned(), Some(75.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(1.0)),
+                ("potassium".to_owned(), Some(358.0)),
+                ("iron".to_owned(), Some(0.3)),
+                ("magnesium".to_owned(), Some(27.0)),
+                ("calcium".to_owned(), Some(5.0)),
+                ("zinc".to_owned(), Some(0.2)),
+                ("vitamin_a".to_owned(), Some(0.0)),
+                ("vitamin_b6".to_owned(), Some(0.4)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(8.7)),
+                ("vitamin_d".to_owned(), Some(0.0)),
+                ("vitamin_e".to_owned(), Some(0.1)),
+                ("vitamin_k".to_owned(), Some(0.0)),
+            ]),
             verified: None,
             id: None,
         };
@@ -770,10 +2586,10 @@ mod tests {
         let banana = select_food_by_slug(&pool, "banana".to_owned()).await?;
 
         assert_eq!(apple.description, "Apple");
-        assert_eq!(apple.energy, 52.0);
+        assert_eq!(apple.nutrient("energy"), Some(52.0));
         assert_eq!(banana.description, "Banana");
-        assert_eq!(banana.energy, 89.0);
-        assert_eq!(banana.potassium, 358.0);
+        assert_eq!(banana.nutrient("energy"), Some(89.0));
+        assert_eq!(banana.nutrient("potassium"), Some(358.0));
 
         info!("select_food_by_slug multiple foods testi geçti.");
         Ok(())
@@ -793,30 +2609,41 @@ mod tests {
             tags: vec!["test".to_string()],
             allergens: vec![],
             servings: std::collections::BTreeMap::new(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 50.0,
-            energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(100.0)),
+                ("carbohydrate".to_owned(), Some(20.0)),
+                ("protein".to_owned(), Some(5.0)),
+                ("fat".to_owned(), Some(2.0)),
+                ("saturated_fat".to_owned(), Some(1.0)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(10.0)),
+                ("fiber".to_owned(), Some(3.0)),
+                ("water".to_owned(), Some(55.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(50.0)),
+                ("potassium".to_owned(), Some(200.0)),
+                ("iron".to_owned(), Some(1.0)),
+                ("magnesium".to_owned(), Some(30.0)),
+                ("calcium".to_owned(), Some(10.0)),
+                ("zinc".to_owned(), Some(0.5)),
+                ("vitamin_a".to_owned(), Some(0.1)),
+                ("vitamin_b6".to_owned(), Some(0.2)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(5.0)),
+                ("vitamin_d".to_owned(), Some(0.0)),
+                ("vitamin_e".to_owned(), Some(0.1)),
+                ("vitamin_k".to_owned(), Some(0.05)),
+            ]),
             verified: None,
             id: None,
         };
@@ -846,30 +2673,41 @@ mod tests {
             tags: vec!["test".to_string()],
             allergens: vec!["nuts".to_string()], // Bu relation tablolarına eklenmeli
             servings: [("100g".to_string(), 100.0)].iter().cloned().collect(),
+            ingredients: None,
+            density: None,
+            provenance: BTreeMap::new(),
+            source_id: None,
+            source_url: None,
+            parent: None,
+            variant_of: None,
+            children: vec![],
+            variants: vec![],
             glycemic_index: 50.0,
-            energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(100.0)),
+                ("carbohydrate".to_owned(), Some(20.0)),
+                ("protein".to_owned(), Some(5.0)),
+                ("fat".to_owned(), Some(2.0)),
+                ("saturated_fat".to_owned(), Some(1.0)),
+                ("trans_fat".to_owned(), Some(0.0)),
+                ("sugar".to_owned(), Some(10.0)),
+                ("fiber".to_owned(), Some(3.0)),
+                ("water".to_owned(), Some(55.0)),
+                ("cholesterol".to_owned(), Some(0.0)),
+                ("sodium".to_owned(), Some(50.0)),
+                ("potassium".to_owned(), Some(200.0)),
+                ("iron".to_owned(), Some(1.0)),
+                ("magnesium".to_owned(), Some(30.0)),
+                ("calcium".to_owned(), Some(10.0)),
+                ("zinc".to_owned(), Some(0.5)),
+                ("vitamin_a".to_owned(), Some(0.1)),
+                ("vitamin_b6".to_owned(), Some(0.2)),
+                ("vitamin_b12".to_owned(), Some(0.0)),
+                ("vitamin_c".to_owned(), Some(5.0)),
+                ("vitamin_d".to_owned(), Some(0.0)),
+                ("vitamin_e".to_owned(), Some(0.1)),
+                ("vitamin_k".to_owned(), Some(0.05)),
+            ]),
             verified: None,
             id: None,
         };
@@ -881,7 +2719,7 @@ mod tests {
 
         // Temel field'lar
         assert_eq!(food.description, "Relations Test");
-        assert_eq!(food.energy, 100.0);
+        assert_eq!(food.nutrient("energy"), Some(100.0));
 
         // Relations boş olabilir (relation tabloları yok)
         assert!(food.allergens.is_empty() || food.allergens.len() <= 1);
@@ -891,4 +2729,117 @@ mod tests {
         info!("select_food relations basic testi geçti.");
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_insert_recipe_and_select_ingredients() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        // Tarifin malzemelerini ekle
+        let mercimek = Food {
+            description: "Mercimek".to_string(),
+            image_url: "/mercimek.jpg".to_string(),
+            source: "test_source".to_string(),
+            tags: vec!["baklagil".to_string()],
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(360.0)),
+                ("water".to_owned(), Some(10.0)),
+            ]),
+            ..Default::default()
+        };
+        let su = Food {
+            description: "Su".to_string(),
+            image_url: "/su.jpg".to_string(),
+            source: "test_source".to_string(),
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(0.0)),
+                ("water".to_owned(), Some(100.0)),
+            ]),
+            ..Default::default()
+        };
+        insert_food(&pool, mercimek).await?;
+        insert_food(&pool, su).await?;
+
+        let recipe: Recipe = serde_json::from_str(
+            r#"
+            {
+                "description": "Test Çorbası",
+                "image_url": "/corba.jpg",
+                "source": "test_source",
+                "tags": ["çorba"],
+                "servings": {"Kase": 250},
+                "yield_factor": 0.5,
+                "ingredients": [
+                    {"slug": "mercimek", "weight": 100},
+                    {"slug": "su", "weight": 300}
+                ]
+            }
+        "#,
+        )?;
+        insert_recipe(&pool, recipe).await?;
+
+        let food = select_food_by_slug(&pool, "test-corbasi".to_owned()).await?;
+        assert_eq!(food.nutrient("energy"), Some(180.0));
+        assert_eq!(food.nutrient("water"), Some(55.0));
+
+        let ingredients = food.ingredients.expect("Tarifin malzemeleri dönmeli");
+        assert_eq!(ingredients.len(), 2);
+        assert_eq!(ingredients[0].slug, "mercimek");
+        assert_eq!(ingredients[1].weight, 300.0);
+
+        // Normal yemeklerde malzeme listesi olmamalı
+        let mercimek = select_food_by_slug(&pool, "mercimek".to_owned()).await?;
+        assert!(mercimek.ingredients.is_none());
+
+        // Olmayan bir malzeme ile tarif eklenememeli
+        let recipe = Recipe {
+            description: "Eksik Tarif".to_string(),
+            yield_factor: 1.0,
+            ingredients: vec![crate::core::recipe::RecipeIngredient {
+                slug: "olmayan".to_string(),
+                weight: 100.0,
+            }],
+            ..Default::default()
+        };
+        assert!(insert_recipe(&pool, recipe).await.is_err());
+
+        info!("insert_recipe testi geçti.");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_foods() -> Result<(), Error> {
+        use futures_util::TryStreamExt;
+
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+
+        let food = |slug: &str, verified: bool| Food {
+            slug: Some(slug.to_owned()),
+            description: slug.to_owned(),
+            image_url: format!("/{}.jpg", slug),
+            source: "test_source".to_owned(),
+            verified: Some(verified),
+            ..Default::default()
+        };
+        insert_food(&pool, food("elma", true)).await?;
+        insert_food(&pool, food("muz", false)).await?;
+        insert_food(&pool, food("armut", true)).await?;
+
+        // Yemekler eklenme sırasıyla geliyor, onaylanmamışlar sadece istendiğinde dahil ediliyor
+        let query = export_foods_query(false);
+        let foods: Vec<Food> = stream_foods(&pool, &query).try_collect().await?;
+        let slugs: Vec<&str> = foods
+            .iter()
+            .filter_map(|food| food.slug.as_deref())
+            .collect();
+        assert_eq!(slugs, vec!["elma", "armut"]);
+
+        let query = export_foods_query(true);
+        let foods: Vec<Food> = stream_foods(&pool, &query).try_collect().await?;
+        assert_eq!(foods.len(), 3);
+        assert_eq!(foods[1].verified, Some(false));
+
+        Ok(())
+    }
 }
@@ -1,10 +1,19 @@
 use std::collections::BTreeMap;
 
-use axum::{extract::State, Json};
+use axum::{extract::State, response::Response};
 
-use crate::SharedState;
+use crate::{
+    SharedState,
+    api::{
+        error::APIError,
+        format::{ResponseFormat, format_response},
+    },
+};
 
-pub(crate) async fn endpoints(State(shared_state): State<SharedState>) -> Json<BTreeMap<&'static str, String>> {
+pub(crate) async fn endpoints(
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
     // Henüz test etmedim ama ne olur ne olmaz diye to_owned atıyorum birkaç ms olsa bile config'e blok atılmaması için
     let api_base_url = &shared_state.config.lock().await.api.base_url.to_owned();
     let mut endpoints: BTreeMap<&'static str, String> = BTreeMap::new();
@@ -15,14 +24,54 @@ pub(crate) async fn endpoints(State(shared_state): State<SharedState>) -> Json<B
         format!("{}/{}", &api_base_url, "foods/list"),
     );
     endpoints.insert("get_food_url", format!("{}/{}", api_base_url, "food/{slug}"));
+    endpoints.insert(
+        "food_substitutes_url",
+        format!(
+            "{}/{}",
+            api_base_url, "food/{slug}/substitutes?goal={less_sugar, more_protein, less_sodium}&limit={limit}"
+        ),
+    );
+    endpoints.insert(
+        "parse_ingredients_url",
+        format!("{}/{}", api_base_url, "parse"),
+    );
+    endpoints.insert(
+        "autocomplete_food_url",
+        format!("{}/{}", api_base_url, "foods/autocomplete?q={query}&limit={limit}"),
+    );
     endpoints.insert(
         "search_food_url",
         format!(
             "{}/{}",
-            api_base_url, "foods/search?q={query}&mode={description, tag}&limit={limit}"
+            api_base_url, "foods/search?q={query}&mode={description, tag}&limit={limit}&collapse={true, false}&all={tags}&any={tags}&none={tags}&filter={expression}&facets={true, false}&view={summary, full}"
         ),
     );
+    endpoints.insert(
+        "search_click_url",
+        format!("{}/{}", api_base_url, "foods/search/click"),
+    );
+    endpoints.insert(
+        "export_foods_url",
+        format!("{}/{}", api_base_url, "foods/export.ndjson"),
+    );
+    endpoints.insert(
+        "optimize_meal_url",
+        format!("{}/{}", api_base_url, "meals/optimize"),
+    );
     endpoints.insert("show_all_tags", format!("{}/{}", api_base_url, "tags"));
+    endpoints.insert("get_tag_url", format!("{}/{}", api_base_url, "tags/{tag}"));
+    endpoints.insert(
+        "show_all_sources",
+        format!("{}/{}", api_base_url, "sources"),
+    );
+    endpoints.insert(
+        "get_source_url",
+        format!("{}/{}", api_base_url, "sources/{id}"),
+    );
+    endpoints.insert(
+        "show_all_nutrients",
+        format!("{}/{}", api_base_url, "nutrients"),
+    );
 
-    Json(endpoints)
+    format_response(format, &endpoints)
 }
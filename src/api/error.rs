@@ -3,61 +3,411 @@ use std::fmt::{Display, Formatter};
 use axum::{
     body::Body,
     extract::Request,
-    http::{self, HeaderMap, HeaderValue, header::CONTENT_TYPE},
+    http::{self, HeaderMap, HeaderValue, header::{ACCEPT, CONTENT_TYPE}},
     middleware::Next,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use http::StatusCode;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
-#[derive(Serialize, Deserialize)]
+use crate::{
+    api::{
+        format::{ResponseFormat, encode, request_format},
+        language::request_language,
+    },
+    core::language::Language,
+};
+
+// İstemcilerin mesaj metinlerini karşılaştırmak yerine kullanabileceği, dilden bağımsız ve değişmeyen hata kodları
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum ErrorCode {
+    EndpointNotFound,
+    ClientError,
+    ServerError,
+    RateLimited,
+    DatabaseError,
+    InternalError,
+    ValidationFailed,
+    UnsupportedLanguage,
+    UnsupportedFormat,
+    InvalidCsvOptions,
+    InvalidSlug,
+    InvalidCharacters,
+    QueryTooLong,
+    MissingQuery,
+    InvalidSearchMode,
+    SearchLimitExceeded,
+    SuggestionLimitExceeded,
+    InvalidFilter,
+    TooManyTagFilters,
+    UnknownNutrient,
+    UnknownUnit,
+    InvalidAmount,
+    UnitNotConvertible,
+    FoodNotFound,
+    FoodUnverified,
+    TagNotFound,
+    SourceNotFound,
+    TooManyLines,
+    TooManyConstraints,
+    InvalidEnergyTarget,
+    NoMatchingFoods,
+    MealInfeasible,
+    AdminDisabled,
+    InvalidAdminToken,
+    InvalidReportRange,
+    ExportBusy,
+}
+
+impl ErrorCode {
+    // ClientError ve ServerError axum'un kendi hatalarından geliyor, onların durum kodu yanıttan alınıyor
+    pub(crate) fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::EndpointNotFound
+            | ErrorCode::FoodNotFound
+            | ErrorCode::TagNotFound
+            | ErrorCode::SourceNotFound
+            | ErrorCode::NoMatchingFoods => StatusCode::NOT_FOUND,
+            ErrorCode::FoodUnverified | ErrorCode::AdminDisabled => StatusCode::FORBIDDEN,
+            ErrorCode::InvalidAdminToken => StatusCode::UNAUTHORIZED,
+            ErrorCode::UnitNotConvertible | ErrorCode::MealInfeasible => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::ExportBusy => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::ServerError | ErrorCode::DatabaseError | ErrorCode::InternalError => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    // Mesajlardaki {} yerine hatayla birlikte verilen değer yazılıyor
+    pub(crate) fn message(&self, language: Language) -> &'static str {
+        match (self, language) {
+            (ErrorCode::EndpointNotFound, Language::Tr) => "İstenen API endpoint'i bulunamadı",
+            (ErrorCode::EndpointNotFound, Language::En) => {
+                "The requested API endpoint was not found"
+            }
+            (ErrorCode::ClientError, Language::Tr) => "İstemci Hatası: {}",
+            (ErrorCode::ClientError, Language::En) => "Client Error: {}",
+            (ErrorCode::ServerError, Language::Tr) => "Sunucu Hatası: {}",
+            (ErrorCode::ServerError, Language::En) => "Server Error: {}",
+            (ErrorCode::RateLimited, Language::Tr) => {
+                "Çok fazla istek gönderdiniz, lütfen biraz bekleyin"
+            }
+            (ErrorCode::RateLimited, Language::En) => "Too many requests, please slow down",
+            (ErrorCode::DatabaseError, Language::Tr) => {
+                "Veritabanı sorgusu yapılırken hata oluştu"
+            }
+            (ErrorCode::DatabaseError, Language::En) => "A database query failed",
+            (ErrorCode::InternalError, Language::Tr) => "Sunucuda beklenmeyen bir hata oluştu",
+            (ErrorCode::InternalError, Language::En) => "An unexpected server error occurred",
+            (ErrorCode::ValidationFailed, Language::Tr) => {
+                "Gönderdiğiniz parametrelerde {} hata bulundu"
+            }
+            (ErrorCode::ValidationFailed, Language::En) => "Found {} invalid parameters",
+            (ErrorCode::UnsupportedLanguage, Language::Tr) => {
+                "Desteklenmeyen bir dil girdiniz, desteklenen diller: tr, en"
+            }
+            (ErrorCode::UnsupportedLanguage, Language::En) => {
+                "Unsupported language, supported languages: tr, en"
+            }
+            (ErrorCode::UnsupportedFormat, Language::Tr) => {
+                "Desteklenmeyen bir yanıt biçimi girdiniz, desteklenen biçimler: json, csv, msgpack, cbor"
+            }
+            (ErrorCode::UnsupportedFormat, Language::En) => {
+                "Unsupported response format, supported formats: json, csv, msgpack, cbor"
+            }
+            (ErrorCode::InvalidCsvOptions, Language::Tr) => {
+                "CSV ayracı comma veya semicolon, ondalık ayracı point veya comma olmalı"
+            }
+            (ErrorCode::InvalidCsvOptions, Language::En) => {
+                "The CSV separator must be comma or semicolon and the decimal mark point or comma"
+            }
+            (ErrorCode::InvalidSlug, Language::Tr) => {
+                "Slug en az 1 karakter, en fazla 100 karakterden oluşabilir"
+            }
+            (ErrorCode::InvalidSlug, Language::En) => {
+                "Slugs must be between 1 and 100 characters long"
+            }
+            (ErrorCode::InvalidCharacters, Language::Tr) => "Sorgu geçersiz karakterler içeriyor",
+            (ErrorCode::InvalidCharacters, Language::En) => {
+                "The query contains invalid characters"
+            }
+            (ErrorCode::QueryTooLong, Language::Tr) => "Gönderdiğiniz sorgu {} bayt limitini aşıyor!",
+            (ErrorCode::QueryTooLong, Language::En) => "The query exceeds the {} byte limit!",
+            (ErrorCode::MissingQuery, Language::Tr) => "Arama sorgusu veya filtre girilmeli",
+            (ErrorCode::MissingQuery, Language::En) => "A search query or a filter is required",
+            (ErrorCode::InvalidSearchMode, Language::Tr) => {
+                "Geçersiz arama modu, description veya tag olmalı"
+            }
+            (ErrorCode::InvalidSearchMode, Language::En) => {
+                "Invalid search mode, must be description or tag"
+            }
+            (ErrorCode::SearchLimitExceeded, Language::Tr) => "Arama limitini geçtiniz!",
+            (ErrorCode::SearchLimitExceeded, Language::En) => "The search limit was exceeded!",
+            (ErrorCode::SuggestionLimitExceeded, Language::Tr) => "Öneri limitini geçtiniz!",
+            (ErrorCode::SuggestionLimitExceeded, Language::En) => {
+                "The suggestion limit was exceeded!"
+            }
+            (ErrorCode::InvalidFilter, Language::Tr) => "Geçersiz filtre: {}",
+            (ErrorCode::InvalidFilter, Language::En) => "Invalid filter: {}",
+            (ErrorCode::TooManyTagFilters, Language::Tr) => {
+                "En fazla {} etiket ile filtreleme yapabilirsiniz"
+            }
+            (ErrorCode::TooManyTagFilters, Language::En) => "You can filter by at most {} tags",
+            (ErrorCode::UnknownNutrient, Language::Tr) => "Bilinmeyen besin değeri: {}",
+            (ErrorCode::UnknownNutrient, Language::En) => "Unknown nutrient: {}",
+            (ErrorCode::UnknownUnit, Language::Tr) => "Bilinmeyen bir birim girdiniz",
+            (ErrorCode::UnknownUnit, Language::En) => "Unknown unit",
+            (ErrorCode::InvalidAmount, Language::Tr) => "Miktar 0 ile 10000 arasında olmalı",
+            (ErrorCode::InvalidAmount, Language::En) => "The amount must be between 0 and 10000",
+            (ErrorCode::UnitNotConvertible, Language::Tr) => {
+                "Bu yemek için girilen birim grama çevrilemiyor"
+            }
+            (ErrorCode::UnitNotConvertible, Language::En) => {
+                "The unit cannot be converted to grams for this food"
+            }
+            (ErrorCode::FoodNotFound, Language::Tr) => "Bu yemekle ilgili veriye ulaşılamadı",
+            (ErrorCode::FoodNotFound, Language::En) => "No data was found for this food",
+            (ErrorCode::FoodUnverified, Language::Tr) => {
+                "Bu yemek henüz onaylanmadığı için gösterilemiyor"
+            }
+            (ErrorCode::FoodUnverified, Language::En) => {
+                "This food cannot be shown because it has not been verified yet"
+            }
+            (ErrorCode::TagNotFound, Language::Tr) => "Bu etikete ulaşılamadı",
+            (ErrorCode::TagNotFound, Language::En) => "This tag was not found",
+            (ErrorCode::SourceNotFound, Language::Tr) => "Bu kaynağa ulaşılamadı",
+            (ErrorCode::SourceNotFound, Language::En) => "This source was not found",
+            (ErrorCode::TooManyLines, Language::Tr) => {
+                "En fazla 50 satır ve satır başına en fazla 100 karakter gönderilebilir"
+            }
+            (ErrorCode::TooManyLines, Language::En) => {
+                "At most 50 lines of at most 100 characters each can be sent"
+            }
+            (ErrorCode::TooManyConstraints, Language::Tr) => {
+                "Gönderdiğiniz etiket, alerjen veya besin sınırı sayısı limiti aşıyor"
+            }
+            (ErrorCode::TooManyConstraints, Language::En) => {
+                "Too many tags, allergens or nutrient limits were sent"
+            }
+            (ErrorCode::InvalidEnergyTarget, Language::Tr) => {
+                "Enerji hedefi pozitif bir sayı olmalı"
+            }
+            (ErrorCode::InvalidEnergyTarget, Language::En) => {
+                "The energy target must be a positive number"
+            }
+            (ErrorCode::NoMatchingFoods, Language::Tr) => {
+                "Seçilen etiket ve alerjenlere uyan yemek bulunamadı"
+            }
+            (ErrorCode::NoMatchingFoods, Language::En) => {
+                "No foods match the selected tags and allergens"
+            }
+            (ErrorCode::MealInfeasible, Language::Tr) => {
+                "Bu hedef ve sınırlara uyan bir öğün planı bulunamadı"
+            }
+            (ErrorCode::MealInfeasible, Language::En) => {
+                "No meal plan satisfies these targets and limits"
+            }
+            (ErrorCode::AdminDisabled, Language::Tr) => "Yönetici erişimi bu sunucuda kapalı",
+            (ErrorCode::AdminDisabled, Language::En) => {
+                "Admin access is disabled on this server"
+            }
+            (ErrorCode::InvalidAdminToken, Language::Tr) => "Geçersiz yönetici anahtarı",
+            (ErrorCode::InvalidAdminToken, Language::En) => "Invalid admin token",
+            (ErrorCode::InvalidReportRange, Language::Tr) => {
+                "Gün sayısı 1 ile 365, limit en fazla 100 olabilir"
+            }
+            (ErrorCode::InvalidReportRange, Language::En) => {
+                "Days must be between 1 and 365 and the limit at most 100"
+            }
+            (ErrorCode::ExportBusy, Language::Tr) => {
+                "Şu anda çok fazla dışa aktarım yapılıyor, lütfen daha sonra tekrar deneyin"
+            }
+            (ErrorCode::ExportBusy, Language::En) => {
+                "Too many exports are running, please try again later"
+            }
+        }
+    }
+
+    fn render(&self, language: Language, argument: Option<&str>) -> String {
+        let message = self.message(language);
+        match argument {
+            Some(argument) => message.replace("{}", argument),
+            None => message.to_owned(),
+        }
+    }
+}
+
+// Birden fazla parametre hatalı olduğunda her biri için ayrı bir kayıt dönüyoruz
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct ErrorDetail {
+    pub(crate) field: String,
+    pub(crate) error: ErrorCode,
+    pub(crate) message: String,
+    #[serde(skip)]
+    argument: Option<String>,
+}
+
+impl ErrorDetail {
+    pub(crate) fn new(field: &str, error: APIError) -> Self {
+        ErrorDetail {
+            field: field.to_owned(),
+            error: error.error,
+            message: error.message,
+            argument: error.argument,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct APIError {
     // StatusCode kullanmak yerine u16 olarak saklama sebebimiz deserialize ve serialize fonksiyonlarını kullanabilmek
     pub(crate) code: u16,
+    pub(crate) error: ErrorCode,
+    // Varsayılan olarak Türkçe, middleware isteğin diline göre yeniden oluşturuyor
     pub(crate) message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) details: Vec<ErrorDetail>,
+    #[serde(skip)]
+    argument: Option<String>,
+}
+
+// RFC 9457 problem+json gövdesi, hata kodu ve detaylar ek alan olarak ekleniyor
+#[derive(Serialize)]
+struct Problem<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    title: &'a str,
+    status: u16,
+    detail: &'a str,
+    error: ErrorCode,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    details: &'a [ErrorDetail],
 }
 
 impl APIError {
-    pub(crate) fn new(code: StatusCode, message: &str) -> Self {
+    pub(crate) fn new(error: ErrorCode) -> Self {
+        APIError {
+            code: error.status().as_u16(),
+            error,
+            message: error.render(Language::default(), None),
+            details: Vec::new(),
+            argument: None,
+        }
+    }
+
+    pub(crate) fn with_argument(error: ErrorCode, argument: &str) -> Self {
+        APIError {
+            message: error.render(Language::default(), Some(argument)),
+            argument: Some(argument.to_owned()),
+            ..APIError::new(error)
+        }
+    }
+
+    // Tek hata varsa kendisi, birden fazla hata varsa ValidationFailed dönüyor, iki durumda da tüm hatalar details'te
+    pub(crate) fn validation(mut details: Vec<ErrorDetail>) -> Self {
+        let mut error = match details.as_slice() {
+            [detail] => APIError {
+                code: detail.error.status().as_u16(),
+                error: detail.error,
+                message: detail.message.to_owned(),
+                details: Vec::new(),
+                argument: detail.argument.to_owned(),
+            },
+            _ => APIError::with_argument(ErrorCode::ValidationFailed, &details.len().to_string()),
+        };
+        error.details.append(&mut details);
+        error
+    }
+
+    // axum'un kendi hataları ve rate limiter gibi katmanlardan gelen, gövdesi JSON olmayan hatalar için
+    pub(crate) fn from_status(status: StatusCode) -> Self {
+        let error = match status {
+            StatusCode::NOT_FOUND => ErrorCode::EndpointNotFound,
+            StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+            status if status.is_server_error() => ErrorCode::ServerError,
+            _ => ErrorCode::ClientError,
+        };
+        let reason = status.canonical_reason().unwrap_or("Tanımsız Davranış");
         APIError {
-            code: code.as_u16(),
-            message: message.to_owned(),
+            code: status.as_u16(),
+            ..APIError::with_argument(error, reason)
+        }
+    }
+
+    pub(crate) fn localize(&mut self, language: Language) {
+        self.message = self.error.render(language, self.argument.as_deref());
+        for detail in self.details.iter_mut() {
+            detail.message = detail.error.render(language, detail.argument.as_deref());
         }
     }
 
     pub(crate) async fn not_found_handler() -> impl IntoResponse {
-        APIError::new(StatusCode::NOT_FOUND, "İstenen API endpoint'i bulunamadı")
+        APIError::new(ErrorCode::EndpointNotFound)
     }
-}
 
-impl IntoResponse for APIError {
-    fn into_response(self) -> axum::response::Response {
+    // problem+json sadece JSON istendiğinde kullanılıyor, MessagePack ve CBOR'da hata olduğu gibi kodlanıyor
+    fn render(self, format: ResponseFormat, problem: bool) -> Response {
         let status = match StatusCode::from_u16(self.code) {
             Ok(code) => code,
             _ => StatusCode::INTERNAL_SERVER_ERROR, // Varsayılan INTERNAL_SERVER_ERROR kullanıyoruz eğer kod geçersizse
         };
 
+        let body = match format {
+            ResponseFormat::Json | ResponseFormat::Csv(_) if problem => {
+                let problem = Problem {
+                    kind: "about:blank",
+                    title: status.canonical_reason().unwrap_or_default(),
+                    status: status.as_u16(),
+                    detail: &self.message,
+                    error: self.error,
+                    details: &self.details,
+                };
+                serde_json::to_vec(&problem)
+                    .map(|body| ("application/problem+json", body))
+                    .map_err(anyhow::Error::from)
+            }
+            format => encode(format, &self),
+        };
+
         // Serde başarısız olursa manuel yapıyoruz? Tehlikeli olabilri mi bu kod
-        let body = serde_json::to_string(&self).unwrap_or_else(|e| {
+        let (content_type, body) = body.unwrap_or_else(|e| {
             error!(
                 "Serde JSON serileştirme başarısız oldu: {}\nDetaylar: {}",
                 self, e
             );
 
-            format!(
-                // {{ ve }} kullanıyoruz escape etmek için
-                r#"{{"code": {}, "message": "Serde JSON serileştirme başarısız oldu: {}"}}"#,
-                status.as_u16(),
-                self.message.replace("\"", "\\\""), // Özel karakterleri escape ediyoruz
+            (
+                "application/json",
+                format!(
+                    // {{ ve }} kullanıyoruz escape etmek için
+                    r#"{{"code": {}, "message": "Serde JSON serileştirme başarısız oldu: {}"}}"#,
+                    status.as_u16(),
+                    self.message.replace("\"", "\\\""), // Özel karakterleri escape ediyoruz
+                )
+                .into_bytes(),
             )
         });
 
         // JSON için ve belki gelecekte başka değeler için header açalım
         let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+        // Hata middleware'i isteğin diline ve Accept başlığına göre yanıtı yeniden oluşturabilsin diye hatayı da ekliyoruz
+        let mut response = (status, headers, body).into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
 
-        (status, headers, body).into_response()
+impl IntoResponse for APIError {
+    fn into_response(self) -> Response {
+        self.render(ResponseFormat::Json, false)
     }
 }
 
@@ -68,22 +418,40 @@ impl Display for APIError {
 }
 
 // Middleware için hata işleyicisi, axum'un çok detaylı hata döndürmesini istemiyoruz güvenlik açığına sebep olabileceği için.
-pub(crate) async fn handle_axum_rejections(
-    request: Request<Body>,
-    next: Next,
-) -> Result<impl IntoResponse, APIError> {
-    let response = next.run(request).await;
+// Ayrıca tüm hata mesajları burada isteğin diline çevriliyor ve istenen biçimde veya problem+json olarak dönüyor
+pub(crate) async fn handle_axum_rejections(request: Request<Body>, next: Next) -> Response {
+    // Geçersiz lang parametresinin hatası da bu middleware'den geçiyor, o durumda Türkçe dönüyoruz
+    let language = request_language(request.uri(), request.headers()).unwrap_or_default();
+    let problem = request
+        .headers()
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/problem+json"));
+    // Geçersiz format parametresinin hatası JSON dönüyor
+    let format = request_format(request.uri(), request.headers()).unwrap_or_default();
+    let binary = matches!(format, ResponseFormat::MsgPack | ResponseFormat::Cbor);
+
+    let mut response = next.run(request).await;
+
+    // Handler'ların döndürdüğü hatalar
+    if let Some(mut error) = response.extensions_mut().remove::<APIError>() {
+        if language == Language::default() && !problem && !binary {
+            return response;
+        }
+        error.localize(language);
+        return error.render(format, problem);
+    }
 
-    // Eğer zaten APIError'ün oluşturduğu bir response ise, yani JSON formatındaysa direkt döndürebiliriz
+    // Eğer JSON formatındaysa başarılı bir yanıt, direkt döndürebiliriz
     if match response.headers().get(CONTENT_TYPE) {
         Some(content_type) => content_type == HeaderValue::from_static("application/json"),
         _ => false,
     } {
-        return Ok(response);
+        return response;
     }
     /* let Some içindeki veri aynı koşul içerisinde kullanılamıyormuş mevcut sürümde, üstteki match yapısına geçilmiştir
     https://github.com/rust-lang/rust/issues/53667
-    
+
     if let Some(content_type) = response.headers().get(CONTENT_TYPE)
         && content_type == HeaderValue::from_static("application/json")
     {
@@ -92,20 +460,66 @@ pub(crate) async fn handle_axum_rejections(
 
     // Deserializasyon hatalarını yakalamak için response status kontrolü
     match response.status() {
-        status if status.is_client_error() => Err(APIError::new(
-            status,
-            &(format!(
-                "İstemci Hatası: {}",
-                status.canonical_reason().unwrap_or("Tanımsız Davranış")
-            )),
-        )),
-        status if status.is_server_error() => Err(APIError::new(
-            status,
-            &(format!(
-                "Sunucu Hatası: {}",
-                status.canonical_reason().unwrap_or("Tanımsız Davranış")
-            )),
-        )),
-        _ => Ok(response),
+        status if status.is_client_error() || status.is_server_error() => {
+            let mut error = APIError::from_status(status);
+            error.localize(language);
+            error.render(format, problem)
+        }
+        _ => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_localization() {
+        let mut error = APIError::with_argument(ErrorCode::UnknownNutrient, "sekr");
+        assert_eq!(error.code, 400);
+        assert_eq!(error.message, "Bilinmeyen besin değeri: sekr");
+        error.localize(Language::En);
+        assert_eq!(error.message, "Unknown nutrient: sekr");
+
+        let json = serde_json::to_value(APIError::new(ErrorCode::FoodUnverified)).unwrap();
+        assert_eq!(json["code"], 403);
+        assert_eq!(json["error"], "FOOD_UNVERIFIED");
+        assert!(json.get("details").is_none());
+    }
+
+    #[test]
+    fn test_validation_error() {
+        let single = APIError::validation(vec![ErrorDetail::new(
+            "limit",
+            APIError::new(ErrorCode::SearchLimitExceeded),
+        )]);
+        assert_eq!(single.error, ErrorCode::SearchLimitExceeded);
+        assert_eq!(single.details.len(), 1);
+
+        let mut multiple = APIError::validation(vec![
+            ErrorDetail::new("limit", APIError::new(ErrorCode::SearchLimitExceeded)),
+            ErrorDetail::new("q", APIError::new(ErrorCode::InvalidCharacters)),
+        ]);
+        assert_eq!(multiple.error, ErrorCode::ValidationFailed);
+        assert_eq!(multiple.code, 400);
+        multiple.localize(Language::En);
+        assert_eq!(multiple.message, "Found 2 invalid parameters");
+        assert_eq!(multiple.details[1].field, "q");
+        assert_eq!(
+            multiple.details[1].message,
+            "The query contains invalid characters"
+        );
+    }
+
+    #[test]
+    fn test_rejection_error_codes() {
+        let error = APIError::from_status(StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(error.error, ErrorCode::RateLimited);
+        assert_eq!(error.code, 429);
+
+        let error = APIError::from_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(error.error, ErrorCode::ClientError);
+        assert_eq!(error.code, 415);
+        assert_eq!(error.message, "İstemci Hatası: Unsupported Media Type");
     }
 }
@@ -1,60 +1,285 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, io};
 
 use axum::{
-    Json,
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, HeaderValue, header::CONTENT_TYPE},
+    response::{IntoResponse, Response},
 };
+use futures_util::{StreamExt, stream};
+use tokio::sync::mpsc;
 
 use anyhow::Result;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::error;
 
 use crate::{
     SharedState,
-    api::{database, error::APIError},
-    core::food::Food,
+    api::{
+        admin::require_admin,
+        analytics::ResultCount,
+        database::{self, SearchFilter, SearchQuery},
+        error::{APIError, ErrorCode, ErrorDetail},
+        format::{ResponseFormat, csv_response, format_response},
+    },
+    core::{
+        autocomplete::{MAX_SUGGESTIONS, Suggestion},
+        facet::Facets,
+        filter::parse_filter,
+        food::{Food, FoodSummary, round_nutrient},
+        language::Language,
+        popularity::Popularity,
+        str::to_lower_en,
+        substitute::{Goal, Substitute, find_substitutes},
+        suggest::{DidYouMean, did_you_mean},
+        translation::{TranslationKind, Translations},
+        units::{UNITS, convert_to_grams, find_unit},
+    },
 };
 
+// Porsiyon hesaplamalarında kabul edilen en büyük miktar, 10 kg veya 10 litreden fazlası anlamsız
+const MAX_AMOUNT: f64 = 10000.0;
+
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Detail {
+    #[default]
+    Basic,
+    // Besin değerlerinin kaynak, yöntem ve belirsizlik bilgileriyle birlikte gösterilmesi
+    Full,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FoodParams {
+    amount: Option<f64>,
+    // "yemek_kasigi" veya "su bardağı" gibi, sadeleştirilip birim tablosunda aranıyor
+    unit: Option<String>,
+    #[serde(default)]
+    detail: Detail,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Portion {
+    amount: f64,
+    unit: &'static str,
+    serving: Option<String>,
+    grams: f64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct FoodResponse {
+    #[serde(flatten)]
+    food: Food,
+    // Miktar istendiğinde besin değerleri 100 gram yerine bu porsiyon için hesaplanıyor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    portion: Option<Portion>,
+}
+
 pub(crate) async fn food(
     Path(slug): Path<String>,
+    params: Query<FoodParams>,
+    language: Language,
+    format: ResponseFormat,
     State(shared_state): State<SharedState>,
-) -> Result<Json<Food>, APIError> {
-    // Girilen yemek isminin, istediğimiz limitler içinde olduğuna emin olalım, DoS'a karşı karakter limiti ekleyelim.
-    if slug.is_empty() || slug.len() > 100 {
-        return Err(APIError::new(
-            StatusCode::BAD_REQUEST,
-            "Slug en az 1 karakter, en fazla 100 karakterden oluşabilir",
+) -> Result<Response, APIError> {
+    validate_slug(&slug)?;
+
+    // Yemeği sorgulamadan önce birimin ve miktarın geçerli olduğundan emin oluyoruz, ikisi de hatalıysa birlikte dönüyoruz
+    let mut details = Vec::new();
+    let unit = params.unit.as_ref().and_then(|unit| {
+        let unit = find_unit(&to_lower_en(&unit.replace('_', " ")));
+        if unit.is_none() {
+            details.push(ErrorDetail::new(
+                "unit",
+                APIError::new(ErrorCode::UnknownUnit),
+            ));
+        }
+        unit
+    });
+
+    if params
+        .amount
+        .is_some_and(|amount| !amount.is_finite() || amount <= 0.0 || amount > MAX_AMOUNT)
+    {
+        details.push(ErrorDetail::new(
+            "amount",
+            APIError::new(ErrorCode::InvalidAmount),
         ));
     }
 
-    sanitize_input(&slug)?;
+    if !details.is_empty() {
+        return Err(APIError::validation(details));
+    }
 
-    let mut food = database::select_food_by_slug(&*shared_state.api_db.lock().await, slug)
-        .await
-        .map_err(|e| {
+    let mut food = {
+        let db = &*shared_state.api_db.lock().await;
+        let mut food = database::select_food_by_slug(db, slug).await.map_err(|e| {
             error!("Veritabanı yemek bilgisi sorgularken hata oluştu: {:?}", e);
-            APIError::new(
-                StatusCode::NOT_FOUND,
-                "Bu yemekle ilgili veriye ulaşılamadı",
-            )
+            APIError::new(ErrorCode::FoodNotFound)
         })?;
 
-    fix_image_url(&State(shared_state), &mut food).await;
+        if !food.verified.is_some_and(|verified| verified) {
+            return Err(APIError::new(ErrorCode::FoodUnverified));
+        }
 
-    if food.verified.is_some_and(|verified| verified) {
-        Ok(Json(food))
-    } else {
-        Err(APIError::new(
-            StatusCode::FORBIDDEN,
-            "Bu yemek henüz onaylanmadığı için gösterilemiyor",
-        ))
+        let food_id = food.id.unwrap_or_default();
+        let relatives = async {
+            Ok::<_, anyhow::Error>((
+                database::select_food_children(db, food_id).await?,
+                database::select_food_variants(db, food_id).await?,
+            ))
+        }
+        .await;
+        (food.children, food.variants) = relatives.map_err(|e| {
+            error!(
+                "Veritabanı yemeğin alt türlerini ve varyantlarını sorgularken hata oluştu: {:?}",
+                e
+            );
+            APIError::new(ErrorCode::DatabaseError)
+        })?;
+
+        if params.detail == Detail::Full {
+            food.provenance = database::select_food_provenance(db, food.id.unwrap_or_default())
+                .await
+                .map_err(|e| {
+                    error!(
+                        "Veritabanı besin kaynaklarını sorgularken hata oluştu: {:?}",
+                        e
+                    );
+                    APIError::new(ErrorCode::DatabaseError)
+                })?;
+        }
+
+        food
+    };
+
+    fix_food_url(&State(shared_state.clone()), &mut food).await;
+
+    if params.amount.is_none() && unit.is_none() {
+        shared_state
+            .translations
+            .translate_food(&mut food, language);
+        return format_response(
+            format,
+            &FoodResponse {
+                food,
+                portion: None,
+            },
+        );
+    }
+
+    // Sadece miktar girildiyse gram, sadece birim girildiyse 1 birim kastediliyor
+    let amount = params.amount.unwrap_or(1.0);
+    let unit = unit.unwrap_or(&UNITS[1]); // Gram
+    let converted = convert_to_grams(&food, amount, unit, None)
+        .ok_or_else(|| APIError::new(ErrorCode::UnitNotConvertible))?;
+
+    let factor = converted.grams / 100.0;
+    for value in food.nutrients.values_mut().flatten() {
+        *value = round_nutrient(*value * factor);
+    }
+    for provenance in food.provenance.values_mut() {
+        provenance.scale(factor);
+    }
+
+    // Porsiyon isimleri grama çevirirken Türkçe aranıyor, çeviriyi en sonda yapıyoruz
+    let translations = &shared_state.translations;
+    translations.translate_food(&mut food, language);
+    format_response(
+        format,
+        &FoodResponse {
+            food,
+            portion: Some(Portion {
+                amount,
+                unit: unit.name,
+                serving: converted
+                    .serving
+                    .map(|serving| translations.serving(language, serving)),
+                grams: round_nutrient(converted.grams),
+            }),
+        },
+    )
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SubstituteParams {
+    goal: Goal,
+    limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SubstitutesResponse {
+    food: String,
+    goal: Goal,
+    nutrient: &'static str,
+    serving: String,
+    serving_weight: f64,
+    substitutes: Vec<Substitute>,
+}
+
+pub(crate) async fn food_substitutes(
+    Path(slug): Path<String>,
+    params: Query<SubstituteParams>,
+    language: Language,
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    validate_slug(&slug)?;
+
+    let limit = params.limit.unwrap_or(5);
+    if limit > shared_state.config.lock().await.api.search_max_limit {
+        return Err(APIError::new(ErrorCode::SearchLimitExceeded));
+    }
+
+    let (food, candidates) = {
+        let db = &*shared_state.api_db.lock().await;
+        let food = database::select_food_by_slug(db, slug).await.map_err(|e| {
+            error!("Veritabanı yemek bilgisi sorgularken hata oluştu: {:?}", e);
+            APIError::new(ErrorCode::FoodNotFound)
+        })?;
+
+        if !food.verified.is_some_and(|verified| verified) {
+            return Err(APIError::new(ErrorCode::FoodUnverified));
+        }
+
+        let candidates = database::select_all_verified_foods(db).await.map_err(|e| {
+            error!(
+                "Veritabanı alternatif yemekleri sorgularken hata oluştu: {:?}",
+                e
+            );
+            APIError::new(ErrorCode::DatabaseError)
+        })?;
+
+        (food, candidates)
+    };
+
+    let mut substitutes = find_substitutes(&food, &candidates, params.goal);
+    substitutes.truncate(limit as usize);
+    for substitute in substitutes.iter_mut() {
+        shared_state
+            .translations
+            .translate_substitute(substitute, language);
     }
+
+    let (serving, serving_weight) = food.typical_serving();
+    let serving = shared_state.translations.serving(language, serving);
+    format_response(
+        format,
+        &SubstitutesResponse {
+            food: food.slug.unwrap_or_default(),
+            goal: params.goal,
+            nutrient: params.goal.nutrient(),
+            serving,
+            serving_weight,
+            substitutes,
+        },
+    )
 }
 
 pub(crate) async fn foods(
+    format: ResponseFormat,
     State(shared_state): State<SharedState>,
-) -> Json<BTreeMap<&'static str, String>> {
+) -> Result<Response, APIError> {
     // Henüz test etmedim ama ne olur ne olmaz diye to_owned atıyorum birkaç ms olsa bile config'e blok atılmaması için
     let api_base_url = &shared_state.config.lock().await.api.base_url.to_owned();
     let mut endpoints: BTreeMap<&'static str, String> = BTreeMap::new();
@@ -63,21 +288,37 @@ pub(crate) async fn foods(
         "list_all_foods_url",
         format!("{}/{}", &api_base_url, "foods/list"),
     );
+    endpoints.insert(
+        "autocomplete_food_url",
+        format!(
+            "{}/{}",
+            api_base_url, "foods/autocomplete?q={query}&limit={limit}"
+        ),
+    );
     endpoints.insert(
         "search_food_url",
         format!(
             "{}/{}",
-            api_base_url, "foods/search?q={query}&mode={description, tag}&limit={limit}"
+            api_base_url,
+            "foods/search?q={query}&mode={description, tag}&limit={limit}&collapse={true, false}&all={tags}&any={tags}&none={tags}&filter={expression}&facets={true, false}&view={summary, full}"
         ),
     );
 
-    Json(endpoints)
+    format_response(format, &endpoints)
+}
+
+// CSV'de slug ve bağlantı ayrı sütunlarda
+#[derive(Serialize)]
+struct FoodLink<'a> {
+    slug: &'a str,
+    url: &'a str,
 }
 
 // HashMap yerine BTreeMap kullanma sebebimiz, yemek isimlerini alfabetik sıralamak istememiz. HashMap kullansaydık her seferinde rastgele sıralama olacaktı
 pub(crate) async fn foods_list(
+    format: ResponseFormat,
     State(shared_state): State<SharedState>,
-) -> Result<Json<BTreeMap<String, String>>, APIError> {
+) -> Result<Response, APIError> {
     let slugs = database::select_all_foods_slugs(&*shared_state.api_db.lock().await)
         .await
         .map_err(|e| {
@@ -85,27 +326,118 @@ pub(crate) async fn foods_list(
                 "Veritabanı yemek açıklamaları sorgularken hata oluştu: {:?}",
                 e
             );
-            APIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Veritabanı yemek sorgusu yapılırken hata oluştu",
-            )
+            APIError::new(ErrorCode::DatabaseError)
         })?;
 
     let api_base_url = &shared_state.config.lock().await.api.base_url;
 
-    Ok(Json(
-        slugs
-            .into_iter()
-            .map(|slug| slug)
-            // Daha sonra fuji-elma: https://API_BASE.URL/food/food1\n.../food2 şeklinde gösteriyoruz
-            .map(|slug| (slug.clone(), api_base_url.clone() + "/food/" + &slug))
-            .collect(),
-    ))
+    let foods: BTreeMap<String, String> = slugs
+        .into_iter()
+        .map(|slug| slug)
+        // Daha sonra fuji-elma: https://API_BASE.URL/food/food1\n.../food2 şeklinde gösteriyoruz
+        .map(|slug| (slug.clone(), api_base_url.clone() + "/food/" + &slug))
+        .collect();
+
+    match format {
+        ResponseFormat::Csv(options) => csv_response(
+            &foods
+                .iter()
+                .map(|(slug, url)| FoodLink { slug, url })
+                .collect::<Vec<FoodLink>>(),
+            options,
+        ),
+        format => format_response(format, &foods),
+    }
+}
+
+// Dışa aktarımlar bitene kadar bir veritabanı bağlantısı tutuyor, yavaş istemciler havuzu tüketmesin diye sınırlıyoruz
+pub(crate) const MAX_CONCURRENT_EXPORTS: usize = 2;
+// İstemci okumadığında veritabanından en fazla bu kadar yemek önden okunuyor
+const EXPORT_BUFFER: usize = 64;
+
+#[derive(Deserialize)]
+pub(crate) struct ExportParams {
+    // Onaylanmamış yemekler sadece yöneticilere gösteriliyor
+    #[serde(default)]
+    include_unverified: bool,
+}
+
+// Tüm veri seti her satırda bir yemek olacak şekilde (NDJSON) akıtılıyor. Yemekler okundukça gönderildiği için veri seti
+// büyüse de bellek kullanımı artmıyor, cache'e de alınmıyor
+pub(crate) async fn foods_export(
+    params: Query<ExportParams>,
+    headers: HeaderMap,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    let include_unverified = params.include_unverified;
+    if include_unverified {
+        require_admin(&shared_state, &headers).await?;
+    }
+
+    let permit = shared_state
+        .exports
+        .clone()
+        .try_acquire_owned()
+        .map_err(|_| APIError::new(ErrorCode::ExportBusy))?;
+
+    // Kilidi dışa aktarım boyunca tutarsak diğer tüm istekler beklerdi, havuzun bir kopyasıyla okuyoruz
+    let pool = shared_state.api_db.lock().await.clone();
+    let (static_url, base_url) = {
+        let config = shared_state.config.lock().await;
+        (
+            config.api.static_url.to_owned(),
+            config.api.base_url.to_owned(),
+        )
+    };
+
+    // Kanal dolduğunda, yani istemci yavaş okuduğunda veritabanından okuma da bekliyor
+    let (sender, mut receiver) = mpsc::channel::<Result<Vec<u8>, io::Error>>(EXPORT_BUFFER);
+    tokio::spawn(async move {
+        let _permit = permit;
+        let query = database::export_foods_query(include_unverified);
+        let mut foods = database::stream_foods(&pool, &query);
+        while let Some(food) = foods.next().await {
+            let line = match food {
+                Ok(mut food) => {
+                    fix_urls(&mut food, &static_url, &base_url);
+                    serde_json::to_vec(&food).map_err(anyhow::Error::from)
+                }
+                Err(e) => Err(e.into()),
+            };
+
+            // Yanıtın durumu gönderilmiş olduğu için hatada bağlantıyı kesiyoruz, istemci eksik dosyayı tam sanmasın
+            let line = line
+                .map(|mut line| {
+                    line.push(b'\n');
+                    line
+                })
+                .map_err(|e| {
+                    error!("Yemekler dışa aktarılırken hata oluştu: {:?}", e);
+                    io::Error::other(e.to_string())
+                });
+            let failed = line.is_err();
+
+            // İstemci bağlantıyı kapattıysa okumayı bırakıyoruz
+            if sender.send(line).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    Ok((
+        [(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-ndjson"),
+        )],
+        Body::from_stream(stream::poll_fn(move |context| receiver.poll_recv(context))),
+    )
+        .into_response())
 }
 
 pub(crate) async fn tags_list(
+    format: ResponseFormat,
     State(shared_state): State<SharedState>,
-) -> Result<Json<Vec<String>>, APIError> {
+) -> Result<Response, APIError> {
     let tags = database::select_all_tags(&*shared_state.api_db.lock().await)
         .await
         .map_err(|e| {
@@ -113,41 +445,250 @@ pub(crate) async fn tags_list(
                 "Veritabanı etiket açıklamaları sorgularken hata oluştu: {:?}",
                 e
             );
-            APIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Veritabanı etiket sorgusu yapılırken hata oluştu",
-            )
+            APIError::new(ErrorCode::DatabaseError)
         })?;
 
-    Ok(Json(tags))
+    format_response(format, &tags)
 }
 
+pub(crate) async fn tag(
+    Path(tag): Path<String>,
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    // Etiketler slug değil ama aynı uzunluk ve karakter sınırlarını uyguluyoruz
+    validate_slug(&tag)?;
+
+    let tag = database::select_tag(&*shared_state.api_db.lock().await, &tag)
+        .await
+        .map_err(|e| {
+            // Sadece etiket gerçekten yoksa 404 dönüyoruz, diğer hatalar veritabanı hatası
+            if matches!(e.downcast_ref::<sqlx::Error>(), Some(sqlx::Error::RowNotFound)) {
+                return APIError::new(ErrorCode::TagNotFound);
+            }
+            error!("Veritabanı etiket bilgisi sorgularken hata oluştu: {:?}", e);
+            APIError::new(ErrorCode::DatabaseError)
+        })?;
+
+    format_response(format, &tag)
+}
+
+pub(crate) async fn nutrients_list(
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    let nutrients = database::select_all_nutrients(&*shared_state.api_db.lock().await)
+        .await
+        .map_err(|e| {
+            error!(
+                "Veritabanı besin tanımlarını sorgularken hata oluştu: {:?}",
+                e
+            );
+            APIError::new(ErrorCode::DatabaseError)
+        })?;
+
+    format_response(format, &nutrients)
+}
+
+// Etiket filtrelerinde toplamda en fazla bu kadar etiket kabul ediyoruz, her etiket SQL'de ayrı bir alt sorgu oluyor
+const MAX_TAG_FILTERS: usize = 10;
+
 #[derive(Deserialize)]
 pub(crate) struct SearchParams {
-    // Sorgu değeri: q
-    q: String,
+    // Sorgu değeri: q, etiket filtresi girildiyse boş bırakılabilir
+    q: Option<String>,
     mode: Option<String>,
     limit: Option<u64>,
+    // Aynı yemeğin farklı hazırlanış şekillerini tek sonuçta topluyor, diğerleri sonucun variants alanında listeleniyor
+    #[serde(default)]
+    collapse: bool,
+    // Sonuçlarla birlikte etiket, alerjen ve diyet sayılarını ve enerji/protein dağılımını da döndürüyor
+    #[serde(default)]
+    facets: bool,
+    #[serde(default)]
+    view: View,
+}
+
+#[derive(Deserialize, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum View {
+    // Slug, isim, resim, enerji ve makrolar ile tam kaydın bağlantısı
+    #[default]
+    Summary,
+    Full,
+}
+
+// Arama sonuçları tam yemek veya özet olarak dönebiliyor, sıralama ve gruplama ikisinde de aynı şekilde yapılıyor
+pub(crate) trait SearchResult: Serialize {
+    fn description(&self) -> &str;
+    fn slug(&self) -> &str;
+    fn variant_of(&self) -> Option<&str>;
+    fn variants_mut(&mut self) -> &mut Vec<String>;
+    fn verified(&self) -> bool;
+    fn fix_urls(&mut self, static_url: &str, base_url: &str);
+    fn translate(&mut self, translations: &Translations, language: Language);
+}
+
+impl SearchResult for Food {
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn slug(&self) -> &str {
+        self.slug.as_deref().unwrap_or_default()
+    }
+
+    fn variant_of(&self) -> Option<&str> {
+        self.variant_of.as_deref()
+    }
+
+    fn variants_mut(&mut self) -> &mut Vec<String> {
+        &mut self.variants
+    }
+
+    fn verified(&self) -> bool {
+        self.verified.unwrap_or(false)
+    }
+
+    fn fix_urls(&mut self, static_url: &str, base_url: &str) {
+        fix_urls(self, static_url, base_url);
+    }
+
+    fn translate(&mut self, translations: &Translations, language: Language) {
+        translations.translate_food(self, language);
+    }
+}
+
+impl SearchResult for FoodSummary {
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    fn variant_of(&self) -> Option<&str> {
+        self.variant_of.as_deref()
+    }
+
+    fn variants_mut(&mut self) -> &mut Vec<String> {
+        &mut self.variants
+    }
+
+    // Özet sorgusu zaten sadece onaylanmış yemekleri döndürüyor
+    fn verified(&self) -> bool {
+        true
+    }
+
+    fn fix_urls(&mut self, static_url: &str, base_url: &str) {
+        if self.image_url.starts_with("/") {
+            self.image_url = format!("{}{}", static_url, self.image_url);
+        }
+        self.url = format!("{}/food/{}", base_url, self.slug);
+    }
+
+    fn translate(&mut self, translations: &Translations, language: Language) {
+        translations.translate_summary(self, language);
+    }
 }
 
 impl SearchParams {
     fn size(self: &SearchParams) -> usize {
-        let query_size = self.q.len();
+        let query_size = self.q.as_ref().map_or(0, |q| q.len());
         let mode_size = self.mode.as_ref().map_or(0, |m| m.len());
         // SearchParams'ın statik boyutunu da ekliyoruz
         size_of::<SearchParams>() + query_size + mode_size
     }
 }
 
+// Filtreler 96 baytlık sorgu sınırına dahil değil, bunun yerine etiket sayısını, filtre uzunluğunu ve koşul sayısını
+// ayrıca sınırlıyoruz
+#[derive(Deserialize)]
+pub(crate) struct FilterParams {
+    // Virgülle ayrılmış, birebir eşleşen etiket listeleri: all=meyve,tropikal&none=tatlı
+    all: Option<String>,
+    any: Option<String>,
+    none: Option<String>,
+    // "protein >= 10 and tag:sebze and not allergen:gluten" gibi bir filtre ifadesi, core::filter'da ayrıştırılıyor
+    filter: Option<String>,
+}
+
+impl FilterParams {
+    // Hatalı parametrenin adıyla birlikte dönüyor, arama endpoint'i bunu diğer parametre hatalarıyla birleştiriyor
+    fn search_filter(&self) -> Result<SearchFilter, ErrorDetail> {
+        let parse = |field: &str, list: &Option<String>| -> Result<Vec<String>, ErrorDetail> {
+            list.iter()
+                .flat_map(|list| list.split(','))
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(|tag| {
+                    validate_slug(tag).map_err(|e| ErrorDetail::new(field, e))?;
+                    Ok(tag.to_lowercase())
+                })
+                .collect()
+        };
+
+        let expression = self
+            .filter
+            .as_deref()
+            .map(parse_filter)
+            .transpose()
+            .map_err(|e| {
+                ErrorDetail::new(
+                    "filter",
+                    APIError::with_argument(ErrorCode::InvalidFilter, &e.to_string()),
+                )
+            })?;
+
+        let filter = SearchFilter {
+            all: parse("all", &self.all)?,
+            any: parse("any", &self.any)?,
+            none: parse("none", &self.none)?,
+            expression,
+        };
+
+        if filter.all.len() + filter.any.len() + filter.none.len() > MAX_TAG_FILTERS {
+            return Err(ErrorDetail::new(
+                "filter",
+                APIError::with_argument(ErrorCode::TooManyTagFilters, &MAX_TAG_FILTERS.to_string()),
+            ));
+        }
+
+        Ok(filter)
+    }
+}
+
+// facets=true ile istenmediği veya sonuç boş olmadığı sürece eskisi gibi sadece yemek listesi dönüyoruz
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum SearchResponse<T> {
+    Foods(Vec<T>),
+    Detailed {
+        results: Vec<T>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        facets: Option<Facets>,
+        // Sonuç bulunamadığında "bunu mu demek istediniz?" önerileri
+        #[serde(skip_serializing_if = "Option::is_none")]
+        suggestions: Option<DidYouMean>,
+    },
+}
+
 pub(crate) async fn foods_search(
     params: Query<SearchParams>,
+    filter_params: Query<FilterParams>,
+    language: Language,
+    format: ResponseFormat,
     State(shared_state): State<SharedState>,
-) -> Result<Json<Vec<Food>>, APIError> {
+) -> Result<Response, APIError> {
+    // Parametre hataları tek tek dönmek yerine toplanıp details dizisinde birlikte dönüyor
+    let mut details = Vec::new();
+
     // Parametrelerin boyutunun 96 baytı geçmesini beklemiyoruz, DoS tarzı saldırıları önlemek için böyle bir önlem alıyoruz
     if params.size() > 96 {
-        return Err(APIError::new(
-            StatusCode::BAD_REQUEST,
-            "Gönderdiğiniz sorgu 96 bayt limitini aşıyor!",
+        details.push(ErrorDetail::new(
+            "q",
+            APIError::with_argument(ErrorCode::QueryTooLong, "96"),
         ));
     }
 
@@ -156,66 +697,331 @@ pub(crate) async fn foods_search(
         Some(mode) => mode.to_lowercase(),
         None => "description".to_owned(),
     };
+    if !matches!(mode.as_str(), "description" | "name" | "tag") {
+        details.push(ErrorDetail::new(
+            "mode",
+            APIError::new(ErrorCode::InvalidSearchMode),
+        ));
+    }
 
     // Eğer limit girilmemişse ilk 5 sonucu varsayılan olarak döndüreceğiz çünkü arama menülerinde genellikle bu şekilde kullanılıyor
     // Bu limiti daha sonra ekleyeceğiz, sort yapmadan önce eklersek asıl göstermemiz gereken en alakalı yemekleri gösteremeyebiliriz
     let limit = params.limit.unwrap_or(5);
     if limit > shared_state.config.lock().await.api.search_max_limit {
-        return Err(APIError::new(
-            StatusCode::BAD_REQUEST,
-            "Arama limitini geçtiniz!",
+        details.push(ErrorDetail::new(
+            "limit",
+            APIError::new(ErrorCode::SearchLimitExceeded),
         ));
     }
 
-    sanitize_input(&params.q)?;
+    let filter = filter_params.search_filter();
+    if let Err(detail) = &filter {
+        details.push(detail.clone());
+    }
+    if let Some(q) = &params.q {
+        if let Err(e) = sanitize_input(q) {
+            details.push(ErrorDetail::new("q", e));
+        }
+    } else if filter.as_ref().is_ok_and(SearchFilter::is_empty) {
+        details.push(ErrorDetail::new(
+            "q",
+            APIError::new(ErrorCode::MissingQuery),
+        ));
+    }
 
-    let mut foods = match mode.as_str() {
-        // İsim ile aratmada ayrıca sıralıyoruz benzerliğine göre
-        "description" | "name" => {
-            let db = &*shared_state.api_db.lock().await;
-            let mut foods = database::search_foods_by_description_wild(db, &params.q)
-                .await
-                .map_err(|_| {
-                    APIError::new(
-                        StatusCode::NOT_FOUND,
-                        "Veritabanına yemek sorgusu atılırken bir hata oluştu",
-                    )
-                })?;
+    if !details.is_empty() {
+        return Err(APIError::validation(details));
+    }
+    // Filtre hatası varsa yukarıda dönüldü
+    let Ok(filter) = filter else {
+        return Err(APIError::new(ErrorCode::InternalError));
+    };
 
-            // Yemeklerin alakasına göre sıralıyoruz, örneğin query=Elm için 1. Elma, 2. Fuji Elma ... gibi
-            sort_foods_by_query(&mut foods, &params.q).await;
+    // Filtrede yazım hatası olan bir besin hiçbir yemekle eşleşmeyeceği için boş sonuç yerine hata döndürüyoruz
+    if let Some(expression) = &filter.expression {
+        let nutrients = database::select_all_nutrients(&*shared_state.api_db.lock().await)
+            .await
+            .map_err(|e| {
+                error!(
+                    "Veritabanı besin tanımlarını sorgularken hata oluştu: {:?}",
+                    e
+                );
+                APIError::new(ErrorCode::DatabaseError)
+            })?;
+
+        if let Some(nutrient) = expression
+            .nutrients()
+            .into_iter()
+            .find(|nutrient| !nutrients.iter().any(|defined| defined.name == *nutrient))
+        {
+            return Err(APIError::with_argument(
+                ErrorCode::UnknownNutrient,
+                nutrient,
+            ));
+        }
+    }
 
-            Ok(foods)
+    let query = match (params.q.as_deref(), mode.as_str()) {
+        // Arama metni yoksa sadece filtrelere uyan yemekleri döndürüyoruz
+        (None, "description" | "name" | "tag") => SearchQuery::Everything,
+        // Ekler ve eş anlamlılar Türkçeye özel, diğer dillerde çevirilerle birlikte düz arama yapıyoruz
+        (Some(q), "description" | "name") if language != Language::Tr => {
+            SearchQuery::TranslatedDescription(q, language)
         }
+        (Some(q), "description" | "name") => {
+            SearchQuery::Description(q, shared_state.synonyms.normalize(q))
+        }
+        (Some(q), "tag") if language != Language::Tr => SearchQuery::TranslatedTag(q, language),
+        (Some(q), "tag") => SearchQuery::Tag(q),
+        _ => return Err(APIError::new(ErrorCode::InvalidSearchMode)),
+    };
 
-        "tag" => {
-            let db = &*shared_state.api_db.lock().await;
-            let foods = database::search_foods_by_tag_wild(db, &params.q)
+    // Facet'ler limitten bağımsız olarak aramayla eşleşen tüm yemekler üzerinden hesaplanıyor
+    let facets = if params.facets {
+        Some(
+            database::search_facets(&*shared_state.api_db.lock().await, &query, &filter)
                 .await
-                .map_err(|_| {
-                    APIError::new(
-                        StatusCode::NOT_FOUND,
-                        "Etiket ile yemek ararken sonuç bulunamadı",
-                    )
-                })?;
+                .map_err(|e| {
+                    error!(
+                        "Veritabanı arama facet'lerini hesaplarken hata oluştu: {:?}",
+                        e
+                    );
+                    APIError::new(ErrorCode::DatabaseError)
+                })?,
+        )
+    } else {
+        None
+    };
+
+    // Sonuç bulunamaması hata değil, boş liste dönüyor. Buradaki hatalar sadece veritabanı hataları
+    let search_error = |e: anyhow::Error| {
+        error!("Veritabanında yemek ararken hata oluştu: {:?}", e);
+        APIError::new(ErrorCode::DatabaseError)
+    };
 
-            Ok(foods)
+    // Listelerde varsayılan olarak özet dönüyoruz, etiket, alerjen, porsiyon ve tüm besin değerleri view=full ile geliyor
+    Ok(match params.view {
+        View::Summary => {
+            let foods = database::search_food_summaries(
+                &*shared_state.api_db.lock().await,
+                &query,
+                &filter,
+            )
+            .await
+            .map_err(search_error)?;
+            search_response(
+                format,
+                finish_search(
+                    &shared_state,
+                    &params,
+                    &query,
+                    language,
+                    foods,
+                    facets,
+                    limit,
+                )
+                .await?,
+            )?
         }
+        View::Full => {
+            let foods = database::search_foods(&*shared_state.api_db.lock().await, &query, &filter)
+                .await
+                .map_err(search_error)?;
+            search_response(
+                format,
+                finish_search(
+                    &shared_state,
+                    &params,
+                    &query,
+                    language,
+                    foods,
+                    facets,
+                    limit,
+                )
+                .await?,
+            )?
+        }
+    })
+}
 
-        _ => Err(APIError::new(StatusCode::BAD_REQUEST, "Geçersiz sorgu!")),
+// CSV'de sadece sonuçlar dönüyor, facet'ler ve öneriler tabloya sığmadığı için JSON ile alınmalı
+fn search_response<T: SearchResult>(
+    format: ResponseFormat,
+    response: SearchResponse<T>,
+) -> Result<Response, APIError> {
+    let count = match &response {
+        SearchResponse::Foods(results) | SearchResponse::Detailed { results, .. } => results.len(),
+    };
+    let mut response = match (format, response) {
+        (ResponseFormat::Csv(options), SearchResponse::Foods(results))
+        | (ResponseFormat::Csv(options), SearchResponse::Detailed { results, .. }) => {
+            csv_response(&results, options)
+        }
+        (format, response) => format_response(format, &response),
     }?;
+    // Analitik katmanı sonuç sayısını yanıtı tekrar çözmek yerine buradan okuyor
+    response.extensions_mut().insert(ResultCount(count as i64));
+    Ok(response)
+}
+
+// Veritabanından gelen sonuçları sıralayıp gruplayarak yanıtı hazırlıyoruz, tam yemekler ve özetler için aynı
+async fn finish_search<T: SearchResult>(
+    shared_state: &SharedState,
+    params: &SearchParams,
+    query: &SearchQuery<'_>,
+    language: Language,
+    mut foods: Vec<T>,
+    facets: Option<Facets>,
+    limit: u64,
+) -> Result<SearchResponse<T>, APIError> {
+    // Sıralama sorgunun dilindeki isimlere göre yapılması için önce çeviriyoruz
+    foods
+        .iter_mut()
+        .for_each(|food| food.translate(&shared_state.translations, language));
+
+    // İsim ile aratmada ayrıca sıralıyoruz benzerliğine göre, örneğin query=Elm için 1. Elma, 2. Fuji Elma ... gibi
+    if let SearchQuery::Description(q, _) | SearchQuery::TranslatedDescription(q, _) = query {
+        if shared_state.config.lock().await.popularity.enabled {
+            let popularity = shared_state.popularity.lock().await;
+            sort_foods_by_query(&mut foods, q, Some(&popularity)).await;
+        } else {
+            sort_foods_by_query(&mut foods, q, None).await;
+        }
+    }
 
     // Onaylanmamış yemekleri döndürmüyoruz
-    foods.retain(|food| food.verified.unwrap_or(false));
+    foods.retain(|food| food.verified());
+    // Limit, gruplanmış sonuçlar üzerinden uygulanıyor ki varyantlar diğer yemeklerin yerini kaplamasın
+    if params.collapse {
+        foods = collapse_variants(foods);
+    }
+    // Hiç sonuç bulunamadıysa yazım hatası olabileceği için benzer sorgular ve yemekler öneriyoruz
+    let suggestions = if foods.is_empty() {
+        search_suggestions(shared_state, query, language).await?
+    } else {
+        None
+    };
     // Sadece limit kadar yemeğe ihtiyacımız var, gerisini siliyoruz
     foods.truncate(limit as usize);
     // Kalan yemeklerin de resim URL'lerini düzeltiyoruz
-    fix_image_urls(&State(shared_state), &mut foods).await;
+    {
+        let config = shared_state.config.lock().await;
+        foods
+            .iter_mut()
+            .for_each(|food| food.fix_urls(&config.api.static_url, &config.api.base_url));
+    }
 
-    Ok(Json(foods))
+    Ok(match (facets, suggestions) {
+        (None, None) => SearchResponse::Foods(foods),
+        (facets, suggestions) => SearchResponse::Detailed {
+            results: foods,
+            facets,
+            suggestions,
+        },
+    })
 }
 
-fn sanitize_input(s: &str) -> Result<(), APIError> {
+// İsim aramasında yemek isimlerinden, etiket aramasında etiketlerden düzeltme öneriyoruz. Yemek isimleri zaten otomatik
+// tamamlama indeksinde bellekte duruyor, öneriler de aramanın dilinde yapılıyor
+async fn search_suggestions(
+    shared_state: &SharedState,
+    query: &SearchQuery<'_>,
+    language: Language,
+) -> Result<Option<DidYouMean>, APIError> {
+    let translations = &shared_state.translations;
+    let tags: Vec<String> = match query {
+        SearchQuery::Everything => return Ok(None),
+        SearchQuery::Description(..) | SearchQuery::TranslatedDescription(..) => Vec::new(),
+        SearchQuery::Tag(_) | SearchQuery::TranslatedTag(..) => {
+            database::select_all_tags(&*shared_state.api_db.lock().await)
+                .await
+                .map_err(|e| {
+                    error!("Veritabanı etiketleri sorgularken hata oluştu: {:?}", e);
+                    APIError::new(ErrorCode::DatabaseError)
+                })?
+                .into_iter()
+                .map(|tag| {
+                    translations
+                        .get(TranslationKind::Tag, language, &tag.description)
+                        .map_or(tag.description.to_owned(), str::to_owned)
+                })
+                .collect()
+        }
+    };
+
+    let foods: Vec<Suggestion> = {
+        let index = shared_state
+            .autocomplete
+            .read()
+            .map_err(|_| APIError::new(ErrorCode::InternalError))?;
+        index
+            .entries()
+            .iter()
+            .map(|food| Suggestion {
+                slug: food.slug.to_owned(),
+                description: translations
+                    .get(TranslationKind::Food, language, &food.slug)
+                    .map_or(food.description.to_owned(), str::to_owned),
+            })
+            .collect()
+    };
+    let (q, phrases): (&str, Vec<&str>) = match query {
+        SearchQuery::Description(q, _) | SearchQuery::TranslatedDescription(q, _) => (
+            q,
+            foods.iter().map(|food| food.description.as_str()).collect(),
+        ),
+        SearchQuery::Tag(q) | SearchQuery::TranslatedTag(q, _) => {
+            (q, tags.iter().map(String::as_str).collect())
+        }
+        SearchQuery::Everything => return Ok(None),
+    };
+
+    Ok(Some(did_you_mean(q, &phrases, &foods)))
+}
+
+fn validate_slug(slug: &str) -> Result<(), APIError> {
+    // Girilen yemek isminin, istediğimiz limitler içinde olduğuna emin olalım, DoS'a karşı karakter limiti ekleyelim.
+    if slug.is_empty() || slug.len() > 100 {
+        return Err(APIError::new(ErrorCode::InvalidSlug));
+    }
+
+    sanitize_input(slug)
+}
+
+// Yemek isimlerinden uzun bir ön ek aramanın anlamı yok
+const MAX_AUTOCOMPLETE_QUERY: usize = 64;
+
+#[derive(Deserialize)]
+pub(crate) struct AutocompleteParams {
+    q: String,
+    limit: Option<usize>,
+}
+
+// Arama kutusunda her tuş vuruşunda çağrılacağı için veritabanına gitmeden bellekteki ön ek indeksinden cevap veriyoruz
+pub(crate) async fn foods_autocomplete(
+    params: Query<AutocompleteParams>,
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    if params.q.len() > MAX_AUTOCOMPLETE_QUERY {
+        return Err(APIError::with_argument(ErrorCode::QueryTooLong, "64"));
+    }
+
+    let limit = params.limit.unwrap_or(5);
+    if limit > MAX_SUGGESTIONS {
+        return Err(APIError::new(ErrorCode::SuggestionLimitExceeded));
+    }
+
+    let index = shared_state
+        .autocomplete
+        .read()
+        .map_err(|_| APIError::new(ErrorCode::InternalError))?;
+
+    let suggestions: Vec<&Suggestion> = index.lookup(&params.q, limit);
+    format_response(format, &suggestions)
+}
+
+pub(crate) fn sanitize_input(s: &str) -> Result<(), APIError> {
     // Normal bir yemek isminde olmaması gereken karakterler var mı diye de bakalım.
     // Bu karakterler kullanılsa dahi sorun olmaması lazım, yine de önlemimizi alalım.
     if s.contains("..")
@@ -232,44 +1038,67 @@ fn sanitize_input(s: &str) -> Result<(), APIError> {
         || s.contains("\\")
         || s.trim().is_empty()
     {
-        return Err(APIError::new(
-            StatusCode::BAD_REQUEST,
-            "Sorgu geçersiz karakterler içeriyor",
-        ));
+        return Err(APIError::new(ErrorCode::InvalidCharacters));
     }
 
     Ok(())
 }
 
-async fn fix_image_urls(State(shared_state): &State<SharedState>, foods: &mut Vec<Food>) {
-    // Eğer bir yemeğin resim URL'si / ile başlıyorsa, örneğin /images/muz.webp gibi, https://api.besinveri.com/images/muz.webp formatına getirilmeli
-    let base_url = &shared_state.config.lock().await.api.static_url;
-    foods
-        .iter_mut()
-        .filter(|food| food.image_url.starts_with("/"))
-        .for_each(|food| food.image_url = format!("{}{}", base_url, food.image_url));
+async fn fix_food_url(State(shared_state): &State<SharedState>, food: &mut Food) {
+    let config = shared_state.config.lock().await;
+    fix_urls(food, &config.api.static_url, &config.api.base_url);
 }
 
-async fn fix_image_url(State(shared_state): &State<SharedState>, food: &mut Food) {
+fn fix_urls(food: &mut Food, static_url: &str, base_url: &str) {
     // Eğer yemeğin resim URL'si / ile başlıyorsa, örneğin /images/muz.webp gibi, https://api.besinveri.com/images/muz.webp formatına getirilmeli
     if food.image_url.starts_with("/") {
-        let base_url = &shared_state.config.lock().await.api.static_url;
-        food.image_url = format!("{}{}", base_url, food.image_url);
+        food.image_url = format!("{}{}", static_url, food.image_url);
+    }
+
+    // Kaynak sadece isim olarak gösteriliyordu, detaylarına ulaşılabilmesi için kaynağın bağlantısını da ekliyoruz
+    if let Some(source_id) = food.source_id {
+        food.source_url = Some(format!("{}/sources/{}", base_url, source_id));
     }
 }
 
-async fn sort_foods_by_query(foods: &mut Vec<Food>, query: &str) {
+// Sıralı listede her varyant grubunun ilk, yani en alakalı yemeğini bırakıp diğerlerini onun varyantları olarak ekliyoruz
+fn collapse_variants<T: SearchResult>(foods: Vec<T>) -> Vec<T> {
+    let mut collapsed: Vec<T> = Vec::new();
+    let mut groups: BTreeMap<String, usize> = BTreeMap::new();
+
+    for food in foods {
+        let slug = food.slug().to_owned();
+        let base = food.variant_of().unwrap_or(&slug).to_owned();
+
+        match groups.get(&base) {
+            Some(&idx) => collapsed[idx].variants_mut().push(slug),
+            None => {
+                groups.insert(base, collapsed.len());
+                collapsed.push(food);
+            }
+        }
+    }
+
+    collapsed
+}
+
+// Aynı puanı alan yemekler popülerliklerine göre, popülerlik verilmemişse veya eşitse veritabanındaki sıralarına göre diziliyor
+pub(crate) async fn sort_foods_by_query<T: SearchResult>(
+    foods: &mut Vec<T>,
+    query: &str,
+    popularity: Option<&Popularity>,
+) {
     let query = query.to_lowercase();
 
     // (original_index, yemek ref, skor)
-    let mut scored: Vec<(usize, Food, u64)> = foods
+    let scored: Vec<(usize, T, u64)> = foods
         .drain(..)
         .enumerate()
         .filter_map(|(idx, food)| {
             // Öncelikle sıralarken prefix şeklinde eşleşenlere öncelik vereceğiz
             // Örneğin ka diye aratıldığında 0: K*ar*puz, 1: Porta*ka*l şeklinde sıralamak istiyoruz
             // Bunun için basit bir puanlama sistemi yapıp bu puanlara göre sort edeceğiz, her eşleşen karakter için 1 puan ekleyeceğiz
-            let desc_lower = food.description.to_lowercase();
+            let desc_lower = food.description().to_lowercase();
             if desc_lower.starts_with(&query) {
                 return Some((idx, food, 20u64));
             }
@@ -288,15 +1117,24 @@ async fn sort_foods_by_query(foods: &mut Vec<Food>, query: &str) {
         .collect();
 
     // Puanlara göre yüksekten düşüğe sıralıyoruz
-    scored.sort_unstable_by(|a, b| b.2.cmp(&a.2));
+    let now = chrono::Utc::now().timestamp();
+    let mut scored: Vec<(usize, T, u64, f64)> = scored
+        .into_iter()
+        .map(|(idx, food, score)| {
+            let popular = popularity.map_or(0.0, |popularity| popularity.score(food.slug(), now));
+            (idx, food, score, popular)
+        })
+        .collect();
+    scored.sort_unstable_by(|a, b| b.2.cmp(&a.2).then(b.3.total_cmp(&a.3)).then(a.0.cmp(&b.0)));
 
     // Sıralanmış yemekleri de birleştirip güncelliyoruz
-    *foods = scored.into_iter().map(|(_, food, _)| food).collect();
+    *foods = scored.into_iter().map(|(_, food, _, _)| food).collect();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::popularity::{CLICK_WEIGHT, VIEW_WEIGHT};
     use std::time::Instant;
 
     // Test verisi oluşturan helper fonksiyonlar
@@ -316,30 +1154,41 @@ mod tests {
                 tags: vec!["meyve".to_string(), "yaz".to_string()],
                 allergens: vec![],
                 servings: servings.clone(),
+                ingredients: None,
+                density: None,
+                provenance: BTreeMap::new(),
+                source_id: None,
+                source_url: None,
+                parent: None,
+                variant_of: None,
+                children: vec![],
+                variants: vec![],
                 glycemic_index: 72.0,
-                energy: 30.0,
-                carbohydrate: 7.55,
-                protein: 0.61,
-                fat: 0.15,
-                saturated_fat: 0.0,
-                trans_fat: 0.0,
-                sugar: 6.2,
-                fiber: 0.4,
-                cholesterol: 0.0,
-                sodium: 1.0,
-                potassium: 112.0,
-                water: 91.45,
-                iron: 0.24,
-                magnesium: 10.0,
-                calcium: 16.0,
-                zinc: 0.17,
-                vitamin_a: 28.0,
-                vitamin_b6: 0.045,
-                vitamin_b12: 0.0,
-                vitamin_c: 7.5,
-                vitamin_d: 0.0,
-                vitamin_e: 0.05,
-                vitamin_k: 0.1,
+                nutrients: BTreeMap::from([
+                    ("energy".to_owned(), Some(30.0)),
+                    ("carbohydrate".to_owned(), Some(7.55)),
+                    ("protein".to_owned(), Some(0.61)),
+                    ("fat".to_owned(), Some(0.15)),
+                    ("saturated_fat".to_owned(), Some(0.0)),
+                    ("trans_fat".to_owned(), Some(0.0)),
+                    ("sugar".to_owned(), Some(6.2)),
+                    ("fiber".to_owned(), Some(0.4)),
+                    ("cholesterol".to_owned(), Some(0.0)),
+                    ("sodium".to_owned(), Some(1.0)),
+                    ("potassium".to_owned(), Some(112.0)),
+                    ("water".to_owned(), Some(91.45)),
+                    ("iron".to_owned(), Some(0.24)),
+                    ("magnesium".to_owned(), Some(10.0)),
+                    ("calcium".to_owned(), Some(16.0)),
+                    ("zinc".to_owned(), Some(0.17)),
+                    ("vitamin_a".to_owned(), Some(28.0)),
+                    ("vitamin_b6".to_owned(), Some(0.045)),
+                    ("vitamin_b12".to_owned(), Some(0.0)),
+                    ("vitamin_c".to_owned(), Some(7.5)),
+                    ("vitamin_d".to_owned(), Some(0.0)),
+                    ("vitamin_e".to_owned(), Some(0.05)),
+                    ("vitamin_k".to_owned(), Some(0.1)),
+                ]),
             },
             // Contains "kar" in middle
             Food {
@@ -352,30 +1201,41 @@ mod tests {
                 tags: vec!["meyve".to_string(), "narenciye".to_string()],
                 allergens: vec![],
                 servings: servings.clone(),
+                ingredients: None,
+                density: None,
+                provenance: BTreeMap::new(),
+                source_id: None,
+                source_url: None,
+                parent: None,
+                variant_of: None,
+                children: vec![],
+                variants: vec![],
                 glycemic_index: 43.0,
-                energy: 47.0,
-                carbohydrate: 11.75,
-                protein: 0.94,
-                fat: 0.12,
-                saturated_fat: 0.0,
-                trans_fat: 0.0,
-                sugar: 9.35,
-                fiber: 2.4,
-                cholesterol: 0.0,
-                sodium: 0.0,
-                potassium: 181.0,
-                water: 86.75,
-                iron: 0.1,
-                magnesium: 10.0,
-                calcium: 40.0,
-                zinc: 0.07,
-                vitamin_a: 11.0,
-                vitamin_b6: 0.06,
-                vitamin_b12: 0.0,
-                vitamin_c: 53.2,
-                vitamin_d: 0.0,
-                vitamin_e: 0.18,
-                vitamin_k: 0.0,
+                nutrients: BTreeMap::from([
+                    ("energy".to_owned(), Some(47.0)),
+                    ("carbohydrate".to_owned(), Some(11.75)),
+                    ("protein".to_owned(), Some(0.94)),
+                    ("fat".to_owned(), Some(0.12)),
+                    ("saturated_fat".to_owned(), Some(0.0)),
+                    ("trans_fat".to_owned(), Some(0.0)),
+                    ("sugar".to_owned(), Some(9.35)),
+                    ("fiber".to_owned(), Some(2.4)),
+                    ("cholesterol".to_owned(), Some(0.0)),
+                    ("sodium".to_owned(), Some(0.0)),
+                    ("potassium".to_owned(), Some(181.0)),
+                    ("water".to_owned(), Some(86.75)),
+                    ("iron".to_owned(), Some(0.1)),
+                    ("magnesium".to_owned(), Some(10.0)),
+                    ("calcium".to_owned(), Some(40.0)),
+                    ("zinc".to_owned(), Some(0.07)),
+                    ("vitamin_a".to_owned(), Some(11.0)),
+                    ("vitamin_b6".to_owned(), Some(0.06)),
+                    ("vitamin_b12".to_owned(), Some(0.0)),
+                    ("vitamin_c".to_owned(), Some(53.2)),
+                    ("vitamin_d".to_owned(), Some(0.0)),
+                    ("vitamin_e".to_owned(), Some(0.18)),
+                    ("vitamin_k".to_owned(), Some(0.0)),
+                ]),
             },
             // Contains "kar" at end
             Food {
@@ -388,30 +1248,41 @@ mod tests {
                 tags: vec!["makarna".to_string(), "italyan".to_string()],
                 allergens: vec!["gluten".to_string()],
                 servings: servings.clone(),
+                ingredients: None,
+                density: None,
+                provenance: BTreeMap::new(),
+                source_id: None,
+                source_url: None,
+                parent: None,
+                variant_of: None,
+                children: vec![],
+                variants: vec![],
                 glycemic_index: 50.0,
-                energy: 371.0,
-                carbohydrate: 75.0,
-                protein: 13.0,
-                fat: 1.5,
-                saturated_fat: 0.3,
-                trans_fat: 0.0,
-                sugar: 2.7,
-                fiber: 2.5,
-                cholesterol: 0.0,
-                sodium: 6.0,
-                potassium: 223.0,
-                water: 8.8,
-                iron: 1.3,
-                magnesium: 53.0,
-                calcium: 21.0,
-                zinc: 1.2,
-                vitamin_a: 0.0,
-                vitamin_b6: 0.08,
-                vitamin_b12: 0.0,
-                vitamin_c: 0.0,
-                vitamin_d: 0.0,
-                vitamin_e: 0.11,
-                vitamin_k: 0.0,
+                nutrients: BTreeMap::from([
+                    ("energy".to_owned(), Some(371.0)),
+                    ("carbohydrate".to_owned(), Some(75.0)),
+                    ("protein".to_owned(), Some(13.0)),
+                    ("fat".to_owned(), Some(1.5)),
+                    ("saturated_fat".to_owned(), Some(0.3)),
+                    ("trans_fat".to_owned(), Some(0.0)),
+                    ("sugar".to_owned(), Some(2.7)),
+                    ("fiber".to_owned(), Some(2.5)),
+                    ("cholesterol".to_owned(), Some(0.0)),
+                    ("sodium".to_owned(), Some(6.0)),
+                    ("potassium".to_owned(), Some(223.0)),
+                    ("water".to_owned(), Some(8.8)),
+                    ("iron".to_owned(), Some(1.3)),
+                    ("magnesium".to_owned(), Some(53.0)),
+                    ("calcium".to_owned(), Some(21.0)),
+                    ("zinc".to_owned(), Some(1.2)),
+                    ("vitamin_a".to_owned(), Some(0.0)),
+                    ("vitamin_b6".to_owned(), Some(0.08)),
+                    ("vitamin_b12".to_owned(), Some(0.0)),
+                    ("vitamin_c".to_owned(), Some(0.0)),
+                    ("vitamin_d".to_owned(), Some(0.0)),
+                    ("vitamin_e".to_owned(), Some(0.11)),
+                    ("vitamin_k".to_owned(), Some(0.0)),
+                ]),
             },
             // No match
             Food {
@@ -424,30 +1295,41 @@ mod tests {
                 tags: vec!["meyve".to_string()],
                 allergens: vec![],
                 servings: servings.clone(),
+                ingredients: None,
+                density: None,
+                provenance: BTreeMap::new(),
+                source_id: None,
+                source_url: None,
+                parent: None,
+                variant_of: None,
+                children: vec![],
+                variants: vec![],
                 glycemic_index: 39.0,
-                energy: 52.0,
-                carbohydrate: 13.81,
-                protein: 0.26,
-                fat: 0.17,
-                saturated_fat: 0.0,
-                trans_fat: 0.0,
-                sugar: 10.39,
-                fiber: 2.4,
-                cholesterol: 0.0,
-                sodium: 1.0,
-                potassium: 107.0,
-                water: 85.56,
-                iron: 0.12,
-                magnesium: 5.0,
-                calcium: 6.0,
-                zinc: 0.04,
-                vitamin_a: 3.0,
-                vitamin_b6: 0.041,
-                vitamin_b12: 0.0,
-                vitamin_c: 4.6,
-                vitamin_d: 0.0,
-                vitamin_e: 0.18,
-                vitamin_k: 2.2,
+                nutrients: BTreeMap::from([
+                    ("energy".to_owned(), Some(52.0)),
+                    ("carbohydrate".to_owned(), Some(13.81)),
+                    ("protein".to_owned(), Some(0.26)),
+                    ("fat".to_owned(), Some(0.17)),
+                    ("saturated_fat".to_owned(), Some(0.0)),
+                    ("trans_fat".to_owned(), Some(0.0)),
+                    ("sugar".to_owned(), Some(10.39)),
+                    ("fiber".to_owned(), Some(2.4)),
+                    ("cholesterol".to_owned(), Some(0.0)),
+                    ("sodium".to_owned(), Some(1.0)),
+                    ("potassium".to_owned(), Some(107.0)),
+                    ("water".to_owned(), Some(85.56)),
+                    ("iron".to_owned(), Some(0.12)),
+                    ("magnesium".to_owned(), Some(5.0)),
+                    ("calcium".to_owned(), Some(6.0)),
+                    ("zinc".to_owned(), Some(0.04)),
+                    ("vitamin_a".to_owned(), Some(3.0)),
+                    ("vitamin_b6".to_owned(), Some(0.041)),
+                    ("vitamin_b12".to_owned(), Some(0.0)),
+                    ("vitamin_c".to_owned(), Some(4.6)),
+                    ("vitamin_d".to_owned(), Some(0.0)),
+                    ("vitamin_e".to_owned(), Some(0.18)),
+                    ("vitamin_k".to_owned(), Some(2.2)),
+                ]),
             },
         ]
     }
@@ -476,30 +1358,41 @@ mod tests {
                 tags: vec![format!("tag-{}", i % 3)],
                 allergens: vec![],
                 servings: servings.clone(),
+                ingredients: None,
+                density: None,
+                provenance: BTreeMap::new(),
+                source_id: None,
+                source_url: None,
+                parent: None,
+                variant_of: None,
+                children: vec![],
+                variants: vec![],
                 glycemic_index: 50.0 + (i as f64 % 50.0), // 50-100 arası rastgele
-                energy: 100.0 + (i as f64 % 400.0),       // 100-500 arası
-                carbohydrate: 20.0 + (i as f64 % 60.0),   // 20-80 arası
-                protein: 5.0 + (i as f64 % 20.0),         // 5-25 arası
-                fat: 3.0 + (i as f64 % 15.0),             // 3-18 arası
-                saturated_fat: 1.0 + (i as f64 % 5.0),    // 1-6 arası
-                trans_fat: 0.0,
-                sugar: 10.0 + (i as f64 % 30.0), // 10-40 arası
-                fiber: 2.0 + (i as f64 % 8.0),   // 2-10 arası
-                cholesterol: 0.0,
-                sodium: 50.0 + (i as f64 % 100.0), // 50-150 arası
-                potassium: 200.0 + (i as f64 % 300.0), // 200-500 arası
-                water: 80.0 + (i as f64 % 20.0),   // 80-100 arası
-                iron: 1.0 + (i as f64 % 2.0),      // 1-3 arası
-                magnesium: 20.0 + (i as f64 % 40.0), // 20-60 arası
-                calcium: 30.0 + (i as f64 % 70.0), // 30-100 arası
-                zinc: 0.5 + (i as f64 % 1.5),      // 0.5-2 arası
-                vitamin_a: 100.0 + (i as f64 % 200.0), // 100-300 arası
-                vitamin_b6: 0.1 + (i as f64 % 0.2), // 0.1-0.3 arası
-                vitamin_b12: 0.0,
-                vitamin_c: 50.0 + (i as f64 % 100.0), // 50-150 arası
-                vitamin_d: 5.0 + (i as f64 % 10.0),   // 5-15 arası
-                vitamin_e: 2.0 + (i as f64 % 3.0),    // 2-5 arası
-                vitamin_k: 10.0 + (i as f64 % 20.0),  // 10-30 arası
+                nutrients: BTreeMap::from([
+                    ("energy".to_owned(), Some(100.0 + (i as f64 % 400.0))), // 100-500 arası
+                    ("carbohydrate".to_owned(), Some(20.0 + (i as f64 % 60.0))), // 20-80 arası
+                    ("protein".to_owned(), Some(5.0 + (i as f64 % 20.0))),   // 5-25 arası
+                    ("fat".to_owned(), Some(3.0 + (i as f64 % 15.0))),       // 3-18 arası
+                    ("saturated_fat".to_owned(), Some(1.0 + (i as f64 % 5.0))), // 1-6 arası
+                    ("trans_fat".to_owned(), Some(0.0)),
+                    ("sugar".to_owned(), Some(10.0 + (i as f64 % 30.0))), // 10-40 arası
+                    ("fiber".to_owned(), Some(2.0 + (i as f64 % 8.0))),   // 2-10 arası
+                    ("cholesterol".to_owned(), Some(0.0)),
+                    ("sodium".to_owned(), Some(50.0 + (i as f64 % 100.0))), // 50-150 arası
+                    ("potassium".to_owned(), Some(200.0 + (i as f64 % 300.0))), // 200-500 arası
+                    ("water".to_owned(), Some(80.0 + (i as f64 % 20.0))),   // 80-100 arası
+                    ("iron".to_owned(), Some(1.0 + (i as f64 % 2.0))),      // 1-3 arası
+                    ("magnesium".to_owned(), Some(20.0 + (i as f64 % 40.0))), // 20-60 arası
+                    ("calcium".to_owned(), Some(30.0 + (i as f64 % 70.0))), // 30-100 arası
+                    ("zinc".to_owned(), Some(0.5 + (i as f64 % 1.5))),      // 0.5-2 arası
+                    ("vitamin_a".to_owned(), Some(100.0 + (i as f64 % 200.0))), // 100-300 arası
+                    ("vitamin_b6".to_owned(), Some(0.1 + (i as f64 % 0.2))), // 0.1-0.3 arası
+                    ("vitamin_b12".to_owned(), Some(0.0)),
+                    ("vitamin_c".to_owned(), Some(50.0 + (i as f64 % 100.0))), // 50-150 arası
+                    ("vitamin_d".to_owned(), Some(5.0 + (i as f64 % 10.0))),   // 5-15 arası
+                    ("vitamin_e".to_owned(), Some(2.0 + (i as f64 % 3.0))),    // 2-5 arası
+                    ("vitamin_k".to_owned(), Some(10.0 + (i as f64 % 20.0))),  // 10-30 arası
+                ]),
             });
         }
 
@@ -512,7 +1405,7 @@ mod tests {
         let mut foods = generate_large_food_dataset(100);
         let query = "kar";
         let start = Instant::now();
-        sort_foods_by_query(&mut foods, query).await; // .await ekle
+        sort_foods_by_query(&mut foods, query, None).await; // .await ekle
         let duration = start.elapsed();
 
         println!("100 foods: {:?}", duration);
@@ -525,7 +1418,7 @@ mod tests {
         let mut foods = generate_large_food_dataset(1000);
         let query = "kar";
         let start = Instant::now();
-        sort_foods_by_query(&mut foods, query).await;
+        sort_foods_by_query(&mut foods, query, None).await;
         let duration = start.elapsed();
 
         println!("1000 foods: {:?}", duration);
@@ -538,7 +1431,7 @@ mod tests {
         let mut foods = generate_large_food_dataset(5000);
         let query = "kar";
         let start = Instant::now();
-        sort_foods_by_query(&mut foods, query).await;
+        sort_foods_by_query(&mut foods, query, None).await;
         let duration = start.elapsed();
 
         println!("5000 foods: {:?}", duration);
@@ -556,7 +1449,7 @@ mod tests {
 
             let mut foods = generate_large_food_dataset(size);
             let start = Instant::now();
-            sort_foods_by_query(&mut foods, query).await; // ✅ .await
+            sort_foods_by_query(&mut foods, query, None).await; // ✅ .await
             let duration = start.elapsed();
 
             let ms = duration.as_millis();
@@ -575,10 +1468,39 @@ mod tests {
     }
 
     // Diğer unit testleri de güncelle
+    #[test]
+    fn test_search_response_result_count() {
+        let result_count = |response: Result<Response, APIError>| {
+            response
+                .ok()
+                .and_then(|response| response.extensions().get::<ResultCount>().copied())
+        };
+
+        let count = ResultCount(create_test_foods().len() as i64);
+        for format in [
+            ResponseFormat::Json,
+            ResponseFormat::Csv(Default::default()),
+            ResponseFormat::MsgPack,
+        ] {
+            let response = search_response(format, SearchResponse::Foods(create_test_foods()));
+            assert_eq!(result_count(response), Some(count));
+        }
+
+        let response = search_response(
+            ResponseFormat::Json,
+            SearchResponse::<Food>::Detailed {
+                results: Vec::new(),
+                facets: None,
+                suggestions: None,
+            },
+        );
+        assert_eq!(result_count(response), Some(ResultCount(0)));
+    }
+
     #[tokio::test]
     async fn test_sort_by_query_prefix_match() {
         let mut foods = create_test_foods();
-        sort_foods_by_query(&mut foods, "kar").await; // ✅ .await
+        sort_foods_by_query(&mut foods, "kar", None).await; // ✅ .await
 
         assert_eq!(foods[0].slug, Some("karpuz".to_string()));
         assert_eq!(foods[1].slug, Some("makarna".to_string()));
@@ -630,7 +1552,7 @@ mod tests {
             },
         ];
 
-        sort_foods_by_query(&mut foods, "kaşar").await;
+        sort_foods_by_query(&mut foods, "kaşar", None).await;
 
         // Başlangıçta olan en yüksek skor almalı (20 puan)
         assert_eq!(foods[0].slug, Some("baslangic".to_string()));
@@ -645,7 +1567,7 @@ mod tests {
         let mut foods = create_test_foods();
         let original_order = foods.clone();
 
-        sort_foods_by_query(&mut foods, "xyz").await; // Hiçbir şeyle eşleşmez
+        sort_foods_by_query(&mut foods, "xyz", None).await; // Hiçbir şeyle eşleşmez
 
         // Sıralama değişmemeli (hepsi 0 skor)
         assert_eq!(foods, original_order);
@@ -656,7 +1578,7 @@ mod tests {
         let mut foods = create_test_foods();
         let original_order = foods.clone();
 
-        sort_foods_by_query(&mut foods, "").await;
+        sort_foods_by_query(&mut foods, "", None).await;
 
         // Boş query ile sıralama değişmemeli
         assert_eq!(foods, original_order);
@@ -667,7 +1589,7 @@ mod tests {
         let mut foods: Vec<Food> = vec![];
         let original = foods.clone();
 
-        sort_foods_by_query(&mut foods, "test").await;
+        sort_foods_by_query(&mut foods, "test", None).await;
 
         assert_eq!(foods, original);
     }
@@ -676,7 +1598,7 @@ mod tests {
     async fn test_sort_by_query_case_insensitive() {
         let mut foods = create_test_foods();
 
-        sort_foods_by_query(&mut foods, "KaR").await;
+        sort_foods_by_query(&mut foods, "KaR", None).await;
 
         // Büyük/küçük harf duyarlılığı olmamalı
         assert_eq!(foods[0].slug, Some("karpuz".to_string()));
@@ -713,9 +1635,97 @@ mod tests {
         ];
 
         let original_order = foods.clone();
-        sort_foods_by_query(&mut foods, "ka").await;
+        sort_foods_by_query(&mut foods, "ka", None).await;
 
         // Aynı skorlu elementler orijinal sıralarını korumalı
         assert_eq!(foods, original_order);
     }
+
+    #[tokio::test]
+    async fn test_sort_foods_by_popularity() {
+        let food = |slug: &str, description: &str| Food {
+            slug: Some(slug.to_owned()),
+            description: description.to_owned(),
+            ..Default::default()
+        };
+        let mut foods = vec![
+            food("fuji-elma", "Fuji Elma"),
+            food("elma-suyu", "Elma Suyu"),
+            food("elma", "Elma"),
+        ];
+
+        let mut popularity = Popularity::new(14.0);
+        let now = chrono::Utc::now().timestamp();
+        popularity.record("elma", CLICK_WEIGHT, now);
+        popularity.record("elma-suyu", CLICK_WEIGHT, now);
+        popularity.record("elma-suyu", VIEW_WEIGHT, now);
+        // Fuji Elma çok popüler olsa da baştan eşleşmediği için sona kalıyor
+        for _ in 0..10 {
+            popularity.record("fuji-elma", CLICK_WEIGHT, now);
+        }
+
+        sort_foods_by_query(&mut foods, "elma", Some(&popularity)).await;
+        let slugs: Vec<&str> = foods.iter().map(|food| food.slug()).collect();
+        assert_eq!(slugs, vec!["elma-suyu", "elma", "fuji-elma"]);
+    }
+
+    #[test]
+    fn test_collapse_variants() {
+        let food = |slug: &str, variant_of: Option<&str>| Food {
+            slug: Some(slug.to_owned()),
+            description: slug.to_owned(),
+            variant_of: variant_of.map(str::to_owned),
+            ..Default::default()
+        };
+
+        // Arama sıralamasında haşlanmış tavuk çiğden önce gelmiş olsun
+        let foods = vec![
+            food("tavuk-gogsu-haslanmis", Some("tavuk-gogsu-cig")),
+            food("muz", None),
+            food("tavuk-gogsu-cig", None),
+            food("tavuk-gogsu-izgara", Some("tavuk-gogsu-cig")),
+        ];
+
+        let collapsed = collapse_variants(foods);
+
+        assert_eq!(collapsed.len(), 2);
+        assert_eq!(collapsed[0].slug, Some("tavuk-gogsu-haslanmis".to_owned()));
+        assert_eq!(
+            collapsed[0].variants,
+            vec![
+                "tavuk-gogsu-cig".to_owned(),
+                "tavuk-gogsu-izgara".to_owned()
+            ]
+        );
+        assert!(collapsed[1].variants.is_empty());
+    }
+
+    #[test]
+    fn test_filter_params_search_filter() {
+        let params = FilterParams {
+            all: Some("Meyve, tropikal,".to_owned()),
+            any: None,
+            none: Some("tatlı".to_owned()),
+            filter: Some("protein > 1".to_owned()),
+        };
+
+        let filter = params.search_filter().ok().expect("Filtre ayrıştırılamadı");
+        assert_eq!(filter.all, vec!["meyve".to_owned(), "tropikal".to_owned()]);
+        assert!(filter.any.is_empty());
+        assert_eq!(filter.none, vec!["tatlı".to_owned()]);
+        assert!(filter.expression.is_some());
+
+        let params = FilterParams {
+            filter: Some("protein >".to_owned()),
+            ..params
+        };
+        assert!(params.search_filter().is_err());
+
+        let params = FilterParams {
+            any: Some(["meyve"; MAX_TAG_FILTERS + 1].join(",")),
+            filter: None,
+            ..params
+        };
+        assert!(params.search_filter().is_err());
+    }
 }
@@ -0,0 +1,301 @@
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{
+        HeaderMap, HeaderValue, StatusCode, Uri,
+        header::{ACCEPT, CONTENT_TYPE},
+        request::Parts,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::{
+    api::error::{APIError, ErrorCode},
+    core::table::{CsvOptions, to_csv},
+};
+
+pub(crate) const JSON_CONTENT_TYPE: &str = "application/json";
+pub(crate) const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8; header=present";
+pub(crate) const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+pub(crate) const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+#[derive(Deserialize, Default)]
+struct FormatParams {
+    format: Option<String>,
+    separator: Option<String>,
+    decimal: Option<String>,
+}
+
+// Yanıtların biçimi, varsayılan JSON. CSV sadece liste dönen endpoint'lerde destekleniyor, diğerleri JSON dönüyor
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) enum ResponseFormat {
+    #[default]
+    Json,
+    Csv(CsvOptions),
+    MsgPack,
+    Cbor,
+}
+
+impl ResponseFormat {
+    fn from_name(name: &str, options: CsvOptions) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "json" | "application/json" => Some(ResponseFormat::Json),
+            "csv" | "text/csv" => Some(ResponseFormat::Csv(options)),
+            "msgpack" | "application/msgpack" | "application/x-msgpack" => {
+                Some(ResponseFormat::MsgPack)
+            }
+            "cbor" | "application/cbor" => Some(ResponseFormat::Cbor),
+            _ => None,
+        }
+    }
+
+    // Cache'te her biçimin kodlanmış yanıtı ayrı tutuluyor, CSV'de ayraçlar da yanıtı değiştirdiği için anahtarda
+    pub(crate) fn cache_key(&self) -> String {
+        match self {
+            ResponseFormat::Json => "json".to_owned(),
+            ResponseFormat::Csv(options) => format!(
+                "csv{}{}",
+                options.separator as char,
+                if options.decimal_comma { ',' } else { '.' }
+            ),
+            ResponseFormat::MsgPack => "msgpack".to_owned(),
+            ResponseFormat::Cbor => "cbor".to_owned(),
+        }
+    }
+
+    // Accept başlığındaki desteklenen biçimler arasından q değeri en yüksek olanı seçiyoruz, tarayıcılar gibi */*
+    // gönderen istemciler JSON alıyor
+    fn from_accept(header: &str, options: CsvOptions) -> Option<Self> {
+        let mut best: Option<(f32, ResponseFormat)> = None;
+        for part in header.split(',') {
+            let mut pieces = part.split(';');
+            let Some(format) = pieces
+                .next()
+                .and_then(|media_type| ResponseFormat::from_name(media_type, options))
+            else {
+                continue;
+            };
+            let quality = pieces
+                .find_map(|piece| piece.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+
+            if quality > 0.0 && best.is_none_or(|(best, _)| quality > best) {
+                best = Some((quality, format));
+            }
+        }
+        best.map(|(_, format)| format)
+    }
+}
+
+fn csv_options(params: &FormatParams) -> Result<CsvOptions, APIError> {
+    let decimal_comma = match params.decimal.as_deref() {
+        None | Some("point" | ".") => false,
+        Some("comma" | ",") => true,
+        Some(_) => return Err(APIError::new(ErrorCode::InvalidCsvOptions)),
+    };
+    // Ondalık virgül seçildiyse Excel'in Türkçe ayarlarında olduğu gibi noktalı virgül varsayılan ayraç oluyor
+    let separator = match params.separator.as_deref() {
+        None if decimal_comma => b';',
+        None | Some("comma" | ",") => b',',
+        Some("semicolon" | ";") => b';',
+        Some(_) => return Err(APIError::new(ErrorCode::InvalidCsvOptions)),
+    };
+
+    Ok(CsvOptions {
+        separator,
+        decimal_comma,
+    })
+}
+
+// ?format=csv parametresi Accept başlığından önce geliyor, başlık gönderemeyen tarayıcı ve Excel kullanıcıları için
+pub(crate) fn request_format(uri: &Uri, headers: &HeaderMap) -> Result<ResponseFormat, APIError> {
+    let params = Query::<FormatParams>::try_from_uri(uri)
+        .map(|Query(params)| params)
+        .unwrap_or_default();
+    let options = csv_options(&params)?;
+
+    if let Some(format) = &params.format {
+        return ResponseFormat::from_name(format, options)
+            .ok_or_else(|| APIError::new(ErrorCode::UnsupportedFormat));
+    }
+
+    // Başlıkta desteklenen bir biçim yoksa 406 yerine JSON dönüyoruz
+    Ok(headers
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|accept| ResponseFormat::from_accept(accept, options))
+        .unwrap_or_default())
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = APIError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        request_format(&parts.uri, &parts.headers)
+    }
+}
+
+// Yanıtı istenen biçimde serileştiriyor. CSV tabloya çevrilebilen listelere özel olduğu için handler'lar onu
+// csv_response ile ayrıca üretiyor, burada CSV istenirse JSON dönüyoruz. MessagePack'te alanlar JSON'daki gibi isimleriyle
+// yazılıyor, dizi olarak yazılsaydı istemcilerin alanların sırasını bilmesi gerekirdi
+pub(crate) fn encode<T: Serialize>(
+    format: ResponseFormat,
+    value: &T,
+) -> anyhow::Result<(&'static str, Vec<u8>)> {
+    Ok(match format {
+        ResponseFormat::Json | ResponseFormat::Csv(_) => {
+            (JSON_CONTENT_TYPE, serde_json::to_vec(value)?)
+        }
+        ResponseFormat::MsgPack => (MSGPACK_CONTENT_TYPE, rmp_serde::to_vec_named(value)?),
+        ResponseFormat::Cbor => {
+            let mut body = Vec::new();
+            ciborium::into_writer(value, &mut body)?;
+            (CBOR_CONTENT_TYPE, body)
+        }
+    })
+}
+
+pub(crate) fn format_response<T: Serialize>(
+    format: ResponseFormat,
+    value: &T,
+) -> Result<Response, APIError> {
+    let (content_type, body) = encode(format, value).map_err(|e| {
+        error!("Yanıt serileştirilirken hata oluştu: {:?}", e);
+        APIError::new(ErrorCode::InternalError)
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        [(CONTENT_TYPE, HeaderValue::from_static(content_type))],
+        body,
+    )
+        .into_response())
+}
+
+pub(crate) fn csv_response<T: Serialize>(
+    records: &[T],
+    options: CsvOptions,
+) -> Result<Response, APIError> {
+    let csv = to_csv(records, options).map_err(|e| {
+        error!("CSV yanıtı oluşturulurken hata oluştu: {:?}", e);
+        APIError::new(ErrorCode::InternalError)
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        [(CONTENT_TYPE, HeaderValue::from_static(CSV_CONTENT_TYPE))],
+        csv,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_format() {
+        let mut headers = HeaderMap::new();
+        let uri: Uri = "/foods/search?q=elma".parse().unwrap();
+        assert_eq!(
+            request_format(&uri, &headers).ok(),
+            Some(ResponseFormat::Json)
+        );
+
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/json;q=0.5, text/csv"),
+        );
+        assert_eq!(
+            request_format(&uri, &headers).ok(),
+            Some(ResponseFormat::Csv(CsvOptions::default()))
+        );
+
+        // Parametre başlığı geçersiz kılıyor, ondalık virgülde ayraç noktalı virgül oluyor
+        let uri: Uri = "/foods/search?q=elma&format=json".parse().unwrap();
+        assert_eq!(
+            request_format(&uri, &headers).ok(),
+            Some(ResponseFormat::Json)
+        );
+        let uri: Uri = "/foods/list?format=csv&decimal=comma".parse().unwrap();
+        assert_eq!(
+            request_format(&uri, &headers).ok(),
+            Some(ResponseFormat::Csv(CsvOptions {
+                separator: b';',
+                decimal_comma: true,
+            }))
+        );
+
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/cbor, application/msgpack;q=0.9"),
+        );
+        let uri: Uri = "/health".parse().unwrap();
+        assert_eq!(
+            request_format(&uri, &headers).ok(),
+            Some(ResponseFormat::Cbor)
+        );
+        let uri: Uri = "/health?format=msgpack".parse().unwrap();
+        assert_eq!(
+            request_format(&uri, &headers).ok(),
+            Some(ResponseFormat::MsgPack)
+        );
+
+        let uri: Uri = "/foods/list?format=xml".parse().unwrap();
+        assert!(request_format(&uri, &headers).is_err());
+        let uri: Uri = "/foods/list?format=csv&separator=tab".parse().unwrap();
+        assert!(request_format(&uri, &headers).is_err());
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Record {
+        slug: String,
+        energy: Option<f64>,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_encode() {
+        let record = Record {
+            slug: "elma".to_owned(),
+            energy: Some(52.5),
+            tags: vec!["meyve".to_owned()],
+        };
+
+        let (content_type, body) = encode(ResponseFormat::MsgPack, &record).unwrap();
+        assert_eq!(content_type, MSGPACK_CONTENT_TYPE);
+        // Alanlar isimleriyle birlikte 3 elemanlı bir map olarak yazılmalı
+        assert_eq!(&body[..6], &[0x83, 0xa4, b's', b'l', b'u', b'g']);
+        assert_eq!(rmp_serde::from_slice::<Record>(&body).unwrap(), record);
+
+        let (content_type, body) = encode(ResponseFormat::Cbor, &record).unwrap();
+        assert_eq!(content_type, CBOR_CONTENT_TYPE);
+        assert_eq!(&body[..6], &[0xa3, 0x64, b's', b'l', b'u', b'g']);
+        assert_eq!(
+            ciborium::from_reader::<Record, _>(&body[..]).unwrap(),
+            record
+        );
+
+        let (content_type, body) =
+            encode(ResponseFormat::Csv(CsvOptions::default()), &record).unwrap();
+        assert_eq!(content_type, JSON_CONTENT_TYPE);
+        assert_eq!(serde_json::from_slice::<Record>(&body).unwrap(), record);
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(ResponseFormat::Json.cache_key(), "json");
+        assert_eq!(ResponseFormat::MsgPack.cache_key(), "msgpack");
+        assert_eq!(ResponseFormat::Cbor.cache_key(), "cbor");
+        assert_eq!(
+            ResponseFormat::Csv(CsvOptions {
+                separator: b';',
+                decimal_comma: true,
+            })
+            .cache_key(),
+            "csv;,"
+        );
+    }
+}
@@ -4,9 +4,9 @@ use std::{
 };
 
 use axum::{
-    Json,
     extract::{ConnectInfo, State},
     http::HeaderMap,
+    response::Response,
 };
 use chrono::{FixedOffset, Utc};
 use reqwest::ClientBuilder;
@@ -14,7 +14,14 @@ use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use tracing::debug;
 
-use crate::{SharedState, api::parse_client_ip};
+use crate::{
+    SharedState,
+    api::{
+        error::APIError,
+        format::{ResponseFormat, format_response},
+        parse_client_ip,
+    },
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct ServerHealth {
@@ -37,10 +44,11 @@ pub(crate) struct ServerHealthDetails {
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub(crate) async fn health(
+    format: ResponseFormat,
     State(shared_state): State<SharedState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
-) -> Json<ServerHealth> {
+) -> Result<Response, APIError> {
     let timestamp = {
         let utc_time = Utc::now();
         let turkish_offset = FixedOffset::east_opt(3 * 3600).unwrap(); // +3 saat
@@ -79,7 +87,7 @@ pub(crate) async fn health(
     let client_ip = parse_client_ip(&addr, &headers);
     
     debug!("GET /health: ({}), {}", health.status, client_ip);
-    Json(health)
+    format_response(format, &health)
 }
 
 async fn check_database(pool: &SqlitePool) -> bool {
@@ -0,0 +1,64 @@
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{HeaderMap, Uri, header::ACCEPT_LANGUAGE, request::Parts},
+};
+use serde::Deserialize;
+
+use crate::{
+    api::error::{APIError, ErrorCode},
+    core::language::Language,
+};
+
+#[derive(Deserialize)]
+struct LanguageParams {
+    lang: Option<String>,
+}
+
+// ?lang=en parametresi Accept-Language başlığından önce geliyor, tarayıcının dilini değiştiremeyen kullanıcılar için
+pub(crate) fn request_language(uri: &Uri, headers: &HeaderMap) -> Result<Language, APIError> {
+    let lang = Query::<LanguageParams>::try_from_uri(uri)
+        .ok()
+        .and_then(|Query(params)| params.lang);
+    if let Some(lang) = lang {
+        return Language::from_code(&lang)
+            .ok_or_else(|| APIError::new(ErrorCode::UnsupportedLanguage));
+    }
+
+    // Başlıkta desteklenen bir dil yoksa hata vermek yerine Türkçe dönüyoruz
+    Ok(headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Language::from_accept_language)
+        .unwrap_or_default())
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Language {
+    type Rejection = APIError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        request_language(&parts.uri, &parts.headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_request_language() {
+        let mut headers = HeaderMap::new();
+        let uri: Uri = "/food/elma".parse().unwrap();
+        assert_eq!(request_language(&uri, &headers).ok(), Some(Language::Tr));
+
+        headers.insert(ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"));
+        assert_eq!(request_language(&uri, &headers).ok(), Some(Language::En));
+
+        // Parametre başlığı geçersiz kılıyor
+        let uri: Uri = "/food/elma?lang=tr".parse().unwrap();
+        assert_eq!(request_language(&uri, &headers).ok(), Some(Language::Tr));
+
+        let uri: Uri = "/food/elma?lang=de".parse().unwrap();
+        assert!(request_language(&uri, &headers).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use axum::{Json, extract::State, response::Response};
+use tracing::error;
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::{APIError, ErrorCode},
+        format::{ResponseFormat, format_response},
+    },
+    core::optimizer::{MealTargets, optimize_meal},
+};
+
+pub(crate) async fn meal_optimize(
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+    Json(targets): Json<MealTargets>,
+) -> Result<Response, APIError> {
+    // Çok fazla etiket veya sınır gönderilirse çözücüye gereksiz yük binmesin, DoS'a karşı basit bir limit
+    if targets.tags.len() > 20 || targets.exclude_allergens.len() > 20 || targets.nutrients.len() > 50
+    {
+        return Err(APIError::new(ErrorCode::TooManyConstraints));
+    }
+
+    if !targets.energy.is_finite() || targets.energy <= 0.0 {
+        return Err(APIError::new(ErrorCode::InvalidEnergyTarget));
+    }
+
+    let (nutrients, foods) = {
+        let db = &*shared_state.api_db.lock().await;
+        let nutrients = database::select_all_nutrients(db).await;
+        let foods = database::select_all_verified_foods(db).await;
+        (nutrients, foods)
+    };
+
+    let nutrients = nutrients.map_err(|e| {
+        error!("Veritabanı besin tanımlarını sorgularken hata oluştu: {:?}", e);
+        APIError::new(ErrorCode::DatabaseError)
+    })?;
+
+    if let Some(nutrient) = targets
+        .nutrients
+        .keys()
+        .find(|nutrient| !nutrients.iter().any(|defined| defined.name == **nutrient))
+    {
+        return Err(APIError::with_argument(
+            ErrorCode::UnknownNutrient,
+            nutrient,
+        ));
+    }
+
+    let foods = foods.map_err(|e| {
+        error!(
+            "Veritabanı öğün yemeklerini sorgularken hata oluştu: {:?}",
+            e
+        );
+        APIError::new(ErrorCode::DatabaseError)
+    })?;
+
+    if !foods.iter().any(|food| targets.accepts(food)) {
+        return Err(APIError::new(ErrorCode::NoMatchingFoods));
+    }
+
+    // Çözücü senkron çalışıyor, büyük problemlerde async runtime'ı bloklamaması için ayrı bir thread'e alıyoruz
+    let plan = tokio::task::spawn_blocking(move || optimize_meal(&targets, &foods))
+        .await
+        .map_err(|e| {
+            error!("Öğün optimizasyonu thread'i çöktü: {:?}", e);
+            APIError::new(ErrorCode::InternalError)
+        })?
+        .map_err(|e| match e {
+            microlp::Error::Infeasible => APIError::new(ErrorCode::MealInfeasible),
+            e => {
+                error!("Öğün optimizasyonu başarısız oldu: {:?}", e);
+                APIError::new(ErrorCode::InternalError)
+            }
+        })?;
+
+    format_response(format, &plan)
+}
@@ -2,12 +2,19 @@ use std::net::SocketAddr;
 
 use axum::http::HeaderMap;
 
+pub(crate) mod admin;
+pub(crate) mod analytics;
 pub(crate) mod cache;
 pub(crate) mod database;
 pub(crate) mod endpoints;
 pub(crate) mod error;
 pub(crate) mod foods;
+pub(crate) mod format;
 pub(crate) mod health;
+pub(crate) mod language;
+pub(crate) mod meals;
+pub(crate) mod parse;
+pub(crate) mod sources;
 
 fn parse_client_ip(proxy_addr: &SocketAddr, headers: &HeaderMap) -> String {
     headers
@@ -0,0 +1,169 @@
+use axum::{Json, extract::State, response::Response};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tracing::error;
+
+use crate::{
+    SharedState,
+    api::{
+        database::{self, SearchFilter, SearchQuery},
+        error::{APIError, ErrorCode},
+        foods::{sanitize_input, sort_foods_by_query},
+        format::{ResponseFormat, csv_response, format_response},
+    },
+    core::{
+        food::Food,
+        parser::{parse_line, resolve_amount},
+        stem::Synonyms,
+        str::to_lower_en,
+    },
+};
+
+// Bir satırdaki yemek ismi için en fazla bu kadar kelimeye bakıyoruz, her kelime grubu için ayrı sorgu atıldığı için limitliyoruz
+const MAX_FOOD_QUERY_WORDS: usize = 6;
+
+#[derive(Deserialize)]
+pub(crate) struct ParseRequest {
+    lines: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ParsedIngredient {
+    line: String,
+    quantity: f64,
+    unit: Option<&'static str>,
+    slug: Option<String>,
+    description: Option<String>,
+    serving: Option<String>,
+    grams: Option<f64>,
+    confidence: f64,
+    // Anlamlandıramadığımız kelimeler, kullanıcıya neyi düzeltmesi gerektiğini göstermek için
+    unresolved: Vec<String>,
+}
+
+pub(crate) async fn parse(
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+    Json(request): Json<ParseRequest>,
+) -> Result<Response, APIError> {
+    // Tariflerin çoğu 20-30 satırı geçmiyor, DoS'a karşı satır sayısını ve uzunluğunu limitliyoruz
+    if request.lines.len() > 50 || request.lines.iter().any(|line| line.len() > 100) {
+        return Err(APIError::new(ErrorCode::TooManyLines));
+    }
+
+    // Her satır için birçok sorgu atılabildiğinden kilidi istek boyunca tutmuyoruz, havuzun bir kopyasıyla sorguluyoruz
+    let pool = shared_state.api_db.lock().await.clone();
+    let mut results = Vec::with_capacity(request.lines.len());
+    for line in request.lines {
+        results.push(parse_ingredient(&pool, &shared_state.synonyms, line).await?);
+    }
+
+    match format {
+        ResponseFormat::Csv(options) => csv_response(&results, options),
+        format => format_response(format, &results),
+    }
+}
+
+async fn parse_ingredient(
+    db: &SqlitePool,
+    synonyms: &Synonyms,
+    line: String,
+) -> Result<ParsedIngredient, APIError> {
+    let parsed = parse_line(&line);
+    let mut result = ParsedIngredient {
+        line,
+        quantity: parsed.quantity,
+        unit: parsed.unit.map(|unit| unit.name),
+        slug: None,
+        description: None,
+        serving: None,
+        grams: None,
+        confidence: 0.0,
+        unresolved: Vec::new(),
+    };
+
+    let Some((food, start, end, match_confidence)) =
+        find_food(db, synonyms, &parsed.food_query).await?
+    else {
+        result.unresolved = parsed.food_query;
+        return Ok(result);
+    };
+
+    // Yemek isminin dışında kalan kelimeleri de çözülemeyenler arasında gösteriyoruz
+    result.unresolved = parsed.food_query[..start]
+        .iter()
+        .chain(&parsed.food_query[end..])
+        .cloned()
+        .collect();
+
+    match resolve_amount(&parsed, &food) {
+        Some(amount) => {
+            result.serving = amount.serving;
+            result.grams = Some(amount.grams);
+            result.confidence = match_confidence * amount.confidence;
+        }
+        None => {
+            if let Some(unit) = parsed.unit {
+                result.unresolved.insert(0, unit.name.to_owned());
+            }
+            result.confidence = match_confidence * 0.5;
+        }
+    }
+
+    // Çözülemeyen her kelime sonuca olan güvenimizi biraz daha düşürüyor
+    result.confidence *= 0.9_f64.powi(result.unresolved.len() as i32);
+    result.slug = food.slug;
+    result.description = Some(food.description);
+
+    Ok(result)
+}
+
+// Yemek ismini, arama endpoint'inin kullandığı sorgu ve sıralama ile buluyoruz. "orta boy muz dilimi" gibi fazladan
+// kelimeler olabileceği için önce tüm ifadeyi, sonra giderek kısalan kelime gruplarını deniyoruz
+async fn find_food(
+    db: &SqlitePool,
+    synonyms: &Synonyms,
+    words: &[String],
+) -> Result<Option<(Food, usize, usize, f64)>, APIError> {
+    let words = &words[..words.len().min(MAX_FOOD_QUERY_WORDS)];
+
+    for len in (1..=words.len()).rev() {
+        for start in 0..=words.len() - len {
+            let query = words[start..start + len].join(" ");
+            if query.chars().count() < 2 || sanitize_input(&query).is_err() {
+                continue;
+            }
+
+            let search = SearchQuery::Description(&query, synonyms.normalize(&query));
+            let mut foods = database::search_foods(db, &search, &SearchFilter::default())
+                .await
+                .map_err(|e| {
+                    error!("Veritabanı malzeme ararken hata oluştu: {:?}", e);
+                    APIError::new(ErrorCode::DatabaseError)
+                })?;
+            foods.retain(|food| food.verified.unwrap_or(false));
+            if foods.is_empty() {
+                continue;
+            }
+
+            // Malzeme eşleştirmesi her seferinde aynı sonucu vermeli, popülerlik burada kullanılmıyor
+            sort_foods_by_query(&mut foods, &query, None).await;
+            let food = foods.swap_remove(0);
+
+            // Tam eşleşme, baştan eşleşme ve içinde geçme için farklı güven değerleri veriyoruz
+            let description = to_lower_en(&food.description);
+            let query = to_lower_en(&query);
+            let confidence = if description == query {
+                1.0
+            } else if description.starts_with(&query) {
+                0.9
+            } else {
+                0.7
+            };
+
+            return Ok(Some((food, start, start + len, confidence)));
+        }
+    }
+
+    Ok(None)
+}
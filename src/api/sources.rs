@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State},
+    response::Response,
+};
+use tracing::error;
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::{APIError, ErrorCode},
+        format::{ResponseFormat, format_response},
+    },
+};
+
+pub(crate) async fn sources_list(
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    let sources = database::select_all_sources(&*shared_state.api_db.lock().await)
+        .await
+        .map_err(|e| {
+            error!("Veritabanı kaynakları sorgularken hata oluştu: {:?}", e);
+            APIError::new(ErrorCode::DatabaseError)
+        })?;
+
+    format_response(format, &sources)
+}
+
+pub(crate) async fn source(
+    Path(id): Path<i64>,
+    format: ResponseFormat,
+    State(shared_state): State<SharedState>,
+) -> Result<Response, APIError> {
+    let source = database::select_source_by_id(&*shared_state.api_db.lock().await, id)
+        .await
+        .map_err(|e| {
+            error!("Veritabanı kaynak bilgisi sorgularken hata oluştu: {:?}", e);
+            APIError::new(ErrorCode::SourceNotFound)
+        })?;
+
+    format_response(format, &source)
+}
@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::core::str::fold_key;
+
+// Her düğümde saklanan en fazla öneri sayısı, aramada bundan fazlası istenemiyor
+pub(crate) const MAX_SUGGESTIONS: usize = 10;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct Suggestion {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+}
+
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<char, usize>,
+    // Bu ön eke uyan en iyi öneriler, sorguda alt ağacı gezmemek için ekleme sırasında hesaplanıyor
+    suggestions: Vec<usize>,
+}
+
+// Yemek isimlerinden başlangıçta oluşturulan ön ek ağacı (trie). Her tuş vuruşunda veritabanına gitmemek için
+// öneriler bellekten, sorgu uzunluğuyla orantılı sürede dönüyor
+pub(crate) struct PrefixIndex {
+    nodes: Vec<Node>,
+    entries: Vec<Suggestion>,
+    // Tıklama beacon'ında slug'ın var olup olmadığına tüm yemekleri gezmeden bakabilmek için
+    slugs: HashSet<String>,
+}
+
+impl Default for PrefixIndex {
+    fn default() -> Self {
+        PrefixIndex {
+            nodes: vec![Node::default()],
+            entries: Vec::new(),
+            slugs: HashSet::new(),
+        }
+    }
+}
+
+impl PrefixIndex {
+    pub(crate) fn build(mut entries: Vec<Suggestion>) -> Self {
+        // Kısa isimler daha genel yemekler oluyor, örneğin "el" için önce "Elma" sonra "Fuji Elma" gelmeli
+        entries.sort_by_cached_key(|entry| {
+            let key = fold_key(&entry.description);
+            (key.chars().count(), key)
+        });
+
+        let keys: Vec<String> = entries
+            .iter()
+            .map(|entry| fold_key(&entry.description))
+            .collect();
+        let slugs = entries.iter().map(|entry| entry.slug.clone()).collect();
+        let mut index = PrefixIndex {
+            entries,
+            slugs,
+            ..Default::default()
+        };
+
+        // Önce ismin başından eşleşenleri, sonra ismin içindeki kelimelerden eşleşenleri ekliyoruz, böylece "elma" için
+        // "Elma Suyu", "Fuji Elma"dan önce geliyor
+        for (idx, key) in keys.iter().enumerate() {
+            index.insert(key, idx);
+        }
+        for (idx, key) in keys.iter().enumerate() {
+            for (start, _) in key.match_indices(' ') {
+                index.insert(&key[start + 1..], idx);
+            }
+        }
+
+        index
+    }
+
+    fn insert(&mut self, key: &str, entry: usize) {
+        let mut node = 0;
+        for c in key.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(c, child);
+                    child
+                }
+            };
+
+            let suggestions = &mut self.nodes[node].suggestions;
+            if suggestions.len() < MAX_SUGGESTIONS && !suggestions.contains(&entry) {
+                suggestions.push(entry);
+            }
+        }
+    }
+
+    pub(crate) fn lookup(&self, query: &str, limit: usize) -> Vec<&Suggestion> {
+        let key = fold_key(query);
+        // Boş sorgu için tüm yemekleri önermek anlamsız
+        if key.is_empty() {
+            return Vec::new();
+        }
+
+        let mut node = 0;
+        for c in key.chars() {
+            match self.nodes[node].children.get(&c) {
+                Some(&child) => node = child,
+                None => return Vec::new(),
+            }
+        }
+
+        self.nodes[node]
+            .suggestions
+            .iter()
+            .take(limit)
+            .map(|&idx| &self.entries[idx])
+            .collect()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn contains_slug(&self, slug: &str) -> bool {
+        self.slugs.contains(slug)
+    }
+
+    pub(crate) fn entries(&self) -> &[Suggestion] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(slug: &str, description: &str) -> Suggestion {
+        Suggestion {
+            slug: slug.to_owned(),
+            description: description.to_owned(),
+        }
+    }
+
+    fn slugs(suggestions: Vec<&Suggestion>) -> Vec<&str> {
+        suggestions
+            .into_iter()
+            .map(|suggestion| suggestion.slug.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_prefix_index_lookup() {
+        let index = PrefixIndex::build(vec![
+            suggestion("fuji-elma", "Fuji Elma"),
+            suggestion("elma-suyu", "Elma Suyu"),
+            suggestion("elma", "Elma"),
+            suggestion("tavuk-gogsu-haslanmis", "Tavuk Göğsü (Haşlanmış)"),
+            suggestion("kasar-peyniri", "Kaşar Peyniri"),
+        ]);
+
+        assert_eq!(index.len(), 5);
+        assert_eq!(
+            slugs(index.lookup("el", MAX_SUGGESTIONS)),
+            vec!["elma", "elma-suyu", "fuji-elma"]
+        );
+        assert_eq!(slugs(index.lookup("ELMA", 1)), vec!["elma"]);
+        // Türkçe karakterli ve ASCII yazım aynı sonucu veriyor
+        assert_eq!(slugs(index.lookup("kaş", 5)), vec!["kasar-peyniri"]);
+        assert_eq!(slugs(index.lookup("kas", 5)), vec!["kasar-peyniri"]);
+        assert_eq!(
+            slugs(index.lookup("tavuk  göğsü (h", 5)),
+            vec!["tavuk-gogsu-haslanmis"]
+        );
+        assert_eq!(slugs(index.lookup("peyn", 5)), vec!["kasar-peyniri"]);
+        assert!(index.lookup("armut", 5).is_empty());
+        assert!(index.lookup("  ", 5).is_empty());
+        assert!(index.contains_slug("elma-suyu"));
+        assert!(!index.contains_slug("armut"));
+    }
+}
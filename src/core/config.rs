@@ -11,6 +11,11 @@ use tracing::{debug, info};
 pub(crate) struct Config {
     pub(crate) core: CoreConfig,
     pub(crate) api: APIConfig,
+    // Eski config dosyalarında bu bölüm bulunmuyor, bu durumda varsayılan değerler kullanılıyor
+    #[serde(default)]
+    pub(crate) analytics: AnalyticsConfig,
+    #[serde(default)]
+    pub(crate) popularity: PopularityConfig,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,6 +30,41 @@ pub(crate) struct APIConfig {
     pub(crate) static_url: String,
     pub(crate) search_max_limit: u64,
     pub(crate) health_internet_check_urls: Vec<String>,
+    // /admin endpoint'leri için "Authorization: Bearer {admin_token}" başlığı gerekiyor, boş bırakılırsa bu endpoint'ler kapalı
+    #[serde(default)]
+    pub(crate) admin_token: String,
+}
+
+// Arama sorguları IP adresi olmadan, sadece sorgu, mod, sonuç sayısı ve süre olarak db/analytics.sqlite'a kaydediliyor
+#[derive(Serialize, Deserialize)]
+pub(crate) struct AnalyticsConfig {
+    pub(crate) enabled: bool,
+    pub(crate) retention_days: u64,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        AnalyticsConfig {
+            enabled: true,
+            retention_days: 90,
+        }
+    }
+}
+
+// Aynı puanı alan arama sonuçları görüntülenme ve tıklanma sayısına göre sıralanıyor, puanlar half_life_days günde yarıya iniyor
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PopularityConfig {
+    pub(crate) enabled: bool,
+    pub(crate) half_life_days: f64,
+}
+
+impl Default for PopularityConfig {
+    fn default() -> Self {
+        PopularityConfig {
+            enabled: true,
+            half_life_days: 14.0,
+        }
+    }
 }
 
 pub(crate) fn load_config_with_defaults() -> Result<Config, Error> {
@@ -60,7 +100,10 @@ fn get_default_config() -> Config {
                 "https://github.com".to_owned(),
                 "https://www.wikipedia.org".to_owned(),
             ],
+            admin_token: String::new(),
         },
+        analytics: AnalyticsConfig::default(),
+        popularity: PopularityConfig::default(),
     }
 }
 
@@ -101,4 +144,26 @@ mod tests {
         let deserialized: Config = toml::from_str(&toml_str).unwrap();
         assert_eq!(deserialized.api.base_url, "https://api.besinveri.com");
     }
+
+    #[test]
+    fn old_config_without_new_fields_is_valid() {
+        // Yeni alanlar eklenmeden önce oluşturulmuş config dosyaları da okunabilmeli
+        let toml_str = r#"
+            [core]
+            cache_capacity = 100
+            tracing_level = "TRACE"
+
+            [api]
+            base_url = "https://api.besinveri.com"
+            static_url = "https://besinveri.com/static"
+            search_max_limit = 10
+            health_internet_check_urls = []
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.api.admin_token.is_empty());
+        assert!(config.analytics.enabled);
+        assert_eq!(config.analytics.retention_days, 90);
+        assert!(config.popularity.enabled);
+    }
 }
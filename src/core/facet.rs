@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+// Diyet filtreleri filtre dili ile tanımlanıyor, böylece arayüz bir filtreyi seçtiğinde aynı ifadeyi filter= ile gönderebiliyor
+// Alerjen bilgisi girilmemiş yemekler de alerjensiz sayıldığı için bu sayılar kesin bir garanti değil
+pub(crate) const DIET_FLAGS: [(&str, &str); 5] = [
+    ("vegan", "tag:vegan"),
+    ("glutensiz", "not allergen:gluten"),
+    ("laktozsuz", "not allergen:süt"),
+    ("yumurtasiz", "not allergen:yumurta"),
+    ("yuksek_protein", "protein >= 20"),
+];
+
+// Histogram aralıklarının alt sınırları, son aralığın üst sınırı yok. Değerler 100 gram için
+pub(crate) const ENERGY_BUCKETS: [f64; 6] = [0.0, 50.0, 100.0, 200.0, 400.0, 600.0];
+pub(crate) const PROTEIN_BUCKETS: [f64; 5] = [0.0, 1.0, 5.0, 10.0, 20.0];
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct Bucket {
+    pub(crate) min: f64,
+    pub(crate) max: Option<f64>,
+    pub(crate) count: i64,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct Facets {
+    pub(crate) total: i64,
+    pub(crate) tags: BTreeMap<String, i64>,
+    pub(crate) allergens: BTreeMap<String, i64>,
+    pub(crate) diets: BTreeMap<&'static str, i64>,
+    // Değeri bilinmeyen yemekler histogramlarda sayılmıyor
+    pub(crate) energy: Vec<Bucket>,
+    pub(crate) protein: Vec<Bucket>,
+}
+
+// Veritabanından sadece sonuç bulunan aralıkların sayıları geliyor, boş aralıkları da sıfır olarak ekliyoruz
+pub(crate) fn histogram(edges: &[f64], counts: &BTreeMap<usize, i64>) -> Vec<Bucket> {
+    edges
+        .iter()
+        .enumerate()
+        .map(|(idx, min)| Bucket {
+            min: *min,
+            max: edges.get(idx + 1).copied(),
+            count: counts.get(&idx).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+// Değeri aralıklara ayıran SQL ifadesi, sınırlar sabit olduğu için sorguya doğrudan yazılıyor
+pub(crate) fn bucket_sql(column: &str, edges: &[f64]) -> String {
+    let cases: String = edges
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(idx, edge)| format!(" WHEN {} < {} THEN {}", column, edge, idx - 1))
+        .collect();
+    format!("CASE{} ELSE {} END", cases, edges.len() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_and_bucket_sql() {
+        let buckets = histogram(&PROTEIN_BUCKETS, &BTreeMap::from([(0, 2), (4, 1)]));
+
+        assert_eq!(buckets.len(), PROTEIN_BUCKETS.len());
+        assert_eq!(
+            buckets[0],
+            Bucket {
+                min: 0.0,
+                max: Some(1.0),
+                count: 2
+            }
+        );
+        assert_eq!(buckets[2].count, 0);
+        assert_eq!(
+            buckets[4],
+            Bucket {
+                min: 20.0,
+                max: None,
+                count: 1
+            }
+        );
+
+        assert_eq!(
+            bucket_sql("V", &[0.0, 10.0, 20.0]),
+            "CASE WHEN V < 10 THEN 0 WHEN V < 20 THEN 1 ELSE 2 END"
+        );
+    }
+}
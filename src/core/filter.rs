@@ -0,0 +1,348 @@
+use anyhow::{Error, anyhow};
+
+// Filtre ifadeleri tek bir sorguya çevrildiği için uzunluğunu, terim sayısını ve parantez derinliğini sınırlıyoruz
+pub(crate) const MAX_FILTER_LENGTH: usize = 256;
+const MAX_FILTER_TERMS: usize = 20;
+const MAX_FILTER_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CmpOp {
+    pub(crate) fn as_sql(&self) -> &'static str {
+        match self {
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+            CmpOp::Eq => "=",
+            CmpOp::Ne => "!=",
+        }
+    }
+}
+
+// Karşılaştırılabilen alanlar, besin isimleri nutrients tablosundaki tanımlara göre sonradan kontrol ediliyor
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Field {
+    GlycemicIndex,
+    Density,
+    Nutrient(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, CmpOp, f64),
+    Tag(String),
+    Allergen(String),
+}
+
+impl Expr {
+    // İfadede geçen besin isimleri, tanımlı olmayan bir besinle filtreleme yapılmasını engellemek için
+    pub(crate) fn nutrients(&self) -> Vec<&str> {
+        match self {
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                let mut nutrients = left.nutrients();
+                nutrients.extend(right.nutrients());
+                nutrients
+            }
+            Expr::Not(expr) => expr.nutrients(),
+            Expr::Compare(Field::Nutrient(name), _, _) => vec![name.as_str()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Number(f64),
+    Op(CmpOp),
+    Colon,
+    LParen,
+    RParen,
+}
+
+// "protein >= 10 and fat < 5 and tag:sebze and not allergen:gluten" gibi ifadeleri ayrıştırıyoruz. Öncelik sırası
+// not > and > or, tanımlar parantezle gruplanabiliyor ve boşluk içeren etiketler tırnak içinde yazılıyor: tag:"kümes hayvanı"
+pub(crate) fn parse_filter(input: &str) -> Result<Expr, Error> {
+    if input.len() > MAX_FILTER_LENGTH {
+        return Err(anyhow!(
+            "Filtre en fazla {} bayt olabilir",
+            MAX_FILTER_LENGTH
+        ));
+    }
+
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        terms: 0,
+        depth: 0,
+    };
+    let expr = parser.or()?;
+
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(anyhow!("Filtrede beklenmeyen ifade: {:?}", token));
+    }
+    Ok(expr)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ':' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Colon,
+                });
+            }
+            '<' | '>' | '=' | '!' => {
+                chars.next();
+                let equals = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Op(match (c, equals) {
+                    ('<', false) => CmpOp::Lt,
+                    ('<', true) => CmpOp::Le,
+                    ('>', false) => CmpOp::Gt,
+                    ('>', true) => CmpOp::Ge,
+                    // "=" ve "==" aynı anlamda
+                    ('=', _) => CmpOp::Eq,
+                    ('!', true) => CmpOp::Ne,
+                    _ => return Err(anyhow!("Filtrede geçersiz operatör: {}", c)),
+                }));
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => text.push(c),
+                        None => return Err(anyhow!("Filtrede kapanmamış tırnak var")),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            c if c.is_ascii_digit() || c == '.' || c == '-' => {
+                let mut number = String::new();
+                while let Some(c) =
+                    chars.next_if(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+                {
+                    number.push(c);
+                }
+                // Türkçede ondalık ayırıcı virgül, "1,5" de kabul ediliyor
+                let value = number
+                    .replace(',', ".")
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| anyhow!("Filtrede geçersiz sayı: {}", number))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+            c => return Err(anyhow!("Filtrede geçersiz karakter: {}", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    terms: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // Sıradaki kelime anahtar kelimeyse tüketiyoruz, anahtar kelimelerde büyük/küçük harf farkı yok
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.pos) {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.not()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, Error> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.term()
+    }
+
+    fn term(&mut self) -> Result<Expr, Error> {
+        match self.next() {
+            Some(Token::LParen) => {
+                self.depth += 1;
+                if self.depth > MAX_FILTER_DEPTH {
+                    return Err(anyhow!(
+                        "Filtrede en fazla {} iç içe parantez kullanılabilir",
+                        MAX_FILTER_DEPTH
+                    ));
+                }
+
+                let expr = self.or()?;
+                if self.next() != Some(Token::RParen) {
+                    return Err(anyhow!("Filtrede kapanmamış parantez var"));
+                }
+                self.depth -= 1;
+                Ok(expr)
+            }
+            Some(Token::Word(word)) => {
+                self.terms += 1;
+                if self.terms > MAX_FILTER_TERMS {
+                    return Err(anyhow!(
+                        "Filtrede en fazla {} koşul kullanılabilir",
+                        MAX_FILTER_TERMS
+                    ));
+                }
+
+                let word = word.to_lowercase();
+                match self.next() {
+                    Some(Token::Colon) => {
+                        let value = match self.next() {
+                            Some(Token::Word(value) | Token::Text(value)) => value.to_lowercase(),
+                            _ => {
+                                return Err(anyhow!("{}: sonrasında bir değer bekleniyordu", word));
+                            }
+                        };
+                        match word.as_str() {
+                            "tag" => Ok(Expr::Tag(value)),
+                            "allergen" => Ok(Expr::Allergen(value)),
+                            _ => Err(anyhow!("Filtrede bilinmeyen önek: {}", word)),
+                        }
+                    }
+                    Some(Token::Op(op)) => {
+                        let Some(Token::Number(value)) = self.next() else {
+                            return Err(anyhow!(
+                                "{} karşılaştırmasında bir sayı bekleniyordu",
+                                word
+                            ));
+                        };
+                        let field = match word.as_str() {
+                            "glycemic_index" => Field::GlycemicIndex,
+                            "density" => Field::Density,
+                            _ => Field::Nutrient(word),
+                        };
+                        Ok(Expr::Compare(field, op, value))
+                    }
+                    _ => Err(anyhow!(
+                        "{} sonrasında bir karşılaştırma veya : bekleniyordu",
+                        word
+                    )),
+                }
+            }
+            Some(token) => Err(anyhow!("Filtrede beklenmeyen ifade: {:?}", token)),
+            None => Err(anyhow!("Filtre beklenmedik şekilde bitti")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nutrient(name: &str, op: CmpOp, value: f64) -> Box<Expr> {
+        Box::new(Expr::Compare(Field::Nutrient(name.to_owned()), op, value))
+    }
+
+    #[test]
+    fn test_parse_filter_precedence() {
+        let expr =
+            parse_filter("protein >= 10 and fat < 5 or tag:sebze and not allergen:gluten").unwrap();
+
+        // and, or'dan önce bağlanmalı
+        assert_eq!(
+            expr,
+            Expr::Or(
+                Box::new(Expr::And(
+                    nutrient("protein", CmpOp::Ge, 10.0),
+                    nutrient("fat", CmpOp::Lt, 5.0)
+                )),
+                Box::new(Expr::And(
+                    Box::new(Expr::Tag("sebze".to_owned())),
+                    Box::new(Expr::Not(Box::new(Expr::Allergen("gluten".to_owned()))))
+                ))
+            )
+        );
+        assert_eq!(expr.nutrients(), vec!["protein", "fat"]);
+    }
+
+    #[test]
+    fn test_parse_filter_grouping_and_values() {
+        let expr =
+            parse_filter("NOT (tag:\"Kümes Hayvanı\" OR glycemic_index>50) and fiber=1,5").unwrap();
+
+        assert_eq!(
+            expr,
+            Expr::And(
+                Box::new(Expr::Not(Box::new(Expr::Or(
+                    Box::new(Expr::Tag("kümes hayvanı".to_owned())),
+                    Box::new(Expr::Compare(Field::GlycemicIndex, CmpOp::Gt, 50.0))
+                )))),
+                nutrient("fiber", CmpOp::Eq, 1.5)
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_filter_errors() {
+        assert!(parse_filter("").is_err());
+        assert!(parse_filter("protein >= ").is_err());
+        assert!(parse_filter("protein >= abc").is_err());
+        assert!(parse_filter("(tag:meyve").is_err());
+        assert!(parse_filter("tag:meyve tag:sebze").is_err());
+        assert!(parse_filter("color:kirmizi").is_err());
+        assert!(parse_filter("protein > 1; DROP TABLE foods").is_err());
+        assert!(parse_filter(&"(".repeat(MAX_FILTER_DEPTH + 1)).is_err());
+        assert!(parse_filter(&["tag:a"; MAX_FILTER_TERMS + 1].join(" and ")).is_err());
+    }
+}
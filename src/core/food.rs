@@ -1,8 +1,11 @@
 use std::collections::{BTreeMap};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+use serde_json::Value;
 use sqlx::{Error, FromRow, Row, sqlite::SqliteRow};
 
+use crate::core::{nutrient::Provenance, recipe::Ingredient};
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub(crate) struct Food {
     // ID ve Verified değerleri JSON'dan yükleme yapılırken bulunmayabilir, okurken de bu struct'ı kullanacağımız için Option olarak kullanacağız
@@ -12,33 +15,57 @@ pub(crate) struct Food {
     pub(crate) verified: Option<bool>,
     pub(crate) image_url: String,
     pub(crate) source: String,
+    // Kaynağın detaylarına /sources/{id} üzerinden ulaşılıyor, bağlantı yanıt dönmeden önce dolduruluyor
+    #[serde(skip)]
+    pub(crate) source_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) source_url: Option<String>,
     pub(crate) tags: Vec<String>,
     pub(crate) allergens: Vec<String>,
     pub(crate) servings: BTreeMap<String, f64>,
+    // Üst yemeğin (Fuji Elma için Elma) ve hazırlanış şekli farklı olan temel yemeğin (Tavuk Göğsü (Haşlanmış) için
+    // Tavuk Göğsü (Çiğ)) slug'ları, JSON'dan yüklenirken de bu şekilde giriliyor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) variant_of: Option<String>,
+    // Alt yemekler ve aynı temel yemeğin diğer hazırlanış şekilleri, sadece /food/{slug} üzerinde yükleniyor
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) variants: Vec<String>,
+    // Sadece tariflerde bulunuyor, normal yemeklerde JSON'a hiç yazmıyoruz
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) ingredients: Option<Vec<Ingredient>>,
+    // Hacim ölçülerini grama çevirmek için g/ml cinsinden yoğunluk, sadece sıvı ve tanecikli yemeklerde biliniyor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) density: Option<f64>,
     pub(crate) glycemic_index: f64,
-    pub(crate) energy: f64,
-    pub(crate) carbohydrate: f64,
-    pub(crate) protein: f64,
-    pub(crate) fat: f64,
-    pub(crate) saturated_fat: f64,
-    pub(crate) trans_fat: f64,
-    pub(crate) sugar: f64,
-    pub(crate) fiber: f64,
-    pub(crate) cholesterol: f64,
-    pub(crate) sodium: f64,
-    pub(crate) potassium: f64,
-    pub(crate) water: f64,
-    pub(crate) iron: f64,
-    pub(crate) magnesium: f64,
-    pub(crate) calcium: f64,
-    pub(crate) zinc: f64,
-    pub(crate) vitamin_a: f64,
-    pub(crate) vitamin_b6: f64,
-    pub(crate) vitamin_b12: f64,
-    pub(crate) vitamin_c: f64,
-    pub(crate) vitamin_d: f64,
-    pub(crate) vitamin_e: f64,
-    pub(crate) vitamin_k: f64,
+    // Besin değerlerinin kaynağı ve belirsizliği, ağır bir sorgu olduğu için sadece ?detail=full ile yükleniyor
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) provenance: BTreeMap<String, Provenance>,
+    // Besin değerleri nutrients tablosundaki tanımlara göre tutuluyor, JSON'da eskisi gibi düz alanlar olarak görünüyor
+    // None "veri yok", 0 ise "ölçülmüş ve yok" anlamına geliyor, JSON'da null olarak gösteriliyor
+    #[serde(flatten, deserialize_with = "deserialize_nutrients")]
+    pub(crate) nutrients: BTreeMap<String, Option<f64>>,
+}
+
+// Struct'ta olmayan her anahtar besin değeri olarak okunuyor, sayı veya null olmayan bir değer tüm dosyayı düşürmesin ve
+// hangi alanın hatalı olduğu görülebilsin diye anahtarları önce ham değer olarak okuyoruz
+fn deserialize_nutrients<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Option<f64>>, D::Error> {
+    BTreeMap::<String, Value>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| match value {
+            Value::Null => Ok((key, None)),
+            Value::Number(number) if number.as_f64().is_some() => Ok((key, number.as_f64())),
+            value => Err(D::Error::custom(format!(
+                "{} alanı sayı veya null olmalı, {} girilmiş",
+                key, value
+            ))),
+        })
+        .collect()
 }
 
 impl<'r> FromRow<'r, SqliteRow> for Food {
@@ -60,6 +87,16 @@ impl<'r> FromRow<'r, SqliteRow> for Food {
         let servings_str: String = row.try_get("servings")?;
         let servings = serde_json::from_str(&servings_str).map_err(json_err)?;
 
+        // Tarif olmayan yemeklerde malzeme listesi boş geliyor, bu durumda alanı hiç göstermiyoruz
+        let ingredients_str: String = row.try_get("ingredients")?;
+        let ingredients: Vec<Ingredient> =
+            serde_json::from_str(&ingredients_str).map_err(json_err)?;
+        let ingredients = (!ingredients.is_empty()).then_some(ingredients);
+
+        // Besin değerleri tüm tanımlar için { "isim": değer } şeklinde geliyor, bilinmeyenler null
+        let nutrients_str: String = row.try_get("nutrients")?;
+        let nutrients = serde_json::from_str(&nutrients_str).map_err(json_err)?;
+
         // Son olarak struct'ımızı döndürüyoruz
         Ok(Food {
             id: Some(row.try_get("id")?),
@@ -68,33 +105,82 @@ impl<'r> FromRow<'r, SqliteRow> for Food {
             verified, 
             image_url: row.try_get("image_url")?,
             source: row.try_get("source_description")?,
+            source_id: row.try_get("source_id")?,
+            source_url: None,
             tags,
             allergens,
             servings,
+            parent: row.try_get("parent")?,
+            variant_of: row.try_get("variant_of")?,
+            children: Vec::new(),
+            variants: Vec::new(),
+            ingredients,
+            density: row.try_get("density")?,
             glycemic_index: row.try_get("glycemic_index")?,
+            provenance: BTreeMap::new(),
+            nutrients,
+        })
+    }
+}
+
+// Listelerde dönen hafif yemek özeti, etiket, porsiyon ve diğer besin değerleri url'deki tam kayıttan alınabiliyor
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub(crate) struct FoodSummary {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) image_url: String,
+    pub(crate) url: String,
+    pub(crate) energy: Option<f64>,
+    pub(crate) carbohydrate: Option<f64>,
+    pub(crate) protein: Option<f64>,
+    pub(crate) fat: Option<f64>,
+    // Sadece arama sonuçlarını gruplarken kullanılıyor
+    #[serde(skip)]
+    pub(crate) variant_of: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) variants: Vec<String>,
+}
+
+impl<'r> FromRow<'r, SqliteRow> for FoodSummary {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, Error> {
+        Ok(FoodSummary {
+            slug: row.try_get("slug")?,
+            description: row.try_get("description")?,
+            image_url: row.try_get("image_url")?,
+            // Bağlantı yanıt dönmeden önce API adresiyle dolduruluyor
+            url: String::new(),
             energy: row.try_get("energy")?,
             carbohydrate: row.try_get("carbohydrate")?,
             protein: row.try_get("protein")?,
             fat: row.try_get("fat")?,
-            saturated_fat: row.try_get("saturated_fat")?,
-            trans_fat: row.try_get("trans_fat")?,
-            sugar: row.try_get("sugar")?,
-            fiber: row.try_get("fiber")?,
-            water: row.try_get("water")?,
-            cholesterol: row.try_get("cholesterol")?,
-            sodium: row.try_get("sodium")?,
-            potassium: row.try_get("potassium")?,
-            iron: row.try_get("iron")?,
-            magnesium: row.try_get("magnesium")?,
-            calcium: row.try_get("calcium")?,
-            zinc: row.try_get("zinc")?,
-            vitamin_a: row.try_get("vitamin_a")?,
-            vitamin_b6: row.try_get("vitamin_b6")?,
-            vitamin_b12: row.try_get("vitamin_b12")?,
-            vitamin_c: row.try_get("vitamin_c")?,
-            vitamin_d: row.try_get("vitamin_d")?,
-            vitamin_e: row.try_get("vitamin_e")?,
-            vitamin_k: row.try_get("vitamin_k")?,
+            variant_of: row.try_get("variant_of")?,
+            variants: Vec::new(),
         })
     }
-}
\ No newline at end of file
+}
+
+// Hesaplanan değerleri 2 basamağa yuvarlıyoruz, JSON'da 0.30000000000000004 gibi değerler görmek istemiyoruz
+pub(crate) fn round_nutrient(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl Food {
+    // 100 gramdaki besin değerini ismiyle almak için, besin bilinmiyorsa veya böyle bir besin yoksa None döndürüyoruz
+    pub(crate) fn nutrient(&self, name: &str) -> Option<f64> {
+        self.nutrients.get(name).copied().flatten()
+    }
+
+    // Bir yemeğin "tipik" porsiyonunu döndürüyoruz, karşılaştırmalarda tek bir porsiyon üzerinden konuşabilmek için
+    // Önce Porsiyon (Orta) arıyoruz, yoksa ilk porsiyonu alıyoruz, hiç porsiyon yoksa 100 gram varsayıyoruz
+    pub(crate) fn typical_serving(&self) -> (String, f64) {
+        if let Some(weight) = self.servings.get("Porsiyon (Orta)") {
+            return ("Porsiyon (Orta)".to_owned(), *weight);
+        }
+
+        self.servings
+            .iter()
+            .next()
+            .map(|(description, weight)| (description.to_owned(), *weight))
+            .unwrap_or(("100 Gram".to_owned(), 100.0))
+    }
+}
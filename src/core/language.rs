@@ -0,0 +1,85 @@
+use serde::Serialize;
+
+// Yanıtların dili. Veriler Türkçe giriliyor, diğer diller için çeviri tablolarına bakılıyor ve çevirisi olmayan
+// alanlar Türkçe bırakılıyor
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Language {
+    #[default]
+    Tr,
+    En,
+}
+
+impl Language {
+    pub(crate) const ALL: [Language; 2] = [Language::Tr, Language::En];
+
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Language::Tr => "tr",
+            Language::En => "en",
+        }
+    }
+
+    // "en", "EN" ve "en-US" gibi bölgeli kodlar kabul ediliyor
+    pub(crate) fn from_code(code: &str) -> Option<Self> {
+        let primary = code.trim().split(['-', '_']).next().unwrap_or_default();
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code().eq_ignore_ascii_case(primary))
+    }
+
+    // Accept-Language başlığındaki desteklenen diller arasından q değeri en yüksek olanı seçiyoruz,
+    // "de-DE,de;q=0.9,en;q=0.8" için İngilizce dönüyor
+    pub(crate) fn from_accept_language(header: &str) -> Option<Self> {
+        let mut best: Option<(f32, Language)> = None;
+        for part in header.split(',') {
+            let mut pieces = part.split(';');
+            let Some(language) = pieces.next().and_then(Language::from_code) else {
+                continue;
+            };
+            let quality = pieces
+                .find_map(|piece| piece.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+
+            // Eşit q değerlerinde başlıkta önce yazılan tercih ediliyor
+            if quality > 0.0 && best.is_none_or(|(best, _)| quality > best) {
+                best = Some((quality, language));
+            }
+        }
+        best.map(|(_, language)| language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_code() {
+        assert_eq!(Language::from_code("en"), Some(Language::En));
+        assert_eq!(Language::from_code("EN-us"), Some(Language::En));
+        assert_eq!(Language::from_code("tr_TR"), Some(Language::Tr));
+        assert_eq!(Language::from_code("de"), None);
+        assert_eq!(Language::from_code(""), None);
+    }
+
+    #[test]
+    fn test_language_from_accept_language() {
+        assert_eq!(
+            Language::from_accept_language("de-DE,de;q=0.9,en;q=0.8"),
+            Some(Language::En)
+        );
+        assert_eq!(
+            Language::from_accept_language("en;q=0.5, tr-TR"),
+            Some(Language::Tr)
+        );
+        assert_eq!(
+            Language::from_accept_language("en-GB,tr;q=0.9"),
+            Some(Language::En)
+        );
+        assert_eq!(Language::from_accept_language("en;q=0"), None);
+        assert_eq!(Language::from_accept_language("*"), None);
+        assert_eq!(Language::from_accept_language(""), None);
+    }
+}
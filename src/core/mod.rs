@@ -1,3 +1,21 @@
 pub(crate) mod food;
 pub(crate) mod str;
-pub(crate) mod config;
\ No newline at end of file
+pub(crate) mod config;
+pub(crate) mod autocomplete;
+pub(crate) mod facet;
+pub(crate) mod filter;
+pub(crate) mod language;
+pub(crate) mod nutrient;
+pub(crate) mod optimizer;
+pub(crate) mod parser;
+pub(crate) mod popularity;
+pub(crate) mod recipe;
+pub(crate) mod source;
+pub(crate) mod stem;
+pub(crate) mod substitute;
+pub(crate) mod suggest;
+pub(crate) mod table;
+pub(crate) mod tag;
+pub(crate) mod translation;
+pub(crate) mod units;
+pub(crate) mod value;
\ No newline at end of file
@@ -0,0 +1,138 @@
+use anyhow::{Error, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::core::food::round_nutrient;
+
+// nutrients tablosundaki bir besin tanımı, yemeklerin JSON'daki besin alanları bu tanımların isimleri ile aynı
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub(crate) struct Nutrient {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) unit: String,
+}
+
+// Besin değerinin nasıl elde edildiği
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Method {
+    // Laboratuvarda bu yemek için ölçülmüş
+    LabAnalysis,
+    // Tariflerdeki gibi başka değerlerden hesaplanmış
+    Calculated,
+    // USDA, TürKomp gibi başka bir veri tabanından alınmış
+    Borrowed,
+    // Benzer yemeklerden tahmin edilmiş
+    Estimated,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl Method {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Method::LabAnalysis => "lab_analysis",
+            Method::Calculated => "calculated",
+            Method::Borrowed => "borrowed",
+            Method::Estimated => "estimated",
+        }
+    }
+}
+
+impl Confidence {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Confidence::High => "high",
+            Confidence::Medium => "medium",
+            Confidence::Low => "low",
+        }
+    }
+}
+
+// Tek bir besin değerinin kaynağı ve belirsizliği, klinik kullanıcılar bir değere ne kadar güvenebileceklerini bilmeli
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub(crate) struct Provenance {
+    pub(crate) method: Method,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) confidence: Option<Confidence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) std_dev: Option<f64>,
+}
+
+impl Provenance {
+    pub(crate) fn calculated() -> Self {
+        Provenance {
+            method: Method::Calculated,
+            source: None,
+            confidence: None,
+            min: None,
+            max: None,
+            std_dev: None,
+        }
+    }
+
+    // Aralık değeri kapsamalı ve standart sapma negatif olmamalı
+    pub(crate) fn validate(&self, nutrient: &str, value: f64) -> Result<(), Error> {
+        if self.min.is_some_and(|min| min > value) || self.max.is_some_and(|max| max < value) {
+            return Err(anyhow!(
+                "{} değeri ({}) belirtilen aralığın dışında",
+                nutrient,
+                value
+            ));
+        }
+
+        if self
+            .std_dev
+            .is_some_and(|std_dev| !std_dev.is_finite() || std_dev < 0.0)
+        {
+            return Err(anyhow!("{} için standart sapma negatif olamaz", nutrient));
+        }
+
+        Ok(())
+    }
+
+    // Porsiyon hesaplamalarında aralık ve sapma da değerin kendisiyle aynı oranda değişiyor
+    pub(crate) fn scale(&mut self, factor: f64) {
+        for value in [&mut self.min, &mut self.max, &mut self.std_dev]
+            .into_iter()
+            .flatten()
+        {
+            *value = round_nutrient(*value * factor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provenance_validate_and_scale() {
+        let mut provenance: Provenance = serde_json::from_str(
+            r#"{ "method": "lab_analysis", "confidence": "high", "min": 80, "max": 100, "std_dev": 4 }"#,
+        )
+        .unwrap();
+
+        assert!(provenance.validate("energy", 89.0).is_ok());
+        assert!(provenance.validate("energy", 120.0).is_err());
+
+        provenance.scale(1.5);
+        assert_eq!(provenance.min, Some(120.0));
+        assert_eq!(provenance.max, Some(150.0));
+        assert_eq!(provenance.std_dev, Some(6.0));
+
+        provenance.std_dev = Some(-1.0);
+        assert!(provenance.validate("energy", 130.0).is_err());
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use microlp::{ComparisonOp, LinearExpr, OptimizationDirection, Problem, Variable};
+use serde::{Deserialize, Serialize};
+
+use crate::core::food::{Food, round_nutrient};
+
+// Bir yemeğin bir porsiyonundan en fazla kaç tane önerebileceğimiz, 5 kase yulaf gibi gerçekçi olmayan planları engelliyor
+const MAX_SERVINGS_PER_ITEM: i32 = 3;
+// Hedeflere aynı derecede yakın iki plan arasında daha az porsiyon içereni seçmek için her porsiyona çok küçük bir maliyet veriyoruz
+const SERVING_COST: f64 = 0.001;
+// Aday porsiyonlarla bulunan planın LP alt sınırından en fazla bu kadar kötü olmasına izin veriyoruz, hedeflerden toplam
+// %5'lik sapmaya karşılık geliyor
+const MAX_INTEGRALITY_GAP: f64 = 0.05;
+// Aday porsiyonlarla bulunan plan yetersizse probleme en fazla bu kadar tipik porsiyon ekliyoruz, tam sayılı problemin
+// boyutu yemek sayısından bağımsız kalsın ve istek başına çözüm süresi sınırlı olsun diye
+const MAX_WIDENED_CANDIDATES: usize = 8;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Bounds {
+    pub(crate) min: Option<f64>,
+    pub(crate) max: Option<f64>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub(crate) struct MealTargets {
+    pub(crate) energy: f64,
+    pub(crate) protein: Option<f64>,
+    pub(crate) carbohydrate: Option<f64>,
+    pub(crate) fat: Option<f64>,
+    #[serde(default)]
+    pub(crate) nutrients: BTreeMap<String, Bounds>,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    #[serde(default)]
+    pub(crate) exclude_allergens: Vec<String>,
+}
+
+impl MealTargets {
+    // Tam olarak yakalamaya çalıştığımız hedefler, min/max sınırlarından farklı olarak sapma serbest ama cezalı
+    fn goals(&self) -> Vec<(&'static str, f64)> {
+        [
+            ("energy", Some(self.energy)),
+            ("protein", self.protein),
+            ("carbohydrate", self.carbohydrate),
+            ("fat", self.fat),
+        ]
+        .into_iter()
+        .filter_map(|(name, target)| target.map(|target| (name, target)))
+        .collect()
+    }
+
+    pub(crate) fn accepts(&self, food: &Food) -> bool {
+        (self.tags.is_empty() || food.tags.iter().any(|tag| self.tags.contains(tag)))
+            && !food
+                .allergens
+                .iter()
+                .any(|allergen| self.exclude_allergens.contains(allergen))
+            // Hedeflenen bir besini bilinmeyen yemeği plana koyarsak hedefi tutturup tutturmadığımızı bilemeyiz
+            && self
+                .goals()
+                .iter()
+                .map(|(nutrient, _)| *nutrient)
+                .chain(self.nutrients.keys().map(String::as_str))
+                .all(|nutrient| food.nutrient(nutrient).is_some())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub(crate) struct MealItem {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) serving: String,
+    pub(crate) quantity: u32,
+    pub(crate) weight: f64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub(crate) struct MealPlan {
+    pub(crate) items: Vec<MealItem>,
+    pub(crate) totals: BTreeMap<String, f64>,
+    // Plandaki yemeklerden en az birinde değeri bilinmeyen besinler, bunların toplamı gerçek değerden düşük olabilir
+    pub(crate) incomplete: Vec<String>,
+}
+
+// Tüm yemeklerin tüm porsiyonları üzerinde tam sayılı problemi çözmek (branch and bound) birkaç düzine değişkende bile
+// dakikalar sürebiliyor. Bu yüzden önce sürekli (LP) gevşetmeyi çözüp kullanılan porsiyonları seçiyoruz, daha sonra
+// sadece bu az sayıdaki aday üzerinde tam sayılı problemi çözüyoruz. LP'nin temel çözümleri kısıt sayısı kadar sıfır
+// olmayan değişken içerdiği için ikinci aşamadaki problem her zaman küçük kalıyor. LP'nin seçmediği yemeklerle
+// kurulabilen daha iyi bir plan olabileceği için (1 muz + 2 tavuk gibi) küçük problem çözümsüz kalırsa veya sonucu LP
+// sınırından uzaksa adaylara hedeflerden kalan sapmayı en çok azaltan birkaç tipik porsiyonu ekleyip bir kez daha
+// çözüyoruz. Genişletilmiş problem de sınırı tutturamazsa daha fazla aday eklemiyoruz, bulunan en iyi planı döndürüyoruz.
+pub(crate) fn optimize_meal(
+    targets: &MealTargets,
+    foods: &[Food],
+) -> Result<MealPlan, microlp::Error> {
+    // Her yemeğin her porsiyonu bir aday, değeri o porsiyondan kaç tane yeneceği
+    let candidates: Vec<(&Food, &String, f64)> = foods
+        .iter()
+        .filter(|food| targets.accepts(food))
+        .flat_map(|food| {
+            food.servings
+                .iter()
+                .map(move |(serving, weight)| (food, serving, *weight))
+        })
+        .collect();
+
+    // LP çözümsüzse tam sayılı problem de çözümsüz
+    let (bound, relaxed) = solve(targets, &candidates, false)?;
+
+    // LP'de kullanılan porsiyonları ve bu yemeklerin tipik porsiyonlarını tam sayılı aşamaya taşıyoruz
+    let mut selected: Vec<(&Food, &String, f64)> = Vec::new();
+    for (candidate, quantity) in candidates.iter().zip(&relaxed) {
+        if *quantity <= 1e-6 {
+            continue;
+        }
+
+        let (food, _, _) = candidate;
+        let typical = candidates.iter().find(|(other, serving, _)| {
+            other.slug == food.slug && **serving == food.typical_serving().0
+        });
+
+        for item in [Some(candidate), typical].into_iter().flatten() {
+            if !selected
+                .iter()
+                .any(|(food, serving, _)| food.slug == item.0.slug && serving == &item.1)
+            {
+                selected.push(*item);
+            }
+        }
+    }
+
+    let first = solve(targets, &selected, true);
+    let (selected, quantities) = match first {
+        Ok((objective, quantities)) if objective - bound <= MAX_INTEGRALITY_GAP => {
+            (selected, quantities)
+        }
+        Ok(_) | Err(microlp::Error::Infeasible) => {
+            // Sapmayı tam sayılı plandan, plan yoksa LP çözümünün aşağı yuvarlanmış halinden hesaplıyoruz
+            let base = match &first {
+                Ok((_, quantities)) => totals(&selected, quantities),
+                Err(_) => totals(
+                    &candidates,
+                    &relaxed.iter().map(|q| q.floor()).collect::<Vec<f64>>(),
+                ),
+            };
+            let widened = widen_candidates(targets, &candidates, &selected, &base);
+            // Önceki adaylar korunduğu için genişletilmiş problemin planı hiçbir zaman daha kötü olmuyor
+            match (solve(targets, &widened, true), first) {
+                (Ok((_, quantities)), _) => (widened, quantities),
+                (Err(_), Ok((_, quantities))) => (selected, quantities),
+                (Err(e), Err(_)) => return Err(e),
+            }
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut items = Vec::new();
+    let mut incomplete = BTreeSet::new();
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    for ((food, serving, weight), quantity) in selected.iter().zip(quantities) {
+        if quantity < 1.0 {
+            continue;
+        }
+
+        for (nutrient, value) in &food.nutrients {
+            let total = totals.entry(nutrient.to_owned()).or_default();
+            match value {
+                Some(value) => *total += value * weight * quantity / 100.0,
+                None => {
+                    incomplete.insert(nutrient.to_owned());
+                }
+            }
+        }
+
+        items.push(MealItem {
+            slug: food.slug.clone().unwrap_or_default(),
+            description: food.description.clone(),
+            serving: serving.to_string(),
+            quantity: quantity as u32,
+            weight: weight * quantity,
+        });
+    }
+
+    totals
+        .values_mut()
+        .for_each(|total| *total = round_nutrient(*total));
+
+    Ok(MealPlan {
+        items,
+        totals,
+        incomplete: incomplete.into_iter().collect(),
+    })
+}
+
+// Seçilen adaylara, bir porsiyonu eklendiğinde hedeflerden sapmayı en çok azaltan en fazla MAX_WIDENED_CANDIDATES
+// tipik porsiyonu ekliyoruz
+fn widen_candidates<'a>(
+    targets: &MealTargets,
+    candidates: &[(&'a Food, &'a String, f64)],
+    selected: &[(&'a Food, &'a String, f64)],
+    base: &BTreeMap<String, f64>,
+) -> Vec<(&'a Food, &'a String, f64)> {
+    let base_deviation = deviation(targets, base);
+    let mut scored: Vec<(f64, (&Food, &String, f64))> = candidates
+        .iter()
+        .filter(|(food, serving, _)| {
+            **serving == food.typical_serving().0
+                && !selected.iter().any(|(other, other_serving, _)| {
+                    other.slug == food.slug && other_serving == serving
+                })
+        })
+        .map(|candidate| {
+            let mut added = base.clone();
+            for (nutrient, total) in totals(&[*candidate], &[1.0]) {
+                *added.entry(nutrient).or_default() += total;
+            }
+            (base_deviation - deviation(targets, &added), *candidate)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    selected
+        .iter()
+        .copied()
+        .chain(
+            scored
+                .into_iter()
+                .take(MAX_WIDENED_CANDIDATES)
+                .map(|(_, candidate)| candidate),
+        )
+        .collect()
+}
+
+fn totals(candidates: &[(&Food, &String, f64)], quantities: &[f64]) -> BTreeMap<String, f64> {
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    for ((food, _, weight), quantity) in candidates.iter().zip(quantities) {
+        for (nutrient, value) in &food.nutrients {
+            *totals.entry(nutrient.to_owned()).or_default() +=
+                value.unwrap_or(0.0) * weight * quantity / 100.0;
+        }
+    }
+    totals
+}
+
+// solve ile aynı ölçekte hedeflerden sapma, min/max sınırlarının aşılması da aynı şekilde hedefe bölünerek ekleniyor
+fn deviation(targets: &MealTargets, totals: &BTreeMap<String, f64>) -> f64 {
+    let total = |nutrient: &str| totals.get(nutrient).copied().unwrap_or(0.0);
+    let goals: f64 = targets
+        .goals()
+        .iter()
+        .map(|(nutrient, target)| (total(nutrient) - target).abs() / target.max(1.0))
+        .sum();
+    let bounds: f64 = targets
+        .nutrients
+        .iter()
+        .map(|(nutrient, bounds)| {
+            let total = total(nutrient);
+            let below = bounds
+                .min
+                .map_or(0.0, |min| (min - total).max(0.0) / min.max(1.0));
+            let above = bounds
+                .max
+                .map_or(0.0, |max| (total - max).max(0.0) / max.max(1.0));
+            below + above
+        })
+        .sum();
+    goals + bounds
+}
+
+fn solve(
+    targets: &MealTargets,
+    candidates: &[(&Food, &String, f64)],
+    integer: bool,
+) -> Result<(f64, Vec<f64>), microlp::Error> {
+    let mut problem = Problem::new(OptimizationDirection::Minimize);
+
+    let variables: Vec<Variable> = candidates
+        .iter()
+        .map(|_| {
+            if integer {
+                problem.add_integer_var(SERVING_COST, (0, MAX_SERVINGS_PER_ITEM))
+            } else {
+                problem.add_var(SERVING_COST, (0.0, MAX_SERVINGS_PER_ITEM as f64))
+            }
+        })
+        .collect();
+
+    let amount_expr = |nutrient: &str| -> LinearExpr {
+        variables
+            .iter()
+            .zip(candidates)
+            .map(|(variable, (food, _, weight))| {
+                (
+                    *variable,
+                    food.nutrient(nutrient).unwrap_or(0.0) * weight / 100.0,
+                )
+            })
+            .collect()
+    };
+
+    // Hedefler için sapma değişkenleri ekliyoruz: toplam - fazla + eksik = hedef
+    // Sapmaları hedefe bölerek cezalandırıyoruz ki 2000 kcal ile 60 gram yağ aynı ölçekte karşılaştırılabilsin
+    for (nutrient, target) in targets.goals() {
+        let weight = 1.0 / target.max(1.0);
+        let over = problem.add_var(weight, (0.0, f64::INFINITY));
+        let under = problem.add_var(weight, (0.0, f64::INFINITY));
+
+        let mut expr = amount_expr(nutrient);
+        expr.add(over, -1.0);
+        expr.add(under, 1.0);
+        problem.add_constraint(expr, ComparisonOp::Eq, target);
+    }
+
+    for (nutrient, bounds) in &targets.nutrients {
+        if let Some(min) = bounds.min {
+            problem.add_constraint(amount_expr(nutrient), ComparisonOp::Ge, min);
+        }
+        if let Some(max) = bounds.max {
+            problem.add_constraint(amount_expr(nutrient), ComparisonOp::Le, max);
+        }
+    }
+
+    let solution = problem.solve()?;
+
+    let quantities = variables
+        .iter()
+        .map(|variable| {
+            if integer {
+                solution.var_value_rounded(*variable)
+            } else {
+                *solution.var_value(*variable)
+            }
+        })
+        .collect();
+    Ok((solution.objective(), quantities))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_food(
+        slug: &str,
+        tags: &[&str],
+        allergens: &[&str],
+        energy: f64,
+        protein: f64,
+    ) -> Food {
+        Food {
+            slug: Some(slug.to_owned()),
+            description: slug.to_owned(),
+            verified: Some(true),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            allergens: allergens
+                .iter()
+                .map(|allergen| allergen.to_string())
+                .collect(),
+            servings: BTreeMap::from([("Porsiyon (Orta)".to_owned(), 100.0)]),
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(energy)),
+                ("protein".to_owned(), Some(protein)),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_optimize_meal_hits_energy_target() {
+        let foods = vec![
+            create_food("muz", &["meyve"], &[], 89.0, 1.1),
+            create_food("tavuk", &["et"], &[], 120.0, 22.5),
+        ];
+        let targets = MealTargets {
+            energy: 329.0,
+            ..Default::default()
+        };
+
+        let plan = optimize_meal(&targets, &foods).unwrap();
+
+        // 1 muz + 2 tavuk tam olarak 329 kcal ediyor
+        assert_eq!(plan.totals["energy"], 329.0);
+        assert_eq!(plan.items.len(), 2);
+    }
+
+    #[test]
+    fn test_optimize_meal_exact_bounds() {
+        let foods = vec![
+            create_food("muz", &["meyve"], &[], 89.0, 1.1),
+            create_food("tavuk", &["et"], &[], 120.0, 22.5),
+        ];
+        let targets = MealTargets {
+            energy: 300.0,
+            nutrients: BTreeMap::from([(
+                "energy".to_owned(),
+                Bounds {
+                    min: Some(329.0),
+                    max: Some(329.0),
+                },
+            )]),
+            ..Default::default()
+        };
+
+        // LP gevşetmesinin seçmediği porsiyonlarla kurulabilen tek plan bulunmalı
+        let plan = optimize_meal(&targets, &foods).unwrap();
+        assert_eq!(plan.totals["energy"], 329.0);
+    }
+
+    #[test]
+    fn test_widen_candidates_is_capped() {
+        let mut foods: Vec<Food> = (0..50)
+            .map(|i| create_food(&format!("yemek-{}", i), &[], &[], 400.0 + i as f64, 5.0))
+            .collect();
+        foods.push(create_food("muz", &["meyve"], &[], 89.0, 1.1));
+        foods.push(create_food("tavuk", &["et"], &[], 120.0, 22.5));
+        let targets = MealTargets {
+            energy: 329.0,
+            ..Default::default()
+        };
+        let candidates: Vec<(&Food, &String, f64)> = foods
+            .iter()
+            .flat_map(|food| {
+                food.servings
+                    .iter()
+                    .map(move |(serving, weight)| (food, serving, *weight))
+            })
+            .collect();
+        let tavuk = candidates[51];
+        let base = totals(&[tavuk], &[2.0]);
+
+        // 52 yemeğin hepsi değil, sadece sınır kadar aday eklenmeli ve sapmayı en çok azaltan muz bunların arasında olmalı
+        let widened = widen_candidates(&targets, &candidates, &[tavuk], &base);
+        assert_eq!(widened.len(), 1 + MAX_WIDENED_CANDIDATES);
+        assert_eq!(widened[1].0.slug.as_deref(), Some("muz"));
+
+        let plan = optimize_meal(&targets, &foods).unwrap();
+        assert_eq!(plan.totals["energy"], 329.0);
+    }
+
+    #[test]
+    fn test_optimize_meal_respects_filters() {
+        let foods = vec![
+            create_food("muz", &["meyve"], &[], 89.0, 1.1),
+            create_food("yumurta", &["protein"], &["yumurta"], 155.0, 13.0),
+            create_food("tavuk", &["et"], &[], 120.0, 22.5),
+        ];
+        let targets = MealTargets {
+            energy: 300.0,
+            tags: vec!["meyve".to_owned(), "protein".to_owned()],
+            exclude_allergens: vec!["yumurta".to_owned()],
+            ..Default::default()
+        };
+
+        let plan = optimize_meal(&targets, &foods).unwrap();
+
+        assert!(plan.items.iter().all(|item| item.slug == "muz"));
+        assert_eq!(plan.items[0].quantity, 3);
+    }
+
+    #[test]
+    fn test_optimize_meal_infeasible_bounds() {
+        let foods = vec![create_food("muz", &["meyve"], &[], 89.0, 1.1)];
+        let targets = MealTargets {
+            energy: 200.0,
+            nutrients: BTreeMap::from([(
+                "protein".to_owned(),
+                Bounds {
+                    min: Some(50.0),
+                    max: None,
+                },
+            )]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            optimize_meal(&targets, &foods),
+            Err(microlp::Error::Infeasible)
+        );
+    }
+
+    #[test]
+    fn test_optimize_meal_reports_incomplete_nutrients() {
+        let mut tavuk = create_food("tavuk", &["et"], &[], 120.0, 22.5);
+        tavuk.nutrients.insert("vitamin_c".to_owned(), Some(0.0));
+        tavuk.nutrients.insert("vitamin_d".to_owned(), None);
+        let mut yumurta = create_food("yumurta", &["protein"], &[], 155.0, 13.0);
+        yumurta.nutrients.insert("protein".to_owned(), None);
+
+        let targets = MealTargets {
+            energy: 360.0,
+            protein: Some(60.0),
+            ..Default::default()
+        };
+
+        let plan = optimize_meal(&targets, &[tavuk, yumurta]).unwrap();
+
+        // Proteini bilinmeyen yumurta, protein hedefi olan bir plana girmemeli
+        assert!(plan.items.iter().all(|item| item.slug == "tavuk"));
+        // Bilinen değerler toplanmalı, D vitamini ise eksik olarak raporlanmalı
+        assert_eq!(plan.totals["vitamin_c"], 0.0);
+        assert_eq!(plan.incomplete, vec!["vitamin_d".to_owned()]);
+    }
+}
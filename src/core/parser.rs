@@ -0,0 +1,251 @@
+use serde::Serialize;
+
+use crate::core::{
+    food::Food,
+    str::to_lower_en,
+    units::{Amount, Unit, UnitKind, convert_to_grams, find_unit},
+};
+
+// Rakam yerine yazıyla girilen miktarlar, "yarım yemek kaşığı" gibi
+const NUMBER_WORDS: [(&str, f64); 12] = [
+    ("ceyrek", 0.25),
+    ("yarim", 0.5),
+    ("bir", 1.0),
+    ("iki", 2.0),
+    ("uc", 3.0),
+    ("dort", 4.0),
+    ("bes", 5.0),
+    ("alti", 6.0),
+    ("yedi", 7.0),
+    ("sekiz", 8.0),
+    ("dokuz", 9.0),
+    ("on", 10.0),
+];
+
+// Porsiyonların parantez içindeki boyut bilgisi ile eşleşen kelimeler, ör: "Adet (Orta)" için "orta boy"
+const SIZE_WORDS: [&str; 6] = ["kucuk", "orta", "buyuk", "tepeleme", "silme", "tam"];
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct ParsedLine {
+    pub(crate) quantity: f64,
+    #[serde(skip)]
+    pub(crate) unit: Option<&'static Unit>,
+    pub(crate) size: Option<String>,
+    // Miktar ve birim çıkarıldıktan sonra kalan, orijinal yazımıyla yemek ismi
+    pub(crate) food_query: Vec<String>,
+}
+
+pub(crate) fn parse_line(line: &str) -> ParsedLine {
+    let tokens = tokenize(line);
+    let folded: Vec<String> = tokens.iter().map(|token| to_lower_en(token)).collect();
+    let mut pos = 0;
+
+    // Önce miktarı okuyoruz, hiç miktar yoksa 1 kabul ediyoruz ("bir muz" ile "muz" aynı)
+    let mut quantity = None;
+    while let Some(value) = folded.get(pos).and_then(|token| parse_number(token)) {
+        // "1 1/2" gibi kesirli yazımlar için değerleri topluyoruz
+        quantity = Some(quantity.unwrap_or(0.0) + value);
+        pos += 1;
+    }
+    if folded.get(pos).is_some_and(|token| token == "bucuk") {
+        quantity = Some(quantity.unwrap_or(1.0) + 0.5);
+        pos += 1;
+    }
+
+    // Birim ve boyut kelimeleri her sırada gelebiliyor: "1 adet orta boy muz" veya "1 orta boy muz"
+    let mut unit = None;
+    let mut size = None;
+    loop {
+        if unit.is_none() {
+            // İki kelimelik birimleri ("su bardağı") tek kelimelik olanlardan önce deniyoruz
+            if let Some(found) = folded
+                .get(pos..pos + 2)
+                .and_then(|words| find_unit(&words.join(" ")))
+            {
+                unit = Some(found);
+                pos += 2;
+                continue;
+            }
+            if let Some(found) = folded.get(pos).and_then(|word| find_unit(word)) {
+                unit = Some(found);
+                pos += 1;
+                continue;
+            }
+        }
+
+        if let Some(word) = folded
+            .get(pos)
+            .filter(|word| size.is_none() && SIZE_WORDS.contains(&word.as_str()))
+        {
+            size = Some(word.to_owned());
+            pos += 1;
+            if folded.get(pos).is_some_and(|word| word == "boy") {
+                pos += 1;
+            }
+            continue;
+        }
+
+        break;
+    }
+
+    ParsedLine {
+        quantity: quantity.unwrap_or(1.0),
+        unit,
+        size,
+        food_query: tokens[pos..].to_vec(),
+    }
+}
+
+// Yemek bulunduktan sonra miktarı grama çeviriyoruz, asıl dönüşüm core::units içinde
+pub(crate) fn resolve_amount(parsed: &ParsedLine, food: &Food) -> Option<Amount> {
+    // Boyut belirtilip birim belirtilmediyse adet kastediliyor, "1 orta boy muz" gibi
+    let unit = parsed
+        .unit
+        .or_else(|| parsed.size.as_ref().and_then(|_| find_unit("adet")));
+
+    let Some(unit) = unit else {
+        // Hiç birim yoksa yemeğin tipik porsiyonunu kullanıyoruz ama bu bir tahmin olduğu için güvenimiz düşük
+        let (serving, weight) = food.typical_serving();
+        return Some(Amount {
+            serving: Some(serving),
+            grams: parsed.quantity * weight,
+            confidence: 0.6,
+        });
+    };
+
+    if let Some(amount) = convert_to_grams(food, parsed.quantity, unit, parsed.size.as_deref()) {
+        return Some(amount);
+    }
+
+    // Yemeğin yoğunluğu bilinmiyorsa, tariflerde en sık karşılaşılan durum olan suyun yoğunluğunu varsayıyoruz
+    match unit.kind {
+        UnitKind::Volume(ml) => Some(Amount {
+            serving: None,
+            grams: parsed.quantity * ml,
+            confidence: 0.6,
+        }),
+        _ => None,
+    }
+}
+
+// "200gr" veya "1,5kg" gibi bitişik yazılmış miktar ve birimleri ayırıyoruz
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in line.split_whitespace() {
+        let split = word
+            .char_indices()
+            .find(|(_, c)| !(c.is_ascii_digit() || *c == ',' || *c == '.' || *c == '/'))
+            .map(|(idx, _)| idx);
+
+        match split {
+            Some(idx) if idx > 0 => {
+                tokens.push(word[..idx].to_owned());
+                tokens.push(word[idx..].to_owned());
+            }
+            _ => tokens.push(word.to_owned()),
+        }
+    }
+    tokens
+}
+
+fn parse_number(token: &str) -> Option<f64> {
+    if let Some(&(_, value)) = NUMBER_WORDS.iter().find(|(word, _)| *word == token) {
+        return Some(value);
+    }
+
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        let numerator: f64 = numerator.parse().ok()?;
+        let denominator: f64 = denominator.parse().ok()?;
+        return (denominator != 0.0).then(|| numerator / denominator);
+    }
+
+    // Türkçede ondalık ayırıcı virgül, "1,5 kg" gibi
+    token
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && *value > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn create_food(servings: &[(&str, f64)]) -> Food {
+        Food {
+            slug: Some("test".to_owned()),
+            description: "Test".to_owned(),
+            servings: servings
+                .iter()
+                .map(|(serving, weight)| (serving.to_string(), *weight))
+                .collect::<BTreeMap<String, f64>>(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let parsed = parse_line("2 su bardağı pirinç");
+        assert_eq!(parsed.quantity, 2.0);
+        assert_eq!(parsed.unit.unwrap().name, "Su Bardağı");
+        assert_eq!(parsed.food_query, vec!["pirinç".to_owned()]);
+
+        let parsed = parse_line("1 adet orta boy muz");
+        assert_eq!(parsed.quantity, 1.0);
+        assert_eq!(parsed.unit.unwrap().name, "Adet");
+        assert_eq!(parsed.size, Some("orta".to_owned()));
+        assert_eq!(parsed.food_query, vec!["muz".to_owned()]);
+
+        let parsed = parse_line("yarım yemek kaşığı zeytinyağı");
+        assert_eq!(parsed.quantity, 0.5);
+        assert_eq!(parsed.unit.unwrap().name, "Yemek Kaşığı");
+
+        let parsed = parse_line("1,5kg Tavuk Göğsü");
+        assert_eq!(parsed.quantity, 1.5);
+        assert_eq!(parsed.unit.unwrap().kind, UnitKind::Mass(1000.0));
+        assert_eq!(
+            parsed.food_query,
+            vec!["Tavuk".to_owned(), "Göğsü".to_owned()]
+        );
+
+        let parsed = parse_line("bir buçuk su bardağı su");
+        assert_eq!(parsed.quantity, 1.5);
+
+        let parsed = parse_line("muz");
+        assert_eq!(parsed.quantity, 1.0);
+        assert!(parsed.unit.is_none());
+    }
+
+    #[test]
+    fn test_resolve_amount_from_servings() {
+        let muz = create_food(&[("Adet (Büyük)", 150.0), ("Adet (Orta)", 120.0)]);
+
+        let amount = resolve_amount(&parse_line("2 büyük boy muz"), &muz).unwrap();
+        assert_eq!(amount.serving, Some("Adet (Büyük)".to_owned()));
+        assert_eq!(amount.grams, 300.0);
+        assert_eq!(amount.confidence, 1.0);
+
+        // Boyut belirtilmezse orta boy seçilmeli
+        let amount = resolve_amount(&parse_line("1 adet muz"), &muz).unwrap();
+        assert_eq!(amount.serving, Some("Adet (Orta)".to_owned()));
+    }
+
+    #[test]
+    fn test_resolve_amount_from_units() {
+        let yag = create_food(&[("Porsiyon (Orta)", 10.0)]);
+
+        let amount = resolve_amount(&parse_line("250 gr zeytinyağı"), &yag).unwrap();
+        assert_eq!(amount.grams, 250.0);
+        assert_eq!(amount.confidence, 1.0);
+
+        // Porsiyonlarda yemek kaşığı olmadığı için mutfak ölçüsünden hesaplanmalı
+        let amount = resolve_amount(&parse_line("yarım yemek kaşığı zeytinyağı"), &yag).unwrap();
+        assert_eq!(amount.grams, 7.5);
+        assert!(amount.serving.is_none());
+
+        // Dilim gibi birimlerin genel bir karşılığı yok
+        assert!(resolve_amount(&parse_line("2 dilim zeytinyağı"), &yag).is_none());
+    }
+}
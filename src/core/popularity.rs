@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+// Aramadan tıklanmak görüntülenmeden daha güçlü bir işaret, kullanıcı sonuçlar arasından bu yemeği seçmiş oluyor
+pub(crate) const VIEW_WEIGHT: f64 = 1.0;
+pub(crate) const CLICK_WEIGHT: f64 = 2.0;
+
+// Yemeklerin zamanla sönümlenen popülerlik puanları. Her kayıtta sadece son puan ve zamanı saklanıyor, puan okunurken
+// yarılanma süresine göre azaltılıyor, böylece eski ilgi yeni aramaların sıralamasını sonsuza kadar etkilemiyor
+pub(crate) struct Popularity {
+    half_life: f64,
+    // slug -> (puan, puanın hesaplandığı unix zamanı)
+    scores: HashMap<String, (f64, i64)>,
+}
+
+impl Popularity {
+    pub(crate) fn new(half_life_days: f64) -> Self {
+        Popularity {
+            half_life: half_life_days.max(f64::EPSILON) * 24.0 * 60.0 * 60.0,
+            scores: HashMap::new(),
+        }
+    }
+
+    fn decay(&self, score: f64, from: i64, to: i64) -> f64 {
+        score * 0.5f64.powf((to - from) as f64 / self.half_life)
+    }
+
+    // Kayıtlar sırasız gelebiliyor (başlangıçta veritabanından yüklenirken), eski bir kayıt kendi zamanından bugüne sönümlenerek ekleniyor
+    pub(crate) fn record(&mut self, slug: &str, weight: f64, at: i64) {
+        let (score, updated) = self.scores.get(slug).copied().unwrap_or((0.0, at));
+        let entry = if at >= updated {
+            (self.decay(score, updated, at) + weight, at)
+        } else {
+            (score + self.decay(weight, at, updated), updated)
+        };
+        self.scores.insert(slug.to_owned(), entry);
+    }
+
+    pub(crate) fn score(&self, slug: &str, at: i64) -> f64 {
+        self.scores.get(slug).map_or(0.0, |&(score, updated)| {
+            self.decay(score, updated, at.max(updated))
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.scores.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn test_popularity_decay() {
+        let mut popularity = Popularity::new(7.0);
+        popularity.record("elma", VIEW_WEIGHT, 0);
+        popularity.record("elma", CLICK_WEIGHT, 0);
+        popularity.record("muz", VIEW_WEIGHT, 7 * DAY);
+
+        assert_eq!(popularity.len(), 2);
+        assert_eq!(popularity.score("elma", 0), 3.0);
+        // Bir yarılanma süresi sonra puan yarıya iniyor
+        assert!((popularity.score("elma", 7 * DAY) - 1.5).abs() < 1e-9);
+        assert!((popularity.score("elma", 14 * DAY) - 0.75).abs() < 1e-9);
+        assert_eq!(popularity.score("armut", 0), 0.0);
+
+        // Eski tarihli bir kayıt eklenme sırasından bağımsız olarak aynı puanı veriyor
+        let mut ordered = Popularity::new(7.0);
+        ordered.record("muz", VIEW_WEIGHT, 0);
+        ordered.record("muz", VIEW_WEIGHT, 7 * DAY);
+        popularity.record("muz", VIEW_WEIGHT, 0);
+        assert!((ordered.score("muz", 14 * DAY) - popularity.score("muz", 14 * DAY)).abs() < 1e-9);
+    }
+}
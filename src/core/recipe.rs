@@ -0,0 +1,331 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Error, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    food::{Food, round_nutrient},
+    nutrient::{Nutrient, Provenance},
+};
+
+// Tarifler de db/foods/*.json gibi JSON dosyalarından yükleniyor, farkı besin değerlerinin elle girilmemesi
+// Besin değerleri malzemelerden, pişirme veriminden (yield) ve pişirmede korunma oranlarından (retention) hesaplanıyor
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub(crate) struct Recipe {
+    pub(crate) description: String,
+    pub(crate) verified: Option<bool>,
+    pub(crate) image_url: String,
+    pub(crate) source: String,
+    pub(crate) tags: Vec<String>,
+    // Malzemelerin alerjenleri zaten otomatik ekleniyor, buraya sadece ekstra alerjenler yazılmalı
+    #[serde(default)]
+    pub(crate) allergens: Vec<String>,
+    pub(crate) servings: BTreeMap<String, f64>,
+    // Pişmiş ağırlık / çiğ malzemelerin toplam ağırlığı, ör: suyunu kaybeden bir çorba için 0.85
+    pub(crate) yield_factor: f64,
+    // Pişirmede besinin ne kadarının korunduğu, ör: { "vitamin_c": 0.5 }. Girilmeyen besinler tamamen korunmuş sayılıyor
+    #[serde(default)]
+    pub(crate) retention_factors: BTreeMap<String, f64>,
+    // Çorba gibi hacimle ölçülen tariflerde pişmiş yemeğin g/ml cinsinden yoğunluğu
+    #[serde(default)]
+    pub(crate) density: Option<f64>,
+    pub(crate) ingredients: Vec<RecipeIngredient>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub(crate) struct RecipeIngredient {
+    pub(crate) slug: String,
+    pub(crate) weight: f64,
+}
+
+// Yemek yanıtlarında gösterilen malzeme dökümü
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub(crate) struct Ingredient {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) weight: f64,
+}
+
+impl Recipe {
+    // Korunma oranlarının tanımlı besinler için girildiğini kontrol edebilmek için besin tanımlarını alıyoruz
+    pub(crate) fn validate(&self, nutrients: &[Nutrient]) -> Result<(), Error> {
+        if self.ingredients.is_empty() {
+            return Err(anyhow!("{} tarifinde hiç malzeme yok", self.description));
+        }
+
+        if !self.yield_factor.is_finite() || self.yield_factor <= 0.0 {
+            return Err(anyhow!(
+                "{} tarifinin pişirme verimi pozitif olmalı",
+                self.description
+            ));
+        }
+
+        if self
+            .density
+            .is_some_and(|density| !density.is_finite() || density <= 0.0)
+        {
+            return Err(anyhow!(
+                "{} tarifinin yoğunluğu pozitif olmalı",
+                self.description
+            ));
+        }
+
+        if let Some(ingredient) = self
+            .ingredients
+            .iter()
+            .find(|ingredient| !ingredient.weight.is_finite() || ingredient.weight <= 0.0)
+        {
+            return Err(anyhow!(
+                "{} tarifindeki {} malzemesinin ağırlığı pozitif olmalı",
+                self.description,
+                ingredient.slug
+            ));
+        }
+
+        // Malzemeler tarif ve malzeme id'leriyle saklandığı için aynı malzeme iki kez girilemez, miktarlar toplanmalı
+        if let Some((index, ingredient)) =
+            self.ingredients
+                .iter()
+                .enumerate()
+                .find(|(index, ingredient)| {
+                    self.ingredients[..*index]
+                        .iter()
+                        .any(|previous| previous.slug == ingredient.slug)
+                })
+        {
+            return Err(anyhow!(
+                "{} tarifinde {} malzemesi birden fazla kez girilmiş ({}. malzeme), ağırlıkları tek satırda toplanmalı",
+                self.description,
+                ingredient.slug,
+                index + 1
+            ));
+        }
+
+        for (nutrient, factor) in &self.retention_factors {
+            if !nutrients.iter().any(|defined| defined.name == *nutrient) {
+                return Err(anyhow!(
+                    "{} tarifinde bilinmeyen besin için korunma oranı girilmiş: {}",
+                    self.description,
+                    nutrient
+                ));
+            }
+
+            if !(0.0..=1.0).contains(factor) {
+                return Err(anyhow!(
+                    "{} tarifindeki {} korunma oranı 0 ile 1 arasında olmalı",
+                    self.description,
+                    nutrient
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Malzemeler tarifteki sırayla verilmeli, her biri (yemek, gram) şeklinde
+pub(crate) fn compute_recipe(recipe: &Recipe, ingredients: &[(Food, f64)]) -> Food {
+    let raw_weight: f64 = ingredients.iter().map(|(_, weight)| weight).sum();
+    let cooked_weight = raw_weight * recipe.yield_factor;
+
+    let mut food = Food {
+        description: recipe.description.clone(),
+        verified: recipe.verified,
+        image_url: recipe.image_url.clone(),
+        source: recipe.source.clone(),
+        tags: recipe.tags.clone(),
+        allergens: recipe.allergens.clone(),
+        servings: recipe.servings.clone(),
+        density: recipe.density,
+        ingredients: Some(
+            ingredients
+                .iter()
+                .map(|(food, weight)| Ingredient {
+                    slug: food.slug.clone().unwrap_or_default(),
+                    description: food.description.clone(),
+                    weight: *weight,
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    // Malzemelerin alerjenlerini tarife taşıyoruz, tekrar edenleri eklemiyoruz
+    for (ingredient, _) in ingredients {
+        for allergen in &ingredient.allergens {
+            if !food.allergens.contains(allergen) {
+                food.allergens.push(allergen.clone());
+            }
+        }
+    }
+
+    // Malzemeler veritabanından geldiği için hepsinde tüm besin tanımları var, değeri bilinmeyenler None
+    let names: BTreeSet<&String> = ingredients
+        .iter()
+        .flat_map(|(ingredient, _)| ingredient.nutrients.keys())
+        .collect();
+
+    for nutrient in names {
+        // Önce tüm tarifteki toplam miktarı buluyoruz, daha sonra pişmiş 100 grama oranlayacağız
+        // Bir malzemede bile besin bilinmiyorsa toplamı eksik göstermek yerine tarifte de bilinmiyor kabul ediyoruz
+        let Some(total) = ingredients
+            .iter()
+            .map(|(ingredient, weight)| {
+                ingredient
+                    .nutrient(nutrient)
+                    .map(|value| value * weight / 100.0)
+            })
+            .sum::<Option<f64>>()
+        else {
+            food.nutrients.insert(nutrient.to_owned(), None);
+            continue;
+        };
+
+        let total = if nutrient == "water" {
+            // Pişirmede kaybedilen veya kazanılan ağırlığın tamamını su olarak kabul ediyoruz
+            (total + cooked_weight - raw_weight).max(0.0)
+        } else {
+            total
+                * recipe
+                    .retention_factors
+                    .get(nutrient)
+                    .copied()
+                    .unwrap_or(1.0)
+        };
+
+        food.nutrients.insert(
+            nutrient.to_owned(),
+            Some(round_nutrient(total * 100.0 / cooked_weight)),
+        );
+        food.provenance
+            .insert(nutrient.to_owned(), Provenance::calculated());
+    }
+
+    // Karışık yemeklerin glisemik indeksi, malzemelerin karbonhidrat katkısına göre ağırlıklı ortalamasıdır
+    let carbohydrates: f64 = ingredients
+        .iter()
+        .map(|(ingredient, weight)| ingredient.nutrient("carbohydrate").unwrap_or(0.0) * weight)
+        .sum();
+    if carbohydrates > 0.0 {
+        food.glycemic_index = round_nutrient(
+            ingredients
+                .iter()
+                .map(|(ingredient, weight)| {
+                    ingredient.glycemic_index
+                        * ingredient.nutrient("carbohydrate").unwrap_or(0.0)
+                        * weight
+                })
+                .sum::<f64>()
+                / carbohydrates,
+        );
+    }
+
+    food
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::nutrient::Method;
+
+    fn create_ingredient(slug: &str, energy: f64, water: f64, vitamin_c: f64) -> Food {
+        Food {
+            slug: Some(slug.to_owned()),
+            description: slug.to_owned(),
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(energy)),
+                ("water".to_owned(), Some(water)),
+                ("vitamin_c".to_owned(), Some(vitamin_c)),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn create_recipe(yield_factor: f64) -> Recipe {
+        Recipe {
+            description: "Test Çorbası".to_owned(),
+            yield_factor,
+            retention_factors: BTreeMap::from([("vitamin_c".to_owned(), 0.5)]),
+            ingredients: vec![
+                RecipeIngredient {
+                    slug: "mercimek".to_owned(),
+                    weight: 100.0,
+                },
+                RecipeIngredient {
+                    slug: "su".to_owned(),
+                    weight: 300.0,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compute_recipe_yield_and_retention() {
+        let recipe = create_recipe(0.5);
+        let ingredients = vec![
+            (create_ingredient("mercimek", 360.0, 10.0, 2.0), 100.0),
+            (create_ingredient("su", 0.0, 100.0, 0.0), 300.0),
+        ];
+
+        let food = compute_recipe(&recipe, &ingredients);
+
+        // 400 gram çiğ malzeme 200 grama iniyor, enerji korunuyor ama 100 grama düşen miktar ikiye katlanıyor
+        assert_eq!(food.nutrient("energy"), Some(180.0));
+        // 310 gram sudan 200 gram buharlaşıyor, 110 gram su 200 gram yemekte kalıyor
+        assert_eq!(food.nutrient("water"), Some(55.0));
+        // C vitamininin yarısı pişirmede kayboluyor
+        assert_eq!(food.nutrient("vitamin_c"), Some(0.5));
+        assert_eq!(food.provenance["vitamin_c"].method, Method::Calculated);
+        // Malzemelerde hiç ölçülmemiş besinler tarifte de bilinmiyor olmalı, 0 değil
+        assert_eq!(food.nutrient("vitamin_d"), None);
+
+        let breakdown = food.ingredients.unwrap();
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].slug, "mercimek");
+    }
+
+    #[test]
+    fn test_compute_recipe_merges_allergens() {
+        let recipe = create_recipe(1.0);
+        let mut mercimek = create_ingredient("mercimek", 360.0, 10.0, 2.0);
+        mercimek.allergens = vec!["gluten".to_owned()];
+        let ingredients = vec![(mercimek.clone(), 100.0), (mercimek, 300.0)];
+
+        let food = compute_recipe(&recipe, &ingredients);
+        assert_eq!(food.allergens, vec!["gluten".to_owned()]);
+    }
+
+    #[test]
+    fn test_recipe_validate() {
+        let nutrients = vec![Nutrient {
+            name: "vitamin_c".to_owned(),
+            description: "C Vitamini".to_owned(),
+            unit: "mg".to_owned(),
+        }];
+
+        assert!(create_recipe(0.85).validate(&nutrients).is_ok());
+        assert!(create_recipe(0.0).validate(&nutrients).is_err());
+
+        let mut recipe = create_recipe(1.0);
+        recipe
+            .retention_factors
+            .insert("bilinmeyen".to_owned(), 0.5);
+        assert!(recipe.validate(&nutrients).is_err());
+
+        let recipe = Recipe {
+            density: Some(-1.0),
+            ..create_recipe(1.0)
+        };
+        assert!(recipe.validate(&nutrients).is_err());
+
+        // Aynı malzeme iki kez girilirse yükleme sırasında veritabanı hatası yerine açık bir hata dönmeli
+        let mut recipe = create_recipe(1.0);
+        recipe.ingredients.push(RecipeIngredient {
+            slug: "mercimek".to_owned(),
+            weight: 50.0,
+        });
+        let error = recipe.validate(&nutrients).unwrap_err().to_string();
+        assert!(error.contains("mercimek malzemesi birden fazla kez girilmiş (3. malzeme)"));
+    }
+}
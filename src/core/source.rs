@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+// food_sources tablosundaki bir kaynak, description alanı yemeklerin JSON'daki "source" değeri ile eşleşiyor
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub(crate) struct Source {
+    #[serde(skip_deserializing)]
+    pub(crate) id: Option<i64>,
+    pub(crate) description: String,
+    pub(crate) title: Option<String>,
+    // Yazar veya kurum
+    pub(crate) organization: Option<String>,
+    pub(crate) url: Option<String>,
+    pub(crate) year: Option<i64>,
+    pub(crate) license: Option<String>,
+    // Verinin kaynaktan alındığı tarih, YYYY-MM-DD
+    pub(crate) retrieved_at: Option<String>,
+    // Bu kaynağa dayanan yemeklerin slug'ları, sadece tek bir kaynak sorgulanırken dolduruluyor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) foods: Option<Vec<String>>,
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use crate::core::str::fold_key;
+
+// Türkçe karakterleri sadeleştirilmiş çekim ve yapım ekleri. Ünlü uyumundan dolayı "lı/li", "lu/lü" gibi ekler sadeleşince
+// tek bir eke iniyor. Her adımda kelimeye uyan en uzun ek atılıyor, "kabaklarından" -> "kabaklarin" -> "kabaklar" -> "kabak"
+const SUFFIXES: [&str; 35] = [
+    "lar", "ler", // Çoğul
+    "dan", "den", "tan", "ten", "da", "de", "ta", "te", // Bulunma, ayrılma
+    "nin", "nun", "in", "un", // İlgi
+    "yla", "yle", "la", "le", // Vasıta
+    "ya", "ye", "yi", "yu", "si", "su", "a", "e", "i", "u", // Yönelme, belirtme, iyelik
+    "li", "lu", "siz", "suz", "lik", "luk", "ci", // Yapım
+];
+
+// Kökün çok kısalıp başka kelimelerle karışmaması için sınırlar, tek harflik ekler için kök daha uzun olmalı
+const MIN_STEM_LENGTH: usize = 3;
+const MAX_STRIPPED_SUFFIXES: usize = 3;
+
+// Sadeleştirilmiş bir kelimenin eklerini atıyor. Kökü tam olarak bulmak yerine aynı kelimenin farklı çekimlerini aynı
+// biçime indirmeyi amaçlıyor, bu yüzden hem yemek isimlerine hem sorgulara aynı şekilde uygulanmalı
+pub(crate) fn stem(word: &str) -> String {
+    let mut stem = word;
+    for _ in 0..MAX_STRIPPED_SUFFIXES {
+        let length = stem.chars().count();
+        let suffix = SUFFIXES
+            .iter()
+            .filter(|suffix| stem.ends_with(*suffix))
+            .filter(|suffix| {
+                let min = MIN_STEM_LENGTH + usize::from(suffix.len() == 1);
+                length - suffix.chars().count() >= min
+            })
+            .max_by_key(|suffix| suffix.len());
+
+        match suffix {
+            Some(suffix) => stem = &stem[..stem.len() - suffix.len()],
+            None => break,
+        }
+    }
+
+    // Ünlüyle başlayan ekler sondaki sert ünsüzü yumuşatıyor, "kebap" -> "kebabı", "ekmek" -> "ekmeği". Ekli ve eksiz
+    // hallerin aynı köke inmesi için kelime sonundaki yumuşak ünsüzleri sertleştiriyoruz
+    let mut stem = stem.to_owned();
+    if stem.chars().count() >= MIN_STEM_LENGTH {
+        let hardened = match stem.chars().last() {
+            Some('b') => Some('p'),
+            Some('d') => Some('t'),
+            Some('g') => Some('k'),
+            _ => None,
+        };
+        if let Some(hardened) = hardened {
+            stem.pop();
+            stem.push(hardened);
+        }
+    }
+    stem
+}
+
+// Eş anlamlı ve yöresel isimler. Her gruptaki kelimeler grubun ilk kelimesine çevriliyor, "zerdali" -> "kayısı"
+#[derive(Default)]
+pub(crate) struct Synonyms {
+    canonical: HashMap<String, String>,
+    // "dere otu" gibi birden fazla kelimeden oluşan eş anlamlıların en uzununun kelime sayısı
+    max_words: usize,
+}
+
+impl Synonyms {
+    pub(crate) fn new(groups: Vec<Vec<String>>) -> Self {
+        let mut canonical = HashMap::new();
+        let mut max_words = 1;
+        for group in groups {
+            let Some(first) = group.first() else {
+                continue;
+            };
+            let target = stem_words(first).join(" ");
+            for word in &group[1..] {
+                let words = stem_words(word);
+                max_words = max_words.max(words.len());
+                canonical.insert(words.join(" "), target.to_owned());
+            }
+        }
+        Synonyms {
+            canonical,
+            max_words,
+        }
+    }
+
+    // Yemek isimleri ve sorgular bu şekilde aranabilir bir metne çevriliyor: "Kayısı Kurusu" -> "kayi kuru"
+    // Çok kelimeli eş anlamlılar için her konumda önce en uzun kelime dizisi deneniyor.
+    pub(crate) fn normalize(&self, text: &str) -> String {
+        let words = stem_words(text);
+        let mut normalized = Vec::with_capacity(words.len());
+        let mut i = 0;
+        while i < words.len() {
+            let longest = self.max_words.min(words.len() - i);
+            let matched = (1..=longest).rev().find_map(|count| {
+                self.canonical
+                    .get(&words[i..i + count].join(" "))
+                    .map(|target| (count, target.to_owned()))
+            });
+            match matched {
+                Some((count, target)) => {
+                    normalized.push(target);
+                    i += count;
+                }
+                None => {
+                    normalized.push(words[i].to_owned());
+                    i += 1;
+                }
+            }
+        }
+        normalized.join(" ")
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.canonical.len()
+    }
+}
+
+fn stem_words(text: &str) -> Vec<String> {
+    fold_key(text)
+        .split(' ')
+        .filter(|word| !word.is_empty())
+        .map(stem)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        assert_eq!(stem("elmalar"), stem("elma"));
+        assert_eq!(stem("domatesli"), "domates");
+        assert_eq!(stem("kabaklari"), "kabak");
+        assert_eq!(stem("kabaklarindan"), "kabak");
+        assert_eq!(stem("peyniri"), "peynir");
+        // Kısa kelimelere dokunulmuyor
+        assert_eq!(stem("su"), "su");
+        assert_eq!(stem("elma"), "elma");
+        assert_eq!(stem("muzlu"), "muz");
+        assert_eq!(stem("kebabi"), stem("kebap"));
+        assert_eq!(stem("ekmegi"), stem("ekmek"));
+    }
+
+    #[test]
+    fn test_synonyms_normalize() {
+        let synonyms = Synonyms::new(vec![
+            vec!["kayısı".to_owned(), "zerdali".to_owned()],
+            vec!["patlıcan".to_owned(), "badımcan".to_owned()],
+        ]);
+
+        assert_eq!(synonyms.len(), 2);
+        assert_eq!(synonyms.normalize("Zerdali"), synonyms.normalize("kayısı"));
+        assert_eq!(
+            synonyms.normalize("Badımcanlı Kebap"),
+            synonyms.normalize("patlıcan kebap")
+        );
+        assert_eq!(synonyms.normalize("Kabakları"), "kabak");
+        assert_eq!(synonyms.normalize("  !! "), "");
+    }
+
+    #[test]
+    fn test_synonyms_normalize_phrase() {
+        let synonyms = Synonyms::new(vec![vec!["dereotu".to_owned(), "dere otu".to_owned()]]);
+
+        assert_eq!(
+            synonyms.normalize("Dere Otu"),
+            synonyms.normalize("dereotu")
+        );
+        assert_eq!(
+            synonyms.normalize("taze dere otu çorbası"),
+            synonyms.normalize("taze dereotu çorbası")
+        );
+        // Tek başına "dere" kelimesi eşleşmiyor
+        assert_eq!(synonyms.normalize("dere"), "dere");
+    }
+}
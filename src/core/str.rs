@@ -2,6 +2,35 @@ pub(crate) fn to_lower_en_kebab_case(s: &str) -> String {
     convert_tr_chars_to_en(&to_kebab_case(&s.to_lowercase()))
 }
 
+// Karşılaştırmalarda büyük/küçük harf ve Türkçe karakter farklarını yok saymak için, ör: "Su Bardağı" -> "su bardagi"
+pub(crate) fn to_lower_en(s: &str) -> String {
+    convert_tr_chars_to_en(&s.to_lowercase())
+}
+
+// Türkçe kurallarına göre küçük harfe çeviriyor, to_lowercase "I" -> "i" ve "İ" -> "i̇" yapıyor
+pub(crate) fn to_lower_tr(s: &str) -> String {
+    s.chars()
+        .flat_map(|c| match c {
+            'I' => vec!['ı'],
+            'İ' => vec!['i'],
+            c => c.to_lowercase().collect(),
+        })
+        .collect()
+}
+
+// "Tavuk Göğsü (Haşlanmış)" -> "tavuk gogsu haslanmis", Türkçe karakterlerle ve ASCII yazılan sorgular aynı anahtara iniyor
+pub(crate) fn fold_key(s: &str) -> String {
+    convert_tr_chars_to_en(s)
+        .to_lowercase()
+        .replace(['â', 'Â'], "a")
+        .replace(['î', 'Î'], "i")
+        .replace(['û', 'Û'], "u")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
 pub(crate) fn to_kebab_case(s: &str) -> String {
     s.to_lowercase()
         .replace("(", "") // Bazı yemekler "Tavuk Göğsü (Çiğ)" gibi, slug'larda parantez olmasını istemiyoruz
@@ -66,6 +95,27 @@ mod tests {
         assert_eq!(to_lower_en_kebab_case("Çay   Kahve"), "cay-kahve");
     }
 
+    #[test]
+    fn test_to_lower_en() {
+        assert_eq!(to_lower_en("Su Bardağı"), "su bardagi");
+        assert_eq!(to_lower_en("ÇAY KAŞIĞI"), "cay kasigi");
+        assert_eq!(to_lower_en(""), "");
+    }
+
+    #[test]
+    fn test_to_lower_tr() {
+        assert_eq!(to_lower_tr("IRMIK İÇLİ"), "ırmık içli");
+        assert_eq!(to_lower_tr("Kaşar Peyniri"), "kaşar peyniri");
+        assert_eq!(to_lower_tr(""), "");
+    }
+
+    #[test]
+    fn test_fold_key() {
+        assert_eq!(fold_key("Tavuk Göğsü (Haşlanmış)"), "tavuk gogsu haslanmis");
+        assert_eq!(fold_key("İÇLİ KÖFTE"), "icli kofte");
+        assert_eq!(fold_key("Kâse"), "kase");
+    }
+
     #[test]
     fn test_to_kebab_case() {
         // Basit boşluk birleştirme testi
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::food::Food;
+
+// Aynı grupta sayılabilmek için iki yemeğin bu kategori etiketlerinden en az birini paylaşması gerekiyor
+// "tatlı", "diyet" gibi geniş etiketler üzerinden eşleştirme yaparsak muz yerine tavuk önermek gibi saçma sonuçlar çıkıyor
+const CATEGORY_TAGS: [&str; 9] = [
+    "meyve",
+    "sebze",
+    "et",
+    "tahıl",
+    "protein",
+    "baklagil",
+    "süt ürünü",
+    "kuruyemiş",
+    "içecek",
+];
+
+// Bir porsiyonun enerji farkı bu değerlerden büyüğünü geçiyorsa öneri yapmıyoruz, amaç aynı öğünü daha sağlıklı yapmak
+const MAX_ENERGY_DRIFT_KCAL: f64 = 20.0;
+const MAX_ENERGY_DRIFT_RATIO: f64 = 0.25;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Goal {
+    LessSugar,
+    MoreProtein,
+    LessSodium,
+}
+
+impl Goal {
+    pub(crate) fn nutrient(&self) -> &'static str {
+        match self {
+            Goal::LessSugar => "sugar",
+            Goal::MoreProtein => "protein",
+            Goal::LessSodium => "sodium",
+        }
+    }
+
+    fn value(&self, food: &Food) -> Option<f64> {
+        food.nutrient(self.nutrient())
+    }
+
+    // Hedefe göre iyileşme miktarı, pozitif değerler daha iyi demek
+    fn improvement(&self, original: f64, candidate: f64) -> f64 {
+        match self {
+            Goal::MoreProtein => candidate - original,
+            Goal::LessSugar | Goal::LessSodium => original - candidate,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub(crate) struct Substitute {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) serving: String,
+    pub(crate) serving_weight: f64,
+    // Farklar, orijinal yemeğin bir porsiyonu yerine bu yemeğin bir porsiyonu yendiğinde oluşan değişimi gösteriyor
+    pub(crate) energy_delta: f64,
+    pub(crate) nutrient_delta: f64,
+}
+
+pub(crate) fn find_substitutes(food: &Food, candidates: &[Food], goal: Goal) -> Vec<Substitute> {
+    // Değeri bilinmeyen besinler üzerinden karşılaştırma yapamayız, 0 kabul etmek yanlış öneriler çıkarır
+    let (Some(original_value), Some(original_energy)) = (goal.value(food), food.nutrient("energy"))
+    else {
+        return Vec::new();
+    };
+
+    // Yanıtta porsiyon başına farkları döndüğümüz için filtreleme ve sıralamayı da porsiyonlar üzerinden yapıyoruz,
+    // 100 gramda daha az şekerli bir yemeğin büyük porsiyonu toplamda daha fazla şeker içerebilir
+    let per_serving = |value_per_100g: f64, weight: f64| value_per_100g * weight / 100.0;
+    let (_, original_weight) = food.typical_serving();
+    let original_value = per_serving(original_value, original_weight);
+    let original_energy = per_serving(original_energy, original_weight);
+    let max_energy_drift = MAX_ENERGY_DRIFT_KCAL.max(original_energy * MAX_ENERGY_DRIFT_RATIO);
+
+    let mut scored: Vec<(f64, Substitute)> = candidates
+        .iter()
+        .filter(|candidate| candidate.slug != food.slug)
+        .filter(|candidate| shares_category(food, candidate))
+        // Orijinal yemekte olmayan bir alerjeni öneremeyiz, kullanıcı zaten o alerjenden kaçınıyor olabilir
+        .filter(|candidate| {
+            candidate
+                .allergens
+                .iter()
+                .all(|allergen| food.allergens.contains(allergen))
+        })
+        .filter_map(|candidate| {
+            let (serving, serving_weight) = candidate.typical_serving();
+            let value = per_serving(goal.value(candidate)?, serving_weight);
+            let energy = per_serving(candidate.nutrient("energy")?, serving_weight);
+            if (energy - original_energy).abs() > max_energy_drift {
+                return None;
+            }
+
+            let improvement = goal.improvement(original_value, value);
+            if improvement <= 0.0 {
+                return None;
+            }
+
+            Some((
+                improvement,
+                Substitute {
+                    slug: candidate.slug.clone().unwrap_or_default(),
+                    description: candidate.description.clone(),
+                    serving,
+                    serving_weight,
+                    energy_delta: energy - original_energy,
+                    nutrient_delta: value - original_value,
+                },
+            ))
+        })
+        .collect();
+
+    // En çok iyileştiren yemek en başta olacak şekilde sıralıyoruz
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    scored
+        .into_iter()
+        .map(|(_, substitute)| substitute)
+        .collect()
+}
+
+fn shares_category(food: &Food, candidate: &Food) -> bool {
+    food.tags
+        .iter()
+        .filter(|tag| CATEGORY_TAGS.contains(&tag.as_str()))
+        .any(|tag| candidate.tags.contains(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn create_food(slug: &str, tags: &[&str], energy: f64, sugar: f64, protein: f64) -> Food {
+        Food {
+            slug: Some(slug.to_owned()),
+            description: slug.to_owned(),
+            verified: Some(true),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            servings: BTreeMap::from([("Porsiyon (Orta)".to_owned(), 100.0)]),
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(energy)),
+                ("sugar".to_owned(), Some(sugar)),
+                ("protein".to_owned(), Some(protein)),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_substitutes_less_sugar() {
+        let muz = create_food("muz", &["meyve", "tatlı"], 89.0, 12.0, 1.1);
+        let candidates = vec![
+            muz.clone(),
+            create_food("elma", &["meyve"], 52.0, 10.0, 0.3),
+            create_food("portakal", &["meyve"], 80.0, 9.0, 0.9),
+            create_food("brokoli", &["sebze"], 34.0, 1.7, 2.8),
+        ];
+
+        let substitutes = find_substitutes(&muz, &candidates, Goal::LessSugar);
+
+        // Elma enerji olarak çok uzak, brokoli ise farklı grupta
+        assert_eq!(substitutes.len(), 1);
+        assert_eq!(substitutes[0].slug, "portakal");
+        assert_eq!(substitutes[0].nutrient_delta, -3.0);
+    }
+
+    #[test]
+    fn test_find_substitutes_compares_servings() {
+        let muz = create_food("muz", &["meyve"], 89.0, 12.0, 1.1);
+        let with_serving = |mut food: Food, weight: f64| {
+            food.servings = BTreeMap::from([("Porsiyon (Orta)".to_owned(), weight)]);
+            food
+        };
+        let candidates = vec![
+            // 100 gramda enerjisi yakın ama iki kat porsiyonu 71 kcal fazla
+            with_serving(create_food("kavun", &["meyve"], 80.0, 8.0, 0.8), 200.0),
+            // 100 gramda daha az şekerli ama porsiyonu 15 gram şeker içeriyor
+            with_serving(create_food("ananas", &["meyve"], 50.0, 10.0, 0.5), 150.0),
+            with_serving(create_food("portakal", &["meyve"], 80.0, 9.0, 0.9), 120.0),
+        ];
+
+        let substitutes = find_substitutes(&muz, &candidates, Goal::LessSugar);
+        assert_eq!(substitutes.len(), 1);
+        assert_eq!(substitutes[0].slug, "portakal");
+        assert!(substitutes[0].nutrient_delta < 0.0);
+        assert!(substitutes[0].energy_delta.abs() <= MAX_ENERGY_DRIFT_KCAL);
+    }
+
+    #[test]
+    fn test_find_substitutes_respects_allergens() {
+        let tavuk = create_food("tavuk", &["protein"], 120.0, 0.0, 22.0);
+        let mut yumurta = create_food("yumurta", &["protein"], 130.0, 1.0, 25.0);
+        yumurta.allergens = vec!["yumurta".to_owned()];
+
+        let substitutes = find_substitutes(&tavuk, &[yumurta], Goal::MoreProtein);
+        assert!(substitutes.is_empty());
+    }
+
+    #[test]
+    fn test_find_substitutes_orders_by_improvement() {
+        let tavuk = create_food("tavuk", &["et"], 120.0, 0.0, 20.0);
+        let candidates = vec![
+            create_food("hindi", &["et"], 115.0, 0.0, 24.0),
+            create_food("dana", &["et"], 125.0, 0.0, 28.0),
+        ];
+
+        let substitutes = find_substitutes(&tavuk, &candidates, Goal::MoreProtein);
+        assert_eq!(substitutes[0].slug, "dana");
+        assert_eq!(substitutes[1].slug, "hindi");
+    }
+}
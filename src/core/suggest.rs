@@ -0,0 +1,189 @@
+use serde::Serialize;
+
+use crate::core::{
+    autocomplete::Suggestion,
+    str::{fold_key, to_lower_tr},
+};
+
+const MAX_QUERY_SUGGESTIONS: usize = 3;
+const MAX_FOOD_SUGGESTIONS: usize = 5;
+
+// Boş dönen aramalarda önerilen düzeltilmiş sorgular ve isim olarak sorguya en yakın yemekler
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub(crate) struct DidYouMean {
+    pub(crate) queries: Vec<String>,
+    pub(crate) foods: Vec<Suggestion>,
+}
+
+// Kısa kelimelerde tek harflik bir hata bile başka bir kelimeye dönüştürebiliyor, kabul edilen mesafeyi uzunlukla artırıyoruz
+fn max_distance(len: usize) -> usize {
+    match len {
+        0..=4 => 1,
+        5..=8 => 2,
+        _ => 3,
+    }
+}
+
+// Levenshtein mesafesi, iki satırlık tablo ile
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+// Mesafeler Türkçe karakterler sadeleştirilerek hesaplanıyor, böylece "kasar" gibi ASCII yazılmış sorgular için de "kaşar"
+// önerilebiliyor. phrases arama moduna göre yemek isimleri veya etiketler oluyor
+pub(crate) fn did_you_mean(query: &str, phrases: &[&str], foods: &[Suggestion]) -> DidYouMean {
+    let key = fold_key(query);
+    if key.is_empty() {
+        return DidYouMean::default();
+    }
+    let query = to_lower_tr(query.trim());
+    let max = max_distance(key.chars().count());
+
+    let mut queries: Vec<(usize, String)> = Vec::new();
+    // Sorgunun tamamı bir isme veya etikete yakınsa onu öneriyoruz
+    for phrase in phrases {
+        let distance = edit_distance(&key, &fold_key(phrase));
+        if distance <= max {
+            queries.push((distance, to_lower_tr(phrase)));
+        }
+    }
+
+    // Ayrıca her kelimeyi sözlükteki en yakın kelimeyle değiştirip düzeltilmiş bir sorgu oluşturuyoruz
+    let mut words: Vec<String> = phrases
+        .iter()
+        .flat_map(|phrase| {
+            to_lower_tr(phrase)
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_owned)
+                .collect::<Vec<String>>()
+        })
+        .collect();
+    words.sort();
+    words.dedup();
+
+    let mut total = 0;
+    let mut corrected: Vec<String> = Vec::new();
+    for word in key.split(' ') {
+        let closest = words
+            .iter()
+            .map(|candidate| (edit_distance(word, &fold_key(candidate)), candidate))
+            .filter(|(distance, _)| *distance <= max_distance(word.chars().count()))
+            .min_by_key(|(distance, candidate)| (*distance, candidate.chars().count()));
+        match closest {
+            Some((distance, candidate)) => {
+                total += distance;
+                corrected.push(candidate.to_owned());
+            }
+            None => corrected.push(word.to_owned()),
+        }
+    }
+    queries.push((total, corrected.join(" ")));
+
+    queries.sort();
+    let mut suggested: Vec<String> = Vec::new();
+    for (_, suggestion) in queries {
+        // Aranan sorgunun kendisini önermenin anlamı yok
+        if suggestion != query && !suggested.contains(&suggestion) {
+            suggested.push(suggestion);
+        }
+    }
+    suggested.truncate(MAX_QUERY_SUGGESTIONS);
+
+    // Yemekler isminin tamamına olan mesafeye veya sorgudaki her kelimenin isimdeki en yakın kelimeye olan mesafelerinin
+    // toplamına göre sıralanıyor, böylece "tavk gogsu" için "Tavuk Göğsü (Çiğ)" de bulunabiliyor
+    let mut closest: Vec<(usize, usize, &Suggestion)> = foods
+        .iter()
+        .filter_map(|food| {
+            let description = fold_key(&food.description);
+            let by_words: usize = key
+                .split(' ')
+                .map(|word| {
+                    description
+                        .split(' ')
+                        .map(|candidate| edit_distance(word, candidate))
+                        .min()
+                        .unwrap_or(word.chars().count())
+                })
+                .sum();
+            let distance = by_words.min(edit_distance(&key, &description));
+            (distance <= max).then(|| (distance, description.chars().count(), food))
+        })
+        .collect();
+    closest.sort_by_key(|(distance, len, _)| (*distance, *len));
+
+    DidYouMean {
+        queries: suggested,
+        foods: closest
+            .into_iter()
+            .take(MAX_FOOD_SUGGESTIONS)
+            .map(|(_, _, food)| food.clone())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("elma", "elma"), 0);
+        assert_eq!(edit_distance("elmma", "elma"), 1);
+        assert_eq!(edit_distance("brokoli", "bruklii"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("çay", "cay"), 1);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let foods = vec![
+            Suggestion {
+                slug: "elma".to_owned(),
+                description: "Elma".to_owned(),
+            },
+            Suggestion {
+                slug: "fuji-elma".to_owned(),
+                description: "Fuji Elma".to_owned(),
+            },
+            Suggestion {
+                slug: "kasar-peyniri".to_owned(),
+                description: "Kaşar Peyniri".to_owned(),
+            },
+        ];
+        let phrases: Vec<&str> = foods.iter().map(|food| food.description.as_str()).collect();
+
+        let suggestions = did_you_mean("elmaa", &phrases, &foods);
+        assert_eq!(suggestions.queries, vec!["elma"]);
+        let slugs: Vec<&str> = suggestions
+            .foods
+            .iter()
+            .map(|food| food.slug.as_str())
+            .collect();
+        assert_eq!(slugs, vec!["elma", "fuji-elma"]);
+
+        // ASCII yazılmış sorgu için Türkçe karakterli hali öneriliyor
+        let suggestions = did_you_mean("kasar peynri", &phrases, &foods);
+        assert_eq!(suggestions.queries, vec!["kaşar peyniri"]);
+        assert_eq!(suggestions.foods[0].slug, "kasar-peyniri");
+
+        let suggestions = did_you_mean("peynir kasr", &phrases, &foods);
+        assert_eq!(suggestions.foods[0].slug, "kasar-peyniri");
+
+        assert_eq!(did_you_mean("xyz", &phrases, &foods), DidYouMean::default());
+        assert_eq!(did_you_mean("  ", &phrases, &foods), DidYouMean::default());
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use csv::{Terminator, WriterBuilder};
+use serde::Serialize;
+use serde_json::Number;
+
+use crate::core::value::Value;
+
+// Etiketler ve alerjenler gibi listeler tek hücrede bu ayraçla birleştiriliyor, virgül ve noktalı virgül ayraç olarak
+// kullanılabildiği için onları seçmedik
+pub(crate) const LIST_SEPARATOR: &str = "|";
+
+// Excel'in UTF-8 dosyaları doğru açması için gerekiyor, yoksa Türkçe karakterler bozuk görünüyor
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CsvOptions {
+    pub(crate) separator: u8,
+    // Türkçe bölge ayarlı Excel ondalık ayracı olarak virgül bekliyor, 1.5 değerini tarih veya metin olarak okuyor
+    pub(crate) decimal_comma: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            separator: b',',
+            decimal_comma: false,
+        }
+    }
+}
+
+// Serde ile serileştirilebilen kayıtları düz bir tabloya çevirip CSV olarak yazıyoruz. İç içe nesneler (porsiyonlar)
+// "servings.Adet (Orta)" gibi ayrı sütunlara açılıyor, sadece bazı kayıtlarda bulunan sütunlar boş bırakılıyor
+pub(crate) fn to_csv<T: Serialize>(records: &[T], options: CsvOptions) -> Result<Vec<u8>> {
+    let mut columns: Vec<(String, String)> = Vec::new();
+    let mut known: HashSet<String> = HashSet::new();
+    let mut rows: Vec<HashMap<String, String>> = Vec::with_capacity(records.len());
+
+    for record in records {
+        let mut cells = Vec::new();
+        flatten("", &Value::from_serialize(record)?, options, &mut cells);
+
+        let mut row = HashMap::with_capacity(cells.len());
+        for (group, column, cell) in cells {
+            if known.insert(column.to_owned()) {
+                // Yeni bir porsiyon sütunu tablonun sonuna değil diğer porsiyonların yanına ekleniyor
+                let position = columns
+                    .iter()
+                    .rposition(|(existing, _)| !group.is_empty() && *existing == group)
+                    .map_or(columns.len(), |position| position + 1);
+                columns.insert(position, (group, column.to_owned()));
+            }
+            row.insert(column, cell);
+        }
+        rows.push(row);
+    }
+
+    let mut csv = Vec::new();
+    if options.decimal_comma {
+        csv.extend_from_slice(UTF8_BOM);
+    }
+
+    // Ayraç, tırnak veya satır sonu içeren hücreler RFC 4180'e göre tırnak içine alınıyor
+    let mut writer = WriterBuilder::new()
+        .delimiter(options.separator)
+        .terminator(Terminator::CRLF)
+        .from_writer(csv);
+    writer.write_record(columns.iter().map(|(_, column)| escape_formula(column)))?;
+    for row in &rows {
+        writer.write_record(
+            columns
+                .iter()
+                .map(|(_, column)| row.get(column).map_or("", String::as_str)),
+        )?;
+    }
+
+    Ok(writer.into_inner().map_err(|e| e.into_error())?)
+}
+
+// (grup, sütun, hücre) üçlüleri üretiyor, grup iç içe nesnenin sütun ön eki, üst seviyedeki alanlar için boş
+fn flatten(
+    prefix: &str,
+    value: &Value,
+    options: CsvOptions,
+    cells: &mut Vec<(String, String, String)>,
+) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let column = match prefix {
+                    "" => key.to_owned(),
+                    prefix => format!("{}.{}", prefix, key),
+                };
+                match value {
+                    Value::Object(_) => flatten(&column, value, options, cells),
+                    value => cells.push((prefix.to_owned(), column, cell(value, options))),
+                }
+            }
+        }
+        // Liste yerine tek değer dönen kayıtlar tek sütunluk bir tablo oluyor
+        value => cells.push((String::new(), "value".to_owned(), cell(value, options))),
+    }
+}
+
+fn cell(value: &Value, options: CsvOptions) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(number) => format_number(number, options),
+        Value::String(text) => escape_formula(text),
+        Value::Array(items) => escape_formula(
+            &items
+                .iter()
+                .map(|item| match item {
+                    Value::Array(_) | Value::Object(_) => to_json(item),
+                    item => cell(item, options),
+                })
+                .collect::<Vec<String>>()
+                .join(LIST_SEPARATOR),
+        ),
+        Value::Object(_) => to_json(value),
+    }
+}
+
+// Listelerdeki nesneler (tarif malzemeleri) tek hücreye JSON olarak yazılıyor
+fn to_json(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_owned(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => serde_json::Value::from(text.as_str()).to_string(),
+        Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(to_json).collect::<Vec<String>>().join(",")
+        ),
+        Value::Object(entries) => format!(
+            "{{{}}}",
+            entries
+                .iter()
+                .map(|(key, value)| format!(
+                    "{}:{}",
+                    serde_json::Value::from(key.as_str()),
+                    to_json(value)
+                ))
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+    }
+}
+
+fn format_number(number: &Number, options: CsvOptions) -> String {
+    let number = number.to_string();
+    if options.decimal_comma {
+        number.replace('.', ",")
+    } else {
+        number
+    }
+}
+
+// Excel =, +, - veya @ ile başlayan metinleri (başlarındaki boşluklar atlanarak) formül olarak çalıştırıyor. /parse
+// gibi kullanıcının gönderdiği satırları geri döndüren endpoint'ler için metin hücrelerinin ve başlıkların başına '
+// ekliyoruz, sayılar bizim ürettiğimiz değerler olduğu için eksi işaretleriyle olduğu gibi kalıyor
+fn escape_formula(text: &str) -> String {
+    if text
+        .trim_start_matches([' ', '\u{a0}'])
+        .starts_with(['=', '+', '-', '@', '\t', '\r', '\n', '＝', '＋', '－', '＠'])
+    {
+        format!("'{}", text)
+    } else {
+        text.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::food::Food;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_to_csv() -> Result<()> {
+        let food = |slug: &str, servings: &[(&str, f64)]| Food {
+            slug: Some(slug.to_owned()),
+            description: slug.to_owned(),
+            tags: vec!["meyve".to_owned(), "tatlı".to_owned()],
+            servings: servings
+                .iter()
+                .map(|(serving, weight)| (serving.to_string(), *weight))
+                .collect(),
+            glycemic_index: 38.5,
+            nutrients: BTreeMap::from([
+                ("energy".to_owned(), Some(52.0)),
+                ("fat".to_owned(), None),
+            ]),
+            ..Default::default()
+        };
+        let foods = vec![
+            food("elma", &[("Adet (Orta)", 180.0)]),
+            food("muz", &[("Adet (Büyük)", 136.5)]),
+        ];
+
+        let csv = String::from_utf8(to_csv(&foods, CsvOptions::default())?)?;
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        // Porsiyon sütunları yan yana, diğer kayıtta bulunmayan porsiyon boş kalıyor
+        assert!(lines[0].contains("servings.Adet (Orta),servings.Adet (Büyük),"));
+        assert!(lines[0].ends_with(",glycemic_index,energy,fat"));
+        assert!(lines[1].contains(",meyve|tatlı,,180.0,,"));
+        assert!(lines[1].ends_with(",38.5,52.0,"));
+        assert!(lines[2].contains(",,136.5,"));
+
+        // Türkçe Excel için noktalı virgül ayraç ve ondalık virgül
+        let options = CsvOptions {
+            separator: b';',
+            decimal_comma: true,
+        };
+        let csv = to_csv(&foods, options)?;
+        assert!(csv.starts_with(UTF8_BOM));
+        let csv = String::from_utf8(csv[UTF8_BOM.len()..].to_vec())?;
+        assert!(csv.lines().nth(2).unwrap().contains(";136,5;"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_escaping() -> Result<()> {
+        let records = vec![
+            BTreeMap::from([("line", "2 \"su\" bardağı, pirinç")]),
+            BTreeMap::from([("line", "=HYPERLINK(\"x\")")]),
+        ];
+        let csv = String::from_utf8(to_csv(&records, CsvOptions::default())?)?;
+        assert_eq!(
+            csv,
+            "line\r\n\"2 \"\"su\"\" bardağı, pirinç\"\r\n\"'=HYPERLINK(\"\"x\"\")\"\r\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_formula_injection() -> Result<()> {
+        #[derive(Serialize)]
+        struct Parsed {
+            line: &'static str,
+            quantity: f64,
+            unresolved: Vec<&'static str>,
+        }
+
+        let records = vec![
+            Parsed {
+                line: "+1 su bardağı",
+                quantity: -1.5,
+                unresolved: vec!["@SUM(A1)", "x"],
+            },
+            Parsed {
+                line: "  -2+3",
+                quantity: 2.0,
+                unresolved: vec!["tuz", "=1"],
+            },
+        ];
+        let csv = String::from_utf8(to_csv(&records, CsvOptions::default())?)?;
+        let lines: Vec<&str> = csv.lines().collect();
+        // Metinler ve liste elemanları etkisizleştiriliyor, sayılar olduğu gibi kalıyor
+        assert_eq!(lines[1], "'+1 su bardağı,-1.5,'@SUM(A1)|x");
+        assert_eq!(lines[2], "'  -2+3,2.0,tuz|'=1");
+
+        let headers = vec![BTreeMap::from([("=cmd", "@a")])];
+        let csv = String::from_utf8(to_csv(&headers, CsvOptions::default())?)?;
+        assert_eq!(csv, "'=cmd\r\n'@a\r\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+// tags tablosundaki bir etiket, description alanı yemeklerin JSON'daki küçük harfli etiketleri ile eşleşiyor
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub(crate) struct Tag {
+    pub(crate) description: String,
+    // Üst etiketin description değeri, ör: "narenciye" için "meyve"
+    pub(crate) parent: Option<String>,
+    // Gösterim için Türkçe ve İngilizce isimler
+    pub(crate) name: Option<String>,
+    pub(crate) name_en: Option<String>,
+    pub(crate) summary: Option<String>,
+    // Bu etikete veya alt etiketlerinden birine sahip onaylı yemek sayısı
+    #[serde(skip_deserializing)]
+    pub(crate) food_count: i64,
+    // Alt etiketler ve etiketin altındaki yemeklerin slug'ları, sadece tek bir etiket sorgulanırken dolduruluyor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) children: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) foods: Option<Vec<String>>,
+}
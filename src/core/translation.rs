@@ -0,0 +1,177 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use crate::core::{
+    food::{Food, FoodSummary},
+    language::Language,
+    substitute::Substitute,
+};
+
+// Çevirisi tutulan alanlar. Yemekler dil değişse de sabit kalan slug'ları, diğerleri Türkçe açıklamaları ile eşleşiyor
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TranslationKind {
+    Food,
+    Tag,
+    Allergen,
+    Serving,
+}
+
+// db/translations dizinindeki JSON dosyalarındaki bir kayıt: { "kind": "food", "key": "elma", "translations": { "en": "Apple" } }
+#[derive(Deserialize, Debug)]
+pub(crate) struct Translation {
+    pub(crate) kind: TranslationKind,
+    pub(crate) key: String,
+    pub(crate) translations: BTreeMap<String, String>,
+}
+
+// Çeviri tablolarının başlangıçta belleğe alınmış hali, her yanıtta veritabanına gitmemek için
+#[derive(Default)]
+pub(crate) struct Translations {
+    texts: HashMap<(TranslationKind, Language, String), String>,
+}
+
+impl Translations {
+    pub(crate) fn new(rows: Vec<(TranslationKind, Language, String, String)>) -> Self {
+        Translations {
+            texts: rows
+                .into_iter()
+                .map(|(kind, language, key, text)| ((kind, language, key), text))
+                .collect(),
+        }
+    }
+
+    // Türkçe istendiğinde veya çevirisi bulunmadığında None dönüyor, çağıran taraf Türkçe metni kullanıyor
+    pub(crate) fn get(&self, kind: TranslationKind, language: Language, key: &str) -> Option<&str> {
+        if language == Language::Tr {
+            return None;
+        }
+        self.texts
+            .get(&(kind, language, key.to_owned()))
+            .map(String::as_str)
+    }
+
+    fn translate(&self, kind: TranslationKind, language: Language, key: &str, text: &mut String) {
+        if let Some(translated) = self.get(kind, language, key) {
+            *text = translated.to_owned();
+        }
+    }
+
+    pub(crate) fn translate_food(&self, food: &mut Food, language: Language) {
+        if language == Language::Tr {
+            return;
+        }
+
+        if let Some(slug) = &food.slug {
+            self.translate(TranslationKind::Food, language, slug, &mut food.description);
+        }
+        for tag in food.tags.iter_mut() {
+            let key = tag.to_owned();
+            self.translate(TranslationKind::Tag, language, &key, tag);
+        }
+        for allergen in food.allergens.iter_mut() {
+            let key = allergen.to_owned();
+            self.translate(TranslationKind::Allergen, language, &key, allergen);
+        }
+        food.servings = std::mem::take(&mut food.servings)
+            .into_iter()
+            .map(|(serving, weight)| (self.serving(language, serving), weight))
+            .collect();
+        for ingredient in food.ingredients.iter_mut().flatten() {
+            self.translate(
+                TranslationKind::Food,
+                language,
+                &ingredient.slug,
+                &mut ingredient.description,
+            );
+        }
+    }
+
+    pub(crate) fn translate_summary(&self, summary: &mut FoodSummary, language: Language) {
+        let slug = summary.slug.to_owned();
+        self.translate(
+            TranslationKind::Food,
+            language,
+            &slug,
+            &mut summary.description,
+        );
+    }
+
+    pub(crate) fn translate_substitute(&self, substitute: &mut Substitute, language: Language) {
+        let slug = substitute.slug.to_owned();
+        self.translate(
+            TranslationKind::Food,
+            language,
+            &slug,
+            &mut substitute.description,
+        );
+        substitute.serving = self.serving(language, std::mem::take(&mut substitute.serving));
+    }
+
+    pub(crate) fn serving(&self, language: Language, serving: String) -> String {
+        self.get(TranslationKind::Serving, language, &serving)
+            .map_or(serving, str::to_owned)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.texts.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::recipe::Ingredient;
+
+    #[test]
+    fn test_translate_food() {
+        let translations = Translations::new(vec![
+            (
+                TranslationKind::Food,
+                Language::En,
+                "elma".to_owned(),
+                "Apple".to_owned(),
+            ),
+            (
+                TranslationKind::Tag,
+                Language::En,
+                "meyve".to_owned(),
+                "fruit".to_owned(),
+            ),
+            (
+                TranslationKind::Serving,
+                Language::En,
+                "Adet (Orta)".to_owned(),
+                "Piece (Medium)".to_owned(),
+            ),
+        ]);
+        assert_eq!(translations.len(), 3);
+
+        let food = Food {
+            slug: Some("elma".to_owned()),
+            description: "Elma".to_owned(),
+            tags: vec!["meyve".to_owned(), "tatlı".to_owned()],
+            servings: BTreeMap::from([("Adet (Orta)".to_owned(), 180.0)]),
+            ingredients: Some(vec![Ingredient {
+                slug: "elma".to_owned(),
+                description: "Elma".to_owned(),
+                weight: 100.0,
+            }]),
+            ..Default::default()
+        };
+
+        let mut english = food.clone();
+        translations.translate_food(&mut english, Language::En);
+        assert_eq!(english.description, "Apple");
+        assert_eq!(english.slug.as_deref(), Some("elma"));
+        // Çevirisi olmayan etiket Türkçe kalıyor
+        assert_eq!(english.tags, vec!["fruit", "tatlı"]);
+        assert_eq!(english.servings.get("Piece (Medium)"), Some(&180.0));
+        assert_eq!(english.ingredients.unwrap()[0].description, "Apple");
+
+        let mut turkish = food.clone();
+        translations.translate_food(&mut turkish, Language::Tr);
+        assert_eq!(turkish, food);
+    }
+}
@@ -0,0 +1,237 @@
+use serde::Serialize;
+
+use crate::core::{food::Food, str::to_lower_en};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UnitKind {
+    // Bir birimin kaç gram olduğu
+    Mass(f64),
+    // Bir birimin kaç mililitre olduğu, mutfak ölçüleri de bu gruptaki değerler ile hesaplanıyor
+    Volume(f64),
+    // Sadece yemeğin kendi porsiyonlarında karşılığı olan birimler, "Adet (Orta)" gibi porsiyonların baş kısmı
+    Serving,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Unit {
+    // Porsiyon açıklamalarında kullanılan yazım, ör: "Su Bardağı (Tam)" için "Su Bardağı"
+    pub(crate) name: &'static str,
+    pub(crate) aliases: &'static [&'static str],
+    pub(crate) kind: UnitKind,
+}
+
+// Türk mutfağında tariflerde kullanılan standart ölçüler, su bardağı 200 ml ve çay bardağı 100 ml kabul ediliyor
+pub(crate) const UNITS: [Unit; 16] = [
+    Unit {
+        name: "Kilogram",
+        aliases: &["kg", "kilo", "kilogram"],
+        kind: UnitKind::Mass(1000.0),
+    },
+    Unit {
+        name: "Gram",
+        aliases: &["g", "gr", "gram"],
+        kind: UnitKind::Mass(1.0),
+    },
+    Unit {
+        name: "Litre",
+        aliases: &["l", "lt", "litre"],
+        kind: UnitKind::Volume(1000.0),
+    },
+    Unit {
+        name: "Mililitre",
+        aliases: &["ml", "mililitre"],
+        kind: UnitKind::Volume(1.0),
+    },
+    Unit {
+        name: "Su Bardağı",
+        aliases: &["su bardagi", "su bardak"],
+        kind: UnitKind::Volume(200.0),
+    },
+    Unit {
+        name: "Çay Bardağı",
+        aliases: &["cay bardagi", "cay bardak"],
+        kind: UnitKind::Volume(100.0),
+    },
+    Unit {
+        name: "Kahve Fincanı",
+        aliases: &["kahve fincani", "fincan"],
+        kind: UnitKind::Volume(70.0),
+    },
+    Unit {
+        name: "Yemek Kaşığı",
+        aliases: &["yemek kasigi", "yk"],
+        kind: UnitKind::Volume(15.0),
+    },
+    Unit {
+        name: "Tatlı Kaşığı",
+        aliases: &["tatli kasigi", "tk"],
+        kind: UnitKind::Volume(10.0),
+    },
+    Unit {
+        name: "Çay Kaşığı",
+        aliases: &["cay kasigi", "ck"],
+        kind: UnitKind::Volume(5.0),
+    },
+    Unit {
+        name: "Adet",
+        aliases: &["adet", "tane"],
+        kind: UnitKind::Serving,
+    },
+    Unit {
+        name: "Dilim",
+        aliases: &["dilim"],
+        kind: UnitKind::Serving,
+    },
+    Unit {
+        name: "Porsiyon",
+        aliases: &["porsiyon"],
+        kind: UnitKind::Serving,
+    },
+    Unit {
+        name: "Kase",
+        aliases: &["kase"],
+        kind: UnitKind::Serving,
+    },
+    Unit {
+        name: "Kepçe",
+        aliases: &["kepce"],
+        kind: UnitKind::Serving,
+    },
+    Unit {
+        name: "Fileto",
+        aliases: &["fileto"],
+        kind: UnitKind::Serving,
+    },
+];
+
+// to_lower_en ile sadeleştirilmiş bir ifadeye karşılık gelen birimi buluyoruz
+pub(crate) fn find_unit(folded: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.aliases.contains(&folded))
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct Amount {
+    pub(crate) serving: Option<String>,
+    pub(crate) grams: f64,
+    pub(crate) confidence: f64,
+}
+
+// Bir miktarı grama çeviriyoruz. Önce yemeğin kendi porsiyonlarına bakıyoruz çünkü bir su bardağı yulaf ile
+// bir su bardağı süt aynı gramaj değil, porsiyonlarda yoksa hacmi yemeğin yoğunluğu (g/ml) ile grama çeviriyoruz
+pub(crate) fn convert_to_grams(
+    food: &Food,
+    quantity: f64,
+    unit: &Unit,
+    size: Option<&str>,
+) -> Option<Amount> {
+    if let UnitKind::Mass(grams) = unit.kind {
+        return Some(Amount {
+            serving: None,
+            grams: quantity * grams,
+            confidence: 1.0,
+        });
+    }
+
+    if let Some((serving, weight, confidence)) = find_serving(food, unit, size) {
+        return Some(Amount {
+            serving: Some(serving),
+            grams: quantity * weight,
+            confidence,
+        });
+    }
+
+    match (unit.kind, food.density) {
+        (UnitKind::Volume(ml), Some(density)) => Some(Amount {
+            serving: None,
+            grams: quantity * ml * density,
+            confidence: 0.9,
+        }),
+        _ => None,
+    }
+}
+
+fn find_serving(food: &Food, unit: &Unit, size: Option<&str>) -> Option<(String, f64, f64)> {
+    let unit_name = to_lower_en(unit.name);
+    let matching: Vec<(&String, f64, String)> = food
+        .servings
+        .iter()
+        .filter_map(|(serving, weight)| {
+            let folded = to_lower_en(serving);
+            let (base, detail) = match folded.split_once(" (") {
+                Some((base, detail)) => (base.to_owned(), detail.trim_end_matches(')').to_owned()),
+                None => (folded, String::new()),
+            };
+            (base == unit_name).then_some((serving, *weight, detail))
+        })
+        .collect();
+
+    if let Some((serving, weight, _)) = size.and_then(|size| {
+        matching
+            .iter()
+            .find(|(_, _, detail)| detail.starts_with(size))
+    }) {
+        return Some((serving.to_string(), *weight, 1.0));
+    }
+
+    if matching.len() == 1 {
+        let (serving, weight, _) = &matching[0];
+        return Some((serving.to_string(), *weight, 0.9));
+    }
+
+    // Birden fazla boyut varsa ve kullanıcı belirtmediyse ortayı seçiyoruz
+    matching
+        .iter()
+        .find(|(_, _, detail)| detail.starts_with("orta"))
+        .or(matching.first())
+        .map(|(serving, weight, _)| (serving.to_string(), *weight, 0.8))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_find_unit() {
+        assert_eq!(find_unit("su bardagi").unwrap().name, "Su Bardağı");
+        assert_eq!(find_unit("gr").unwrap().kind, UnitKind::Mass(1.0));
+        assert_eq!(
+            find_unit(&to_lower_en("Yemek Kaşığı")).unwrap().name,
+            "Yemek Kaşığı"
+        );
+        assert!(find_unit("tutam").is_none());
+    }
+
+    #[test]
+    fn test_convert_to_grams() {
+        let yulaf = Food {
+            slug: Some("yulaf-ezmesi".to_owned()),
+            description: "Yulaf Ezmesi".to_owned(),
+            servings: BTreeMap::from([("Yemek Kaşığı".to_owned(), 8.0)]),
+            density: Some(0.45),
+            ..Default::default()
+        };
+
+        let amount = convert_to_grams(&yulaf, 2.0, find_unit("kg").unwrap(), None).unwrap();
+        assert_eq!(amount.grams, 2000.0);
+
+        // Yemeğin kendi porsiyonu mutfak ölçüsünden önce gelmeli
+        let amount = convert_to_grams(&yulaf, 2.0, find_unit("yk").unwrap(), None).unwrap();
+        assert_eq!(amount.serving, Some("Yemek Kaşığı".to_owned()));
+        assert_eq!(amount.grams, 16.0);
+
+        // Porsiyonlarda su bardağı yok, yoğunluk ile hesaplanmalı
+        let amount = convert_to_grams(&yulaf, 1.0, find_unit("su bardagi").unwrap(), None).unwrap();
+        assert!(amount.serving.is_none());
+        assert_eq!(amount.grams, 90.0);
+
+        // Yoğunluğu bilinmeyen yemeklerde hacim grama çevrilemez
+        let yulaf = Food {
+            density: None,
+            ..yulaf
+        };
+        assert!(convert_to_grams(&yulaf, 1.0, find_unit("su bardagi").unwrap(), None).is_none());
+        assert!(convert_to_grams(&yulaf, 1.0, find_unit("dilim").unwrap(), None).is_none());
+    }
+}
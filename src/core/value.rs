@@ -0,0 +1,129 @@
+use std::fmt;
+
+use anyhow::Result;
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{MapAccess, SeqAccess, Visitor},
+};
+use serde_json::Number;
+
+// serde_json::Value nesne alanlarını alfabetik sıralıyor (preserve_order özelliği kapalı), CSV sütunlarının
+// struct'taki alanların sırasında kalması için alanları sırasıyla tutan kendi değer tipimizi kullanıyoruz
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    // JSON çıktısıyla birebir aynı olması için serde_json üzerinden geçiyoruz, flatten edilen besin değerleri de
+    // bu sayede JSON'daki gibi düz alanlar oluyor
+    pub(crate) fn from_serialize<T: Serialize>(value: &T) -> Result<Self> {
+        Ok(serde_json::from_slice(&serde_json::to_vec(value)?)?)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("bir JSON değeri")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(value).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_owned()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Value::Object(entries))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Record {
+        slug: &'static str,
+        energy: Option<f64>,
+        tags: Vec<&'static str>,
+    }
+
+    #[test]
+    fn test_value_keeps_field_order() -> Result<()> {
+        let value = Value::from_serialize(&Record {
+            slug: "elma",
+            energy: None,
+            tags: vec!["meyve"],
+        })?;
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                ("slug".to_owned(), Value::String("elma".to_owned())),
+                ("energy".to_owned(), Value::Null),
+                (
+                    "tags".to_owned(),
+                    Value::Array(vec![Value::String("meyve".to_owned())])
+                ),
+            ])
+        );
+        Ok(())
+    }
+}
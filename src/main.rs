@@ -1,11 +1,15 @@
-use std::{net::SocketAddr, str::FromStr, sync::Arc};
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 
 use anyhow::Error;
 use axum::{
     Router, ServiceExt,
     extract::Request,
     middleware::{self},
-    routing::get,
+    routing::{get, post},
 };
 use axum_client_ip::ClientIpSource;
 use axum_governor::GovernorLayer;
@@ -15,12 +19,21 @@ use moka::future::Cache;
 use real::RealIpLayer;
 use reqwest::Method;
 use sqlx::{Pool, Sqlite};
-use tokio::{net::TcpListener, sync::Mutex};
+use tokio::{
+    net::TcpListener,
+    sync::{Mutex, Semaphore},
+};
 use tower::Layer;
 use tower_http::{cors::CorsLayer, normalize_path::NormalizePathLayer};
 use tracing::{debug, info};
 
-use crate::core::config::Config;
+use crate::{
+    api::cache::CachedResponse,
+    core::{
+        autocomplete::PrefixIndex, config::Config, popularity::Popularity, stem::Synonyms,
+        translation::Translations,
+    },
+};
 
 mod api;
 mod core;
@@ -29,14 +42,41 @@ mod core;
 #[derive(Clone)]
 struct SharedState {
     api_db: Arc<Mutex<Pool<Sqlite>>>,
+    // Arama ve görüntülenme kayıtları, yemek veritabanı her başlangıçta yeniden oluşturulabildiği için ayrı tutuluyor
+    analytics_db: Arc<Mutex<Pool<Sqlite>>>,
+    // Arama sonuçlarında eşit puanlı yemekleri sıralamak için görüntülenme ve tıklanmalardan hesaplanan puanlar
+    popularity: Arc<Mutex<Popularity>>,
     config: Arc<Mutex<Config>>,
-    cache: Cache<String, String>, // URL -> JSON şeklinde caching yapacağız
+    cache: Cache<String, CachedResponse>, // Dil, biçim ve URL -> kodlanmış yanıt şeklinde caching yapacağız
+    // Otomatik tamamlama için bellekteki yemek ismi indeksi, sadece okunduğu için senkron RwLock yeterli
+    autocomplete: Arc<RwLock<PrefixIndex>>,
+    // Son tıklamalar (istemci IP'si, slug), aynı tıklamanın tekrar sayılmasını engellemek için kısa süre tutuluyor
+    clicks: Cache<(IpAddr, String), ()>,
+    synonyms: Arc<Synonyms>,
+    // Yemek, etiket, alerjen ve porsiyon isimlerinin çevirileri, veritabanı her açılışta güncellendiği için bir defa yükleniyor
+    translations: Arc<Translations>,
+    // Aynı anda yapılabilecek dışa aktarımlar, her biri bitene kadar bir veritabanı bağlantısını tutuyor
+    exports: Arc<Semaphore>,
 }
 
 impl SharedState {
     async fn new() -> Result<Self, Error> {
-        let api_db = Arc::new(Mutex::new(api::database::connect_database().await?));
+        // Eş anlamlılar hem yemeklerin aranabilir metinleri hem de arama sorguları için kullanılıyor
+        let synonyms = Arc::new(api::database::load_synonyms());
+        debug!("Eş anlamlılar sözlüğü {} kelime ile yüklendi.", synonyms.len());
+        let api_db = api::database::connect_database(&synonyms).await?;
+        let translations = Arc::new(api::database::select_translations(&api_db).await?);
+        debug!("{} çeviri yüklendi.", translations.len());
+        let api_db = Arc::new(Mutex::new(api_db));
         let config = Arc::new(Mutex::new(core::config::load_config_with_defaults()?));
+        let (retention_days, half_life_days) = {
+            let config = config.lock().await;
+            (config.analytics.retention_days, config.popularity.half_life_days)
+        };
+        let analytics_db = api::analytics::connect_analytics_database(retention_days).await?;
+        let popularity = api::analytics::load_popularity(&analytics_db, half_life_days).await?;
+        debug!("Popülerlik puanları {} yemek için yüklendi.", popularity.len());
+        let analytics_db = Arc::new(Mutex::new(analytics_db));
 
         let cache_capacity = config.lock().await.core.cache_capacity;
         let cache = Cache::builder()
@@ -44,11 +84,38 @@ impl SharedState {
             .time_to_live(std::time::Duration::from_secs(10 * 60))
             .build();
 
-        Ok(Self {
+        let state = Self {
             api_db,
+            analytics_db,
+            popularity: Arc::new(Mutex::new(popularity)),
             config,
             cache,
-        })
+            autocomplete: Arc::new(RwLock::new(PrefixIndex::default())),
+            clicks: Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(api::analytics::CLICK_DEDUP_WINDOW)
+                .build(),
+            synonyms,
+            translations,
+            exports: Arc::new(Semaphore::new(api::foods::MAX_CONCURRENT_EXPORTS)),
+        };
+        state.rebuild_autocomplete().await?;
+        api::analytics::spawn_retention_task(state.clone());
+
+        Ok(state)
+    }
+
+    // Yemek verisi değiştiğinde indeksin de yeniden oluşturulması gerekiyor, eski indeks yenisi hazır olana kadar kullanılıyor
+    async fn rebuild_autocomplete(&self) -> Result<(), Error> {
+        let entries = api::database::select_autocomplete_entries(&*self.api_db.lock().await).await?;
+        let index = PrefixIndex::build(entries);
+        debug!("Otomatik tamamlama indeksi {} yemek ile oluşturuldu.", index.len());
+
+        *self
+            .autocomplete
+            .write()
+            .map_err(|_| anyhow::anyhow!("Otomatik tamamlama indeksi kilitlenemedi"))? = index;
+        Ok(())
     }
 }
 
@@ -102,7 +169,7 @@ async fn main() -> Result<(), Error> {
     // Web Uygulamalarda tarayıcıların sorun çıkartmaması için CORS header mekanizmasını da ekliyoruz
     let cors = CorsLayer::new()
         .allow_origin(tower_http::cors::Any)
-        .allow_methods([Method::GET])
+        .allow_methods([Method::GET, Method::POST])
         .allow_headers(tower_http::cors::Any)
         .max_age(std::time::Duration::from_secs(3600));
 
@@ -124,16 +191,32 @@ fn api_router(shared_state: SharedState) -> Router {
         .route("/", get(api::endpoints::endpoints))
         .route("/health", get(api::health::health))
         .route("/food/{slug}", get(api::foods::food))
+        .route("/food/{slug}/substitutes", get(api::foods::food_substitutes))
         .route("/foods", get(api::foods::foods))
         .route("/foods/list", get(api::foods::foods_list))
+        .route("/foods/export.ndjson", get(api::foods::foods_export))
         .route("/foods/search", get(api::foods::foods_search))
+        .route("/foods/search/click", post(api::analytics::search_click))
+        .route("/foods/autocomplete", get(api::foods::foods_autocomplete))
         .route("/tags", get(api::foods::tags_list))
+        .route("/tags/{tag}", get(api::foods::tag))
+        .route("/nutrients", get(api::foods::nutrients_list))
+        .route("/sources", get(api::sources::sources_list))
+        .route("/sources/{id}", get(api::sources::source))
+        .route("/meals/optimize", post(api::meals::meal_optimize))
+        .route("/parse", post(api::parse::parse))
+        .route("/admin/analytics", get(api::analytics::analytics_report))
         .with_state(shared_state.clone())
         .fallback(api::error::APIError::not_found_handler)
         .route_layer(middleware::from_fn_with_state(
             shared_state.clone(),
             |state, request, next| api::cache::cache_middleware(state, request, next),
         ))
+        // Cache'ten dönen yanıtların da sayılması için cache katmanının dışında olmalı
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            api::analytics::analytics_middleware,
+        ))
         .layer(
             tower::ServiceBuilder::new()
                 .layer(ClientIpSource::RightmostXForwardedFor.into_extension()) // Caddy gibi reverse proxy yazılımlarından doğru istemci IP'sini almak için gerekli